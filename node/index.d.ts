@@ -0,0 +1,46 @@
+/* tslint:disable */
+/* eslint-disable */
+
+/* auto-generated by NAPI-RS */
+
+/** Azimuth clockwise from north and altitude of the sun in radians. */
+export interface Position {
+  azimuth: number
+  altitude: number
+}
+/**
+ * Times of the requested phases of a day, `null` if the sun doesn't reach the altitude
+ * of a phase.
+ */
+export interface PhaseTimes {
+  unixtime: number
+  times: Array<number | undefined | null>
+}
+/** Calculates the position of the sun. */
+export function getPosition(unixtime: number, lat: number, lon: number): Position
+/**
+ * Calculates the positions of the sun at many locations at once.
+ *
+ * `coordinates` holds pairs of latitude and longitude in degrees, the result pairs of
+ * azimuth and altitude in radians.
+ */
+export function getPositions(unixtime: number, coordinates: Float64Array): Float64Array
+/**
+ * Calculates the time of a phase, `null` if the sun doesn't reach its altitude on the
+ * day of `unixtime`.
+ *
+ * # Errors
+ *
+ * Fails if the phase is unknown.
+ */
+export function getTimeAtPhase(unixtime: number, phase: string, lat: number, lon: number): number | null
+/** Calculates the time at which the sun crosses the meridian. */
+export function getSolarNoon(unixtime: number, lon: number): number
+/**
+ * Calculates the times of the phases on `n_days` consecutive days.
+ *
+ * # Errors
+ *
+ * Fails if a phase is unknown.
+ */
+export function getTimesForRange(start: number, nDays: number, phases: Array<string>, lat: number, lon: number): Array<PhaseTimes>
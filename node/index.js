@@ -0,0 +1,41 @@
+// Loads the native addon built by `npm run build` for the current platform, e.g.
+// `sun.linux-x64-gnu.node`, or a plain `sun.node` copied from `cargo build`.
+
+const { existsSync } = require("fs");
+const { join } = require("path");
+
+function isMusl() {
+  const { glibcVersionRuntime } = process.report.getReport().header;
+  return !glibcVersionRuntime;
+}
+
+function abi() {
+  switch (process.platform) {
+    case "linux":
+      if (process.arch === "arm") {
+        return "gnueabihf";
+      }
+      return isMusl() ? "musl" : "gnu";
+    case "win32":
+      return "msvc";
+    default:
+      return null;
+  }
+}
+
+function load() {
+  const suffix = abi();
+  const triple = [process.platform, process.arch, suffix].filter(Boolean).join("-");
+  const candidates = [`sun.${triple}.node`, "sun.node"];
+  for (const name of candidates) {
+    const path = join(__dirname, name);
+    if (existsSync(path)) {
+      return require(path);
+    }
+  }
+  throw new Error(
+    `no native binding of @flosse/sun for ${triple}, run \`npm run build\` first`,
+  );
+}
+
+module.exports = load();
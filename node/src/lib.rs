@@ -0,0 +1,139 @@
+//! Native [Node.js](https://nodejs.org) bindings of the position, phase and batch
+//! functions of the `sun` crate, built with [napi-rs](https://napi.rs).
+//!
+//! Times are unix times in milliseconds and phases are named like in suncalc, e.g.
+//! `sunrise` or `nauticalDusk`.
+//!
+//! ```js
+//! const sun = require("@flosse/sun");
+//!
+//! const { azimuth, altitude } = sun.getPosition(Date.now(), 48.0, 9.0);
+//! const sunrise = sun.getTimeAtPhase(Date.now(), "sunrise", 48.0, 9.0);
+//! // lat/lon pairs in, azimuth/altitude pairs out
+//! const positions = sun.getPositions(Date.now(), new Float64Array([48.0, 9.0, 50.5, 30.5]));
+//! ```
+
+// napi converts the arguments from JavaScript into owned values
+#![allow(clippy::needless_pass_by_value)]
+
+use napi::bindgen_prelude::Float64Array;
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+use sun::SunPhase;
+
+/// Parses the name of a phase as used by suncalc.
+fn phase(name: &str) -> Result<SunPhase> {
+    Ok(match name {
+        "sunrise" => SunPhase::Sunrise,
+        "sunset" => SunPhase::Sunset,
+        "sunriseEnd" => SunPhase::SunriseEnd,
+        "sunsetStart" => SunPhase::SunsetStart,
+        "dawn" => SunPhase::Dawn,
+        "dusk" => SunPhase::Dusk,
+        "nauticalDawn" => SunPhase::NauticalDawn,
+        "nauticalDusk" => SunPhase::NauticalDusk,
+        "nightEnd" => SunPhase::NightEnd,
+        "night" => SunPhase::Night,
+        "goldenHourEnd" => SunPhase::GoldenHourEnd,
+        "goldenHour" => SunPhase::GoldenHour,
+        "blueHourEnd" => SunPhase::BlueHourEnd(SunPhase::BLUE_HOUR_DEG),
+        "blueHour" => SunPhase::BlueHourStart(SunPhase::BLUE_HOUR_DEG),
+        _ => {
+            return Err(Error::new(
+                Status::InvalidArg,
+                format!("unknown sun phase `{name}`"),
+            ))
+        }
+    })
+}
+
+/// Azimuth clockwise from north and altitude of the sun in radians.
+#[napi(object)]
+pub struct Position {
+    pub azimuth: f64,
+    pub altitude: f64,
+}
+
+/// Times of the requested phases of a day, `null` if the sun doesn't reach the altitude
+/// of a phase.
+#[napi(object)]
+pub struct PhaseTimes {
+    pub unixtime: i64,
+    pub times: Vec<Option<i64>>,
+}
+
+/// Calculates the position of the sun.
+#[napi]
+#[must_use]
+pub fn get_position(unixtime: i64, lat: f64, lon: f64) -> Position {
+    let position = sun::pos(unixtime, lat, lon);
+    Position {
+        azimuth: position.azimuth,
+        altitude: position.altitude,
+    }
+}
+
+/// Calculates the positions of the sun at many locations at once.
+///
+/// `coordinates` holds pairs of latitude and longitude in degrees, the result pairs of
+/// azimuth and altitude in radians.
+#[napi]
+#[must_use]
+pub fn get_positions(unixtime: i64, coordinates: Float64Array) -> Float64Array {
+    let sites: Vec<(f64, f64)> = coordinates
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    let positions: Vec<f64> = sun::pos_many(unixtime, &sites)
+        .into_iter()
+        .flat_map(|position| [position.azimuth, position.altitude])
+        .collect();
+    Float64Array::new(positions)
+}
+
+/// Calculates the time of a phase, `null` if the sun doesn't reach its altitude on the
+/// day of `unixtime`.
+///
+/// # Errors
+///
+/// Fails if the phase is unknown.
+#[napi]
+pub fn get_time_at_phase(unixtime: i64, phase: String, lat: f64, lon: f64) -> Result<Option<i64>> {
+    let phase = self::phase(&phase)?;
+    Ok(sun::times_for_range(unixtime, 1, &[phase], lat, lon)
+        .first()
+        .and_then(|day| day.times[0]))
+}
+
+/// Calculates the time at which the sun crosses the meridian.
+#[napi]
+#[must_use]
+pub fn get_solar_noon(unixtime: i64, lon: f64) -> i64 {
+    sun::solar_noon(unixtime, lon)
+}
+
+/// Calculates the times of the phases on `n_days` consecutive days.
+///
+/// # Errors
+///
+/// Fails if a phase is unknown.
+#[napi]
+pub fn get_times_for_range(
+    start: i64,
+    n_days: u32,
+    phases: Vec<String>,
+    lat: f64,
+    lon: f64,
+) -> Result<Vec<PhaseTimes>> {
+    let phases = phases
+        .iter()
+        .map(|name| phase(name))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(sun::times_for_range(start, n_days, &phases, lat, lon)
+        .into_iter()
+        .map(|day| PhaseTimes {
+            unixtime: day.unixtime_in_ms,
+            times: day.times,
+        })
+        .collect())
+}
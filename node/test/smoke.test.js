@@ -0,0 +1,22 @@
+// Smoke test of the native bindings, run with `npm test` after `npm run build`.
+
+const assert = require("node:assert");
+const test = require("node:test");
+
+const sun = require("..");
+
+// 2013-03-05 UTC
+const date = 1362441600000;
+
+test("getPosition", () => {
+  const { azimuth, altitude } = sun.getPosition(date, 50.5, 30.5);
+  assert.ok(Math.abs(azimuth - 0.6412750628729547) < 1e-12);
+  assert.ok(Math.abs(altitude + 0.7000406838781611) < 1e-12);
+});
+
+test("getTimeAtPhase", () => {
+  assert.strictEqual(sun.getTimeAtPhase(date, "sunrise", 50.5, 30.5), 1362458096440);
+  // polar night in Longyearbyen on 2013-12-21
+  assert.strictEqual(sun.getTimeAtPhase(1387584000000, "sunrise", 78.2, 15.6), null);
+  assert.throws(() => sun.getTimeAtPhase(date, "noon", 50.5, 30.5), /unknown sun phase/);
+});
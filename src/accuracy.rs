@@ -0,0 +1,203 @@
+//! Choice between results identical to [suncalc](https://github.com/mourner/suncalc)
+//! and improved accuracy.
+//!
+//! The functions at the crate root reproduce suncalc, which is what users migrating
+//! from JavaScript need for their regression tests. The [`Mode::Improved`] mode
+//! corrects the known simplifications of that model:
+//!
+//! * the precession of the perihelion and the change of the obliquity over time,
+//!   which otherwise shift the sun by about 0.2° within a decade of J2000,
+//! * the sidereal time, using the IAU expression of the Greenwich mean sidereal time,
+//! * the rise and set times, refined iteratively against the position instead of
+//!   assuming a constant declination over the day, with the horizon corrected for the
+//!   actual apparent size of the sun.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::accuracy::{pos, Mode};
+//!
+//! let compat = pos(1_362_441_600_000, 48.0, 9.0, Mode::Suncalc);
+//! assert_eq!(compat.altitude, sun::pos(1_362_441_600_000, 48.0, 9.0).altitude);
+//! let improved = pos(1_362_441_600_000, 48.0, 9.0, Mode::Improved);
+//! assert!((improved.altitude - compat.altitude).abs().to_degrees() < 0.5);
+//! ```
+
+use std::f64::consts::PI;
+
+use crate::{
+    altitude, azimuth, equation_of_center, observer_angle, solar_mean_anomaly, sun_distance,
+    to_days, Position, SunPhase, ASTRONOMICAL_UNIT_IN_KM,
+};
+
+const DAYS_PER_CENTURY: f64 = 36_525.0;
+const REFRACTION_AT_HORIZON_DEG: f64 = 34.0 / 60.0;
+const SUN_SEMI_DIAMETER_AT_1_AU_DEG: f64 = 959.63 / 3_600.0;
+const MAX_ITERATIONS: usize = 5;
+const HALF_STEP_MS: i64 = 30_000;
+
+/// Model used for the calculations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Results bit-compatible with suncalc, identical to the functions at the crate root.
+    #[default]
+    Suncalc,
+    /// Improved constants and iterative event refinement.
+    Improved,
+}
+
+/// Altitude and azimuth of the sun with the improved model, and its distance in kilometers.
+fn improved_pos(unixtime_in_ms: i64, lat: f64, lon: f64) -> (Position, f64) {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let centuries = days / DAYS_PER_CENTURY;
+    let mean_anomaly = solar_mean_anomaly(days);
+    let perihelion = (102.937_35 + 1.719_46 * centuries).to_radians();
+    let ecliptic_longitude = mean_anomaly + equation_of_center(mean_anomaly) + perihelion + PI;
+    let obliquity = (23.439_291 - 0.013_004_2 * centuries).to_radians();
+    let right_ascension =
+        (ecliptic_longitude.sin() * obliquity.cos()).atan2(ecliptic_longitude.cos());
+    let declination = (obliquity.sin() * ecliptic_longitude.sin()).asin();
+    let sidereal_time =
+        (280.460_618_37 + 360.985_647_366_29 * days).to_radians() + lon.to_radians();
+    let hour_angle = sidereal_time - right_ascension;
+    let latitude_rad = lat.to_radians();
+    let position = Position {
+        azimuth: azimuth(hour_angle, latitude_rad, declination),
+        altitude: altitude(hour_angle, latitude_rad, declination),
+    };
+    (position, sun_distance(mean_anomaly))
+}
+
+/// Calculates the sun position like [`pos`](crate::pos) with the given model.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `mode`      - model to use.
+#[must_use]
+pub fn pos(unixtime_in_ms: i64, lat: f64, lon: f64, mode: Mode) -> Position {
+    match mode {
+        Mode::Suncalc => crate::pos(unixtime_in_ms, lat, lon),
+        Mode::Improved => improved_pos(unixtime_in_ms, lat, lon).0,
+    }
+}
+
+/// Calculates the time for the given [`SunPhase`] like [`time_at_phase`](crate::time_at_phase)
+/// with the given model.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `sun_phase` - [`SunPhase`] to calcuate time for
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`    - Observer height in meters above the horizon
+/// * `mode`      - model to use.
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::accuracy::{time_at_phase, Mode};
+/// use sun::SunPhase;
+///
+/// let compat = time_at_phase(1_362_441_600_000, SunPhase::Sunrise, 48.0, 9.0, 0.0, Mode::Suncalc);
+/// assert_eq!(compat, 1_362_463_116_241);
+/// let improved = time_at_phase(1_362_441_600_000, SunPhase::Sunrise, 48.0, 9.0, 0.0, Mode::Improved);
+/// assert!((improved - compat).abs() < 2 * 60 * 1_000);
+/// ```
+#[must_use]
+pub fn time_at_phase(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+    mode: Mode,
+) -> i64 {
+    let estimate = crate::time_at_phase(unixtime_in_ms, sun_phase, lat, lon, height);
+    if mode == Mode::Suncalc
+        || crate::julian_at_phase(unixtime_in_ms, sun_phase, lat, lon, height).is_nan()
+    {
+        return estimate;
+    }
+
+    let target = |distance: f64| {
+        let angle = match sun_phase {
+            SunPhase::Sunrise | SunPhase::Sunset => {
+                let semi_diameter =
+                    SUN_SEMI_DIAMETER_AT_1_AU_DEG * ASTRONOMICAL_UNIT_IN_KM / distance;
+                -(REFRACTION_AT_HORIZON_DEG + semi_diameter)
+            }
+            _ => sun_phase.angle_deg(),
+        };
+        (angle + observer_angle(height)).to_radians()
+    };
+    let mut time = estimate;
+    for _ in 0..MAX_ITERATIONS {
+        let (position, distance) = improved_pos(time, lat, lon);
+        let before = improved_pos(time - HALF_STEP_MS, lat, lon).0.altitude;
+        let after = improved_pos(time + HALF_STEP_MS, lat, lon).0.altitude;
+        #[allow(clippy::cast_precision_loss)]
+        let rate = (after - before) / (2 * HALF_STEP_MS) as f64;
+        if rate == 0.0 {
+            break;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let step = ((position.altitude - target(distance)) / rate).round() as i64;
+        time -= step;
+        if step.abs() < 1 {
+            break;
+        }
+    }
+    time
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_suncalc_mode_is_identical() {
+        let date = 1_362_441_600_000;
+        for i in 0..8 {
+            let time = date + i * 3 * 60 * 60 * 1_000;
+            let compat = pos(time, 50.5, 30.5, Mode::Suncalc);
+            let root = crate::pos(time, 50.5, 30.5);
+            assert_eq!(compat.azimuth, root.azimuth);
+            assert_eq!(compat.altitude, root.altitude);
+        }
+        assert_eq!(
+            time_at_phase(date, SunPhase::Dusk, 50.5, 30.5, 0.0, Mode::Suncalc),
+            crate::time_at_phase(date, SunPhase::Dusk, 50.5, 30.5, 0.0)
+        );
+        assert_eq!(Mode::default(), Mode::Suncalc);
+    }
+
+    #[test]
+    fn test_improved_events_hit_the_target_altitude() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let dusk = time_at_phase(date, SunPhase::Dusk, 50.5, 30.5, 0.0, Mode::Improved);
+        let altitude = pos(dusk, 50.5, 30.5, Mode::Improved).altitude.to_degrees();
+        assert!((altitude + 6.0).abs() < 1e-3);
+        // polar night stays undefined
+        let winter = 1_387_584_000_000;
+        assert_eq!(
+            time_at_phase(winter, SunPhase::Sunrise, 69.65, 18.96, 0.0, Mode::Improved),
+            crate::time_at_phase(winter, SunPhase::Sunrise, 69.65, 18.96, 0.0)
+        );
+    }
+
+    #[cfg(feature = "reference")]
+    #[test]
+    fn test_improved_mode_agrees_with_reference() {
+        let start = 1_362_441_600_000;
+        for i in 0..16 {
+            let time = start + i * 3 * 60 * 60 * 1_000;
+            let improved = pos(time, 50.5, 30.5, Mode::Improved);
+            let reference = crate::reference::reference_pos(time, 50.5, 30.5);
+            assert!((improved.altitude - reference.altitude).to_degrees().abs() < 0.02);
+        }
+    }
+}
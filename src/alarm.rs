@@ -0,0 +1,211 @@
+//! The next sun event for programming a wake-up alarm, e.g. of a real-time clock in
+//! firmware that sleeps between events.
+//!
+//! The search doesn't allocate and costs two to four evaluations of
+//! [`time_at_phase`](crate::time_at_phase) per phase. It is available without the `std`
+//! feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::alarm::next_event_after;
+//! use sun::SunPhase;
+//!
+//! let now = 1_362_441_600_000;
+//! if let Some((phase, ms_until)) =
+//!     next_event_after(now, &[SunPhase::Sunrise, SunPhase::Sunset], 48.0, 9.0)
+//! {
+//!     println!("wake up in {} s for {:?}", ms_until / 1_000, phase);
+//! }
+//! ```
+//!
+//! For wake-up lights, `dawn_ramp` produces a brightness ramp that ends at the event.
+
+#[cfg(feature = "std")]
+use crate::animation::Easing;
+use crate::{next_phase_within, SunPhase};
+
+/// Days searched for the next event.
+const MAX_DAYS: i64 = 2;
+
+/// Finds the earliest of the given phases after `now_in_ms`.
+///
+/// Returns the phase and the milliseconds until it occurs, or `None` if none of the
+/// phases occurs within the next two days, e.g. sunrise in polar night.
+///
+/// * `now_in_ms` - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `phases`    - [`SunPhase`]s to wait for.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub fn next_event_after(
+    now_in_ms: i64,
+    phases: &[SunPhase],
+    lat: f64,
+    lon: f64,
+) -> Option<(SunPhase, i64)> {
+    phases
+        .iter()
+        .filter_map(|&phase| {
+            next_phase_within(now_in_ms, phase, lat, lon, 0.0, MAX_DAYS)
+                .map(|time| (phase, time - now_in_ms))
+        })
+        .min_by_key(|&(_, ms_until)| ms_until)
+}
+
+/// A point of a brightness ramp, see [`dawn_ramp`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RampPoint {
+    /// [Unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Brightness between 0 and 1.
+    pub intensity: f64,
+}
+
+/// Builds a brightness ramp from 0 to 1 over `duration_ms` milliseconds that ends at the
+/// next occurrence of `phase` after `now_in_ms`, e.g. [`SunPhase::Sunrise`] or
+/// `SunPhase::Custom(-3.0, true)` for a custom depression of the sun.
+///
+/// The ramp has `steps + 1` points spaced evenly in time, with the intensity following
+/// `easing`. If the ramp has already begun at `now_in_ms`, it starts with a point at
+/// `now_in_ms` at the current intensity instead of the points in the past.
+///
+/// Returns `None` if the phase doesn't occur within the next two days.
+///
+/// * `now_in_ms` - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Panics
+///
+/// Panics if `duration_ms` is not positive or `steps` is zero.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn dawn_ramp(
+    now_in_ms: i64,
+    phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    duration_ms: i64,
+    steps: u32,
+    easing: Easing,
+) -> Option<Vec<RampPoint>> {
+    assert!(duration_ms > 0, "the duration must be positive");
+    assert!(steps > 0, "the ramp needs at least one step");
+    let end = next_phase_within(now_in_ms, phase, lat, lon, 0.0, MAX_DAYS)?;
+    let start = end - duration_ms;
+    #[allow(clippy::cast_precision_loss)]
+    let point = |time: i64| RampPoint {
+        unixtime_in_ms: time,
+        intensity: easing.apply((time - start) as f64 / duration_ms as f64),
+    };
+    let mut ramp: Vec<RampPoint> = (0..=i64::from(steps))
+        .map(|step| start + duration_ms * step / i64::from(steps))
+        .filter(|&time| time >= now_in_ms)
+        .map(point)
+        .collect();
+    if now_in_ms > start
+        && ramp
+            .first()
+            .is_none_or(|first| first.unixtime_in_ms > now_in_ms)
+    {
+        ramp.insert(0, point(now_in_ms));
+    }
+    Some(ramp)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_next_event_after() {
+        // 2013-03-05 00:00 UTC
+        let now = 1_362_441_600_000;
+        let sunrise = crate::time_at_phase(
+            now + 12 * 60 * 60 * 1_000,
+            SunPhase::Sunrise,
+            50.5,
+            30.5,
+            0.0,
+        );
+        assert_eq!(
+            next_event_after(now, &[SunPhase::Sunset, SunPhase::Sunrise], 50.5, 30.5),
+            Some((SunPhase::Sunrise, sunrise - now))
+        );
+        // right at sunrise the next event is the sunset
+        let (phase, ms_until) =
+            next_event_after(sunrise, &[SunPhase::Sunset, SunPhase::Sunrise], 50.5, 30.5).unwrap();
+        assert_eq!(phase, SunPhase::Sunset);
+        assert!(ms_until > 10 * 60 * 60 * 1_000);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_dawn_ramp() {
+        // 2013-03-05 00:00 UTC
+        let now = 1_362_441_600_000;
+        let half_hour = 30 * 60 * 1_000;
+        let ramp = dawn_ramp(
+            now,
+            SunPhase::Sunrise,
+            50.5,
+            30.5,
+            half_hour,
+            30,
+            Easing::EaseIn,
+        )
+        .unwrap();
+        let (_, ms_until) = next_event_after(now, &[SunPhase::Sunrise], 50.5, 30.5).unwrap();
+        assert_eq!(ramp.len(), 31);
+        assert_eq!(ramp[30].unixtime_in_ms, now + ms_until);
+        assert_eq!(ramp[0].unixtime_in_ms, now + ms_until - half_hour);
+        assert!(ramp[0].intensity.abs() < 1e-9 && (ramp[30].intensity - 1.0).abs() < 1e-9);
+        assert!((ramp[15].intensity - 0.25).abs() < 1e-9);
+
+        // ten minutes before sunrise, the ramp continues at the current intensity
+        let late = now + ms_until - 10 * 60 * 1_000;
+        let ramp = dawn_ramp(
+            late,
+            SunPhase::Sunrise,
+            50.5,
+            30.5,
+            half_hour,
+            30,
+            Easing::Linear,
+        )
+        .unwrap();
+        assert_eq!(ramp[0].unixtime_in_ms, late);
+        assert!((ramp[0].intensity - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(ramp.len(), 11);
+        assert_eq!(
+            dawn_ramp(
+                1_387_627_200_000,
+                SunPhase::Sunrise,
+                78.2,
+                15.6,
+                half_hour,
+                30,
+                Easing::Linear
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_polar_night() {
+        let now = 1_387_627_200_000;
+        assert_eq!(
+            next_event_after(now, &[SunPhase::Sunrise], 78.2, 15.6),
+            None
+        );
+        assert_eq!(next_event_after(now, &[], 78.2, 15.6), None);
+        assert_eq!(
+            next_event_after(now, &[SunPhase::Sunrise, SunPhase::NightEnd], 78.2, 15.6)
+                .map(|(phase, _)| phase),
+            Some(SunPhase::NightEnd)
+        );
+    }
+}
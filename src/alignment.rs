@@ -0,0 +1,132 @@
+//! Days on which the rising or setting sun lines up with a street, like
+//! [Manhattanhenge](https://en.wikipedia.org/wiki/Manhattanhenge) in New York.
+//!
+//! A street points in two opposite directions, so both are compared with the azimuth
+//! of the sun at the event.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::alignment::street_alignments;
+//! use sun::SunPhase;
+//!
+//! // the streets of Manhattan run at 29° from east-west
+//! let days = street_alignments(2024, 299.0, 40.758, -73.985, SunPhase::Sunset, 0.3);
+//! for day in days {
+//!     println!("the sun sets along the street at {}", day.unixtime_in_ms);
+//! }
+//! ```
+
+use crate::calendar::days_from_civil;
+use crate::report::event;
+use crate::{local_noon, pos, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+/// An event of the sun close to the direction of a street, see [`street_alignments`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Alignment {
+    /// [Unix time](https://en.wikipedia.org/wiki/Unix_time) of the event in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Azimuth of the sun at the event in degrees clockwise from north.
+    pub azimuth_deg: f64,
+    /// Angle between the sun and the street in degrees.
+    pub deviation_deg: f64,
+}
+
+/// Angle in degrees between an azimuth and the closer direction of a street.
+fn deviation(azimuth_deg: f64, bearing_deg: f64) -> f64 {
+    let difference = (azimuth_deg - bearing_deg).rem_euclid(180.0);
+    difference.min(180.0 - difference)
+}
+
+/// Finds the days of `year` on which the sun is within `tolerance_deg` of the direction
+/// of a street at the given phase, e.g. [`SunPhase::Sunset`] for the upper limb touching
+/// the horizon or a custom phase for the whole disc above it.
+///
+/// * `year`         - year of the search.
+/// * `bearing_deg`  - direction of the street in degrees clockwise from north.
+/// * `lat`          - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`          - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `phase`        - event of the sun.
+/// * `tolerance_deg` - largest angle between the sun and the street in degrees.
+///
+/// The days are returned in order. The azimuth of the sun at rise and set changes slowly
+/// around the solstices, so an alignment usually spans several consecutive days with the
+/// best one in the middle.
+#[must_use]
+pub fn street_alignments(
+    year: i32,
+    bearing_deg: f64,
+    lat: f64,
+    lon: f64,
+    phase: SunPhase,
+    tolerance_deg: f64,
+) -> Vec<Alignment> {
+    (days_from_civil(year, 1, 1)..days_from_civil(year + 1, 1, 1))
+        .filter_map(|day| {
+            let unixtime_in_ms = event(
+                local_noon(day * MILLISECONDS_PER_DAY_INT, lon),
+                phase,
+                lat,
+                lon,
+            )?;
+            let azimuth_deg = pos(unixtime_in_ms, lat, lon).azimuth.to_degrees();
+            let deviation_deg = deviation(azimuth_deg, bearing_deg);
+            (deviation_deg <= tolerance_deg).then_some(Alignment {
+                unixtime_in_ms,
+                azimuth_deg,
+                deviation_deg,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::calendar::civil_from_days;
+
+    fn date(alignment: &Alignment) -> (u32, u32) {
+        let (_, month, day) = civil_from_days(
+            alignment
+                .unixtime_in_ms
+                .div_euclid(MILLISECONDS_PER_DAY_INT),
+        );
+        (month, day)
+    }
+
+    #[test]
+    fn test_deviation() {
+        assert!((deviation(299.0, 119.0)).abs() < 1e-9);
+        assert!((deviation(301.0, 119.0) - 2.0).abs() < 1e-9);
+        assert!((deviation(1.0, 359.0) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_manhattanhenge() {
+        let sunsets = street_alignments(2024, 299.0, 40.758, -73.985, SunPhase::Sunset, 0.3);
+        assert!(!sunsets.is_empty());
+        // in late May and in mid July, a few days before the published dates, which refer
+        // to the sun above the skyline of New Jersey
+        let best = |month| {
+            sunsets
+                .iter()
+                .filter(|alignment| date(alignment).0 == month)
+                .min_by(|a, b| a.deviation_deg.total_cmp(&b.deviation_deg))
+                .map(date)
+        };
+        let may = best(5).unwrap();
+        assert!((22..=28).contains(&may.1), "{may:?}");
+        let july = best(7).unwrap();
+        assert!((15..=21).contains(&july.1), "{july:?}");
+        assert!(sunsets
+            .iter()
+            .all(|alignment| matches!(date(alignment).0, 5 | 7)));
+        // the sun rises along the streets in winter
+        let sunrises = street_alignments(2024, 299.0, 40.758, -73.985, SunPhase::Sunrise, 0.3);
+        assert!(!sunrises.is_empty());
+        assert!(sunrises
+            .iter()
+            .all(|alignment| matches!(date(alignment).0, 12 | 1)));
+    }
+}
@@ -0,0 +1,212 @@
+//! A precomputed year of sun events for devices without floating point unit or with a
+//! tight time budget.
+//!
+//! A [`YearAlmanac`] is generated once, e.g. on a server, serialized with the `serde`
+//! feature into a compact format like [postcard](https://docs.rs/postcard) and shipped
+//! to the device, where the lookups use integer arithmetic only. Without the `std`
+//! feature the lookups are available with the `alloc` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[cfg(feature = "std")] {
+//! use sun::almanac::YearAlmanac;
+//!
+//! let almanac = YearAlmanac::new(2013, 48.0, 9.0);
+//! let now = 1_362_484_800_000;
+//! if almanac.is_daylight(now) == Some(true) {
+//!     let altitude = almanac.altitude_centidegrees(now).unwrap();
+//!     println!("the sun stands {}.{:02}° high", altitude / 100, altitude % 100);
+//! }
+//! # }
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::calendar::days_from_civil;
+#[cfg(feature = "std")]
+use crate::report::event;
+use crate::MILLISECONDS_PER_DAY_INT;
+#[cfg(feature = "std")]
+use crate::{local_noon, pos, solar_noon, SunPhase};
+
+const MILLISECONDS_PER_HOUR: i64 = 60 * 60 * 1_000;
+/// Altitude of the sun at sunrise in hundredths of a degree.
+const SUNRISE_CENTIDEGREES: i32 = -83;
+
+/// Sun events of a UTC day.
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds,
+/// `None` if the sun doesn't reach the altitude of the event on that day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlmanacDay {
+    /// Civil dawn.
+    pub dawn: Option<i64>,
+    /// Sunrise.
+    pub sunrise: Option<i64>,
+    /// Transit of the sun through the meridian.
+    pub solar_noon: i64,
+    /// Sunset.
+    pub sunset: Option<i64>,
+    /// Civil dusk.
+    pub dusk: Option<i64>,
+}
+
+/// Sun events and altitudes of a calendar year at one location.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct YearAlmanac {
+    /// The year.
+    pub year: i32,
+    /// [Latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    pub lat: f64,
+    /// [Longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    pub lon: f64,
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of January 1st, 00:00 UTC in milliseconds.
+    pub start_in_ms: i64,
+    /// Events of each day of the year.
+    pub days: Vec<AlmanacDay>,
+    /// Altitude of the sun in hundredths of a degree at each full hour of the year and
+    /// at 00:00 UTC of the following January 1st.
+    pub hourly_altitudes: Vec<i16>,
+}
+
+impl YearAlmanac {
+    /// Computes the almanac of a year.
+    ///
+    /// * `year`  - the year in the Gregorian calendar.
+    /// * `lat`   - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`   - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn new(year: i32, lat: f64, lon: f64) -> Self {
+        let start_in_ms = days_from_civil(year, 1, 1) * MILLISECONDS_PER_DAY_INT;
+        let end_in_ms = days_from_civil(year + 1, 1, 1) * MILLISECONDS_PER_DAY_INT;
+        let days = (start_in_ms..end_in_ms)
+            .step_by(usize::try_from(MILLISECONDS_PER_DAY_INT).unwrap_or(usize::MAX))
+            .map(|day_start| {
+                let noon = local_noon(day_start, lon);
+                AlmanacDay {
+                    dawn: event(noon, SunPhase::Dawn, lat, lon),
+                    sunrise: event(noon, SunPhase::Sunrise, lat, lon),
+                    solar_noon: solar_noon(noon, lon),
+                    sunset: event(noon, SunPhase::Sunset, lat, lon),
+                    dusk: event(noon, SunPhase::Dusk, lat, lon),
+                }
+            })
+            .collect();
+        let hourly_altitudes = (start_in_ms..=end_in_ms)
+            .step_by(usize::try_from(MILLISECONDS_PER_HOUR).unwrap_or(usize::MAX))
+            .map(|time| {
+                #[allow(clippy::cast_possible_truncation)]
+                let centidegrees =
+                    (pos(time, lat, lon).altitude.to_degrees() * 100.0).round() as i16;
+                centidegrees
+            })
+            .collect();
+        Self {
+            year,
+            lat,
+            lon,
+            start_in_ms,
+            days,
+            hourly_altitudes,
+        }
+    }
+
+    /// Returns the events of the UTC day of `unixtime_in_ms`, `None` outside of the year.
+    #[must_use]
+    pub fn day(&self, unixtime_in_ms: i64) -> Option<&AlmanacDay> {
+        let index = (unixtime_in_ms - self.start_in_ms).div_euclid(MILLISECONDS_PER_DAY_INT);
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.days.get(index))
+    }
+
+    /// Interpolates the altitude of the sun in hundredths of a degree linearly between
+    /// the full hours, `None` outside of the year.
+    ///
+    /// The interpolation deviates by up to about 0.5° from [`pos`](crate::pos) around noon.
+    #[must_use]
+    pub fn altitude_centidegrees(&self, unixtime_in_ms: i64) -> Option<i32> {
+        let elapsed = unixtime_in_ms - self.start_in_ms;
+        let index = usize::try_from(elapsed.div_euclid(MILLISECONDS_PER_HOUR)).ok()?;
+        let before = i64::from(*self.hourly_altitudes.get(index)?);
+        let after = self
+            .hourly_altitudes
+            .get(index + 1)
+            .map_or(before, |&a| i64::from(a));
+        let fraction = elapsed.rem_euclid(MILLISECONDS_PER_HOUR);
+        let altitude = before + (after - before) * fraction / MILLISECONDS_PER_HOUR;
+        i32::try_from(altitude).ok()
+    }
+
+    /// Whether the sun is above the horizon between sunrise and sunset, `None` outside
+    /// of the year.
+    ///
+    /// On days without sunrise or sunset the interpolated altitude decides.
+    #[must_use]
+    pub fn is_daylight(&self, unixtime_in_ms: i64) -> Option<bool> {
+        let day = self.day(unixtime_in_ms)?;
+        match (day.sunrise, day.sunset) {
+            (Some(sunrise), Some(sunset)) => Some((sunrise..sunset).contains(&unixtime_in_ms)),
+            _ => self
+                .altitude_centidegrees(unixtime_in_ms)
+                .map(|altitude| altitude > SUNRISE_CENTIDEGREES),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_year_almanac() {
+        let almanac = YearAlmanac::new(2013, 50.5, 30.5);
+        assert_eq!(almanac.days.len(), 365);
+        assert_eq!(almanac.hourly_altitudes.len(), 365 * 24 + 1);
+        assert_eq!(almanac.start_in_ms, 1_356_998_400_000);
+        // 2013-03-05
+        let day = almanac.day(1_362_484_800_000).unwrap();
+        assert_eq!(
+            day.sunrise,
+            Some(crate::time_at_phase(
+                1_362_484_800_000,
+                SunPhase::Sunrise,
+                50.5,
+                30.5,
+                0.0
+            ))
+        );
+        assert!(almanac.day(1_356_998_400_000 - 1).is_none());
+        assert!(almanac.day(1_388_534_400_000).is_none());
+        for minutes in (0..24 * 60).step_by(7) {
+            let time = 1_362_441_600_000 + minutes * 60_000;
+            let altitude = f64::from(almanac.altitude_centidegrees(time).unwrap()) / 100.0;
+            assert!((altitude - pos(time, 50.5, 30.5).altitude.to_degrees()).abs() < 0.5);
+        }
+        assert_eq!(almanac.is_daylight(1_362_484_800_000), Some(true));
+        assert_eq!(almanac.is_daylight(1_362_441_600_000), Some(false));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialization() {
+        let almanac = YearAlmanac::new(2024, 78.2, 15.6);
+        assert_eq!(almanac.days.len(), 366);
+        let bytes = postcard::to_allocvec(&almanac).unwrap();
+        assert!(bytes.len() < 64 * 1024);
+        let restored: YearAlmanac = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(restored, almanac);
+        // polar night in Longyearbyen
+        assert_eq!(restored.is_daylight(1_734_782_400_000), Some(false));
+    }
+}
@@ -0,0 +1,132 @@
+//! Helpers for sampling the sun's path, e.g. to animate it in a UI.
+
+use std::f64::consts::PI;
+
+use crate::{pos, unwrap_azimuth, Position};
+
+/// Distribution of the sample times between the start and the end of an animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// Evenly spaced samples.
+    #[default]
+    Linear,
+    /// Samples are denser at the start.
+    EaseIn,
+    /// Samples are denser at the end.
+    EaseOut,
+    /// Samples are denser at both ends.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps a linear progress `t` in `[0, 1]` onto the eased progress.
+    #[must_use]
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => (1.0 - (PI * t).cos()) / 2.0,
+        }
+    }
+}
+
+/// A sun position at a point in time.
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    pub unixtime_in_ms: i64,
+    pub position: Position,
+}
+
+/// Samples `n` sun positions between `start_in_ms` and `end_in_ms` (both inclusive).
+///
+/// The azimuth of the returned positions is unwrapped, i.e. it does not jump at the
+/// 0/2π boundary but continues below zero or beyond 2π, so consecutive samples
+/// can be interpolated directly.
+///
+/// # Arguments
+///
+/// * `start_in_ms` - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the first sample in milliseconds.
+/// * `end_in_ms`   - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the last sample in milliseconds.
+/// * `n`           - Number of samples.
+/// * `lat`         - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`         - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `easing`      - Distribution of the samples over time.
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::animation::{sample_positions, Easing};
+///
+/// let start = 1_362_441_600_000;
+/// let end = start + 24 * 60 * 60 * 1_000;
+/// let samples = sample_positions(start, end, 25, 48.0, 9.0, Easing::Linear);
+/// assert_eq!(samples.len(), 25);
+/// assert_eq!(samples[24].unixtime_in_ms, end);
+/// ```
+#[must_use]
+pub fn sample_positions(
+    start_in_ms: i64,
+    end_in_ms: i64,
+    n: usize,
+    lat: f64,
+    lon: f64,
+    easing: Easing,
+) -> Vec<Sample> {
+    let mut samples: Vec<Sample> = Vec::with_capacity(n);
+    #[allow(clippy::cast_precision_loss)]
+    let duration = (end_in_ms - start_in_ms) as f64;
+    for i in 0..n {
+        #[allow(clippy::cast_precision_loss)]
+        let t = if n > 1 {
+            i as f64 / (n - 1) as f64
+        } else {
+            0.0
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        let unixtime_in_ms = start_in_ms + (easing.apply(t) * duration).round() as i64;
+        let mut position = pos(unixtime_in_ms, lat, lon);
+        if let Some(previous) = samples.last() {
+            position.azimuth = unwrap_azimuth(previous.position.azimuth, position.azimuth);
+        }
+        samples.push(Sample {
+            unixtime_in_ms,
+            position,
+        });
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_easing_bounds() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-12);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_sample_positions_unwraps_azimuth() {
+        // 2013-03-05 UTC, a full day crosses north at midnight
+        let start = 1_362_441_600_000;
+        let end = start + 24 * 60 * 60 * 1_000;
+        let samples = sample_positions(start, end, 97, 48.0, 9.0, Easing::Linear);
+        assert_eq!(samples.len(), 97);
+        assert_eq!(samples[0].unixtime_in_ms, start);
+        for pair in samples.windows(2) {
+            let delta = pair[1].position.azimuth - pair[0].position.azimuth;
+            assert!(delta.abs() < PI / 4.0);
+        }
+    }
+}
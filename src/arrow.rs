@@ -0,0 +1,182 @@
+//! Results of the batch functions as [Apache Arrow](https://arrow.apache.org/) record
+//! batches, with one column per quantity.
+//!
+//! The values are written directly into the Arrow arrays. The batches can be handed to
+//! Polars or written to Parquet without copying them element by element.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::arrow::times_for_range_batch;
+//! use sun::SunPhase;
+//!
+//! let batch = times_for_range_batch(
+//!     1_356_998_400_000,
+//!     365,
+//!     &[SunPhase::Sunrise, SunPhase::Sunset],
+//!     48.0,
+//!     9.0,
+//! )
+//! .unwrap();
+//! assert_eq!(batch.num_rows(), 365);
+//! assert!(batch.column_by_name("sunrise").is_some());
+//! ```
+
+use std::sync::Arc;
+
+use arrow_array::builder::{Float64Builder, TimestampMillisecondBuilder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+
+use crate::{for_each_pos, SunPhase, Transit, MILLISECONDS_PER_DAY_INT};
+
+const TIME_ZONE: &str = "UTC";
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some(TIME_ZONE.into()))
+}
+
+fn timestamp_builder(capacity: usize) -> TimestampMillisecondBuilder {
+    TimestampMillisecondBuilder::with_capacity(capacity).with_timezone(TIME_ZONE)
+}
+
+/// Name of the column of a phase, e.g. `sunrise` or `custom_-3_rise` for
+/// `SunPhase::Custom(-3.0, true)`.
+#[must_use]
+pub fn column_name(phase: SunPhase) -> String {
+    match phase {
+        SunPhase::Custom(angle, rise) => {
+            format!("custom_{angle}_{}", if rise { "rise" } else { "set" })
+        }
+        _ => phase.name().to_string(),
+    }
+}
+
+/// Calculates the positions of [`pos_many`](crate::pos_many) as a record batch with the
+/// columns `lat` and `lon` in degrees and `azimuth` and `altitude` in radians.
+///
+/// # Errors
+///
+/// Returns the error of Arrow if the batch can't be assembled, which doesn't happen
+/// for valid inputs.
+pub fn pos_many_batch(
+    unixtime_in_ms: i64,
+    sites: &[(f64, f64)],
+) -> Result<RecordBatch, ArrowError> {
+    let mut lat = Float64Builder::with_capacity(sites.len());
+    let mut lon = Float64Builder::with_capacity(sites.len());
+    for &(site_lat, site_lon) in sites {
+        lat.append_value(site_lat);
+        lon.append_value(site_lon);
+    }
+    let mut azimuth = Float64Builder::with_capacity(sites.len());
+    let mut altitude = Float64Builder::with_capacity(sites.len());
+    for_each_pos(unixtime_in_ms, sites, |position| {
+        azimuth.append_value(position.azimuth);
+        altitude.append_value(position.altitude);
+    });
+    let schema = Schema::new(
+        ["lat", "lon", "azimuth", "altitude"]
+            .into_iter()
+            .map(|name| Field::new(name, DataType::Float64, false))
+            .collect::<Vec<_>>(),
+    );
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(lat.finish()),
+        Arc::new(lon.finish()),
+        Arc::new(azimuth.finish()),
+        Arc::new(altitude.finish()),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+/// Calculates the times of [`times_for_range`](crate::times_for_range) as a record batch
+/// with the column `date` followed by one column per phase named by [`column_name`].
+///
+/// All columns are UTC timestamps in milliseconds; the times of a phase are null on days
+/// on which the sun doesn't reach its altitude.
+///
+/// # Errors
+///
+/// Returns the error of Arrow if the batch can't be assembled, which doesn't happen
+/// for valid inputs.
+pub fn times_for_range_batch(
+    start_in_ms: i64,
+    n_days: u32,
+    phases: &[SunPhase],
+    lat: f64,
+    lon: f64,
+) -> Result<RecordBatch, ArrowError> {
+    let capacity = usize::try_from(n_days).unwrap_or(usize::MAX);
+    let mut dates = timestamp_builder(capacity);
+    let mut times: Vec<TimestampMillisecondBuilder> =
+        phases.iter().map(|_| timestamp_builder(capacity)).collect();
+    for day in 0..i64::from(n_days) {
+        let unixtime_in_ms = start_in_ms + day * MILLISECONDS_PER_DAY_INT;
+        dates.append_value(unixtime_in_ms);
+        let transit = Transit::new(unixtime_in_ms, lon);
+        for (&phase, column) in phases.iter().zip(&mut times) {
+            column.append_option(transit.phase_outcome(phase, lat, 0.0).time());
+        }
+    }
+    let mut fields = vec![Field::new("date", timestamp_type(), false)];
+    fields.extend(
+        phases
+            .iter()
+            .map(|&phase| Field::new(column_name(phase), timestamp_type(), true)),
+    );
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(dates.finish())];
+    columns.extend(
+        times
+            .iter_mut()
+            .map(|column| Arc::new(column.finish()) as ArrayRef),
+    );
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, TimestampMillisecondType};
+    use arrow_array::Array;
+
+    use super::*;
+
+    #[test]
+    fn test_pos_many_batch() {
+        let sites = [(50.5, 30.5), (-33.9, 18.4), (78.2, 15.6)];
+        let batch = pos_many_batch(1_362_441_600_000, &sites).unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.num_columns(), 4);
+        let altitude = batch
+            .column_by_name("altitude")
+            .unwrap()
+            .as_primitive::<Float64Type>();
+        let lon = batch.column(1).as_primitive::<Float64Type>();
+        assert_eq!(lon.value(1), 18.4);
+        assert_eq!(
+            altitude.value(2),
+            crate::pos(1_362_441_600_000, 78.2, 15.6).altitude
+        );
+    }
+
+    #[test]
+    fn test_times_for_range_batch() {
+        let phases = [SunPhase::Sunrise, SunPhase::Custom(-3.0, false)];
+        let batch = times_for_range_batch(1_387_584_000_000, 10, &phases, 69.65, 18.96).unwrap();
+        assert_eq!(batch.num_rows(), 10);
+        assert_eq!(batch.schema().field(2).name(), "custom_-3_set");
+        let expected = crate::times_for_range(1_387_584_000_000, 10, &phases, 69.65, 18.96);
+        let sunrise = batch.column(1).as_primitive::<TimestampMillisecondType>();
+        for (row, day) in expected.iter().enumerate() {
+            assert_eq!(sunrise.is_valid(row), day.times[0].is_some());
+            if let Some(time) = day.times[0] {
+                assert_eq!(sunrise.value(row), time);
+            }
+        }
+        // polar night in Tromsø
+        assert!(sunrise.null_count() > 0);
+    }
+}
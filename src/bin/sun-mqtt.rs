@@ -0,0 +1,71 @@
+//! Publishes the sun position and upcoming events to an MQTT broker.
+//!
+//! ```text
+//! sun-mqtt --lat 48.0 --lon 9.0 [--host localhost] [--port 1883] [--topic sun/{key}]
+//!          [--interval 60] [--client-id sun] [--no-retain]
+//! ```
+
+use std::env;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use sun::mqtt::{Publisher, TopicSchema};
+
+const USAGE: &str = "usage: sun-mqtt --lat <deg> --lon <deg> [--host <host>] [--port <port>] \
+                     [--topic <template>] [--interval <seconds>] [--client-id <id>] [--no-retain]";
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Publisher, String> {
+    let mut args = args.peekable();
+    let mut lat = None;
+    let mut lon = None;
+    let mut publisher = Publisher::new(0.0, 0.0);
+    while let Some(flag) = args.next() {
+        if flag == "--no-retain" {
+            publisher.retain = false;
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {flag}"))?;
+        let number = |value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number for {flag}: {value}"))
+        };
+        match flag.as_str() {
+            "--lat" => lat = Some(number(&value)?),
+            "--lon" => lon = Some(number(&value)?),
+            "--host" => publisher.host = value,
+            "--port" => {
+                publisher.port = value
+                    .parse()
+                    .map_err(|_| format!("invalid port: {value}"))?;
+            }
+            "--topic" => publisher.schema = TopicSchema::new(&value),
+            "--interval" => {
+                publisher.interval = Duration::try_from_secs_f64(number(&value)?)
+                    .map_err(|_| format!("invalid interval: {value}"))?;
+            }
+            "--client-id" => publisher.client_id = value,
+            _ => return Err(format!("unknown argument: {flag}")),
+        }
+    }
+    publisher.lat = lat.ok_or("missing --lat")?;
+    publisher.lon = lon.ok_or("missing --lon")?;
+    Ok(publisher)
+}
+
+fn main() -> ExitCode {
+    let publisher = match parse_args(env::args().skip(1)) {
+        Ok(publisher) => publisher,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(err) = publisher.run() {
+        eprintln!("{err}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
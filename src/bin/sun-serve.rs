@@ -0,0 +1,50 @@
+//! Serves the sun position, times and events as JSON over HTTP.
+//!
+//! ```text
+//! sun-serve [--addr 127.0.0.1:3000]
+//! ```
+
+use std::env;
+use std::process::ExitCode;
+
+use tokio::net::TcpListener;
+
+const USAGE: &str = "usage: sun-serve [--addr <host:port>]";
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<String, String> {
+    let mut args = args.peekable();
+    let mut addr = "127.0.0.1:3000".to_string();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {flag}"))?;
+        match flag.as_str() {
+            "--addr" => addr = value,
+            _ => return Err(format!("unknown argument: {flag}")),
+        }
+    }
+    Ok(addr)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let addr = match parse_args(env::args().skip(1)) {
+        Ok(addr) => addr,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("{addr}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(err) = axum::serve(listener, sun::serve::router()).await {
+        eprintln!("{err}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
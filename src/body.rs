@@ -0,0 +1,240 @@
+//! Sun position and solar time on other planets than the earth.
+//!
+//! A [`Body`] describes the planet of the observer by its [`OrbitalElements`] and its
+//! rotation. [`Body::EARTH`] is the model behind [`pos`](crate::pos); [`Body::MARS`]
+//! follows the [algorithm](https://www.giss.nasa.gov/tools/mars24/help/algorithm.html)
+//! of NASA's Mars24 sunclock.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::body::{mars_sol_date, pos_on, Body};
+//!
+//! // the sun over Gale crater, where the Curiosity rover drives
+//! let now = 1_700_000_000_000;
+//! let sun = pos_on(&Body::MARS, now, -4.59, 137.44);
+//! println!("sol {:.0}: the sun stands {:.1}° high", mars_sol_date(now), sun.altitude.to_degrees());
+//! ```
+
+use core::f64::consts::PI;
+
+use crate::delta_t::{decimal_year, delta_t};
+use crate::elements::OrbitalElements;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+use crate::{altitude, azimuth, to_days, FullPosition, Position, MILLISECONDS_PER_DAY};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+const MARS_SOL_IN_DAYS: f64 = 1.027_491_251_7;
+const MARS_SOL_DATE_EPOCH: f64 = 2_405_522.002_877_9;
+
+/// The planet of the observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Body {
+    /// Orbit of the planet around the sun.
+    pub elements: OrbitalElements,
+    /// Rotation angle (sidereal time at the prime meridian) at J2000 in degrees.
+    pub rotation_at_epoch: f64,
+    /// Increase of the rotation angle in degrees per day.
+    pub rotation_rate: f64,
+    /// Whether the elements and the rotation refer to terrestrial time instead of
+    /// UTC, in which case [ΔT](crate::delta_t) is applied.
+    pub terrestrial_time: bool,
+}
+
+impl Body {
+    /// The earth as modelled by [`pos`](crate::pos).
+    pub const EARTH: Self = Self {
+        elements: OrbitalElements::EARTH,
+        rotation_at_epoch: 280.16,
+        rotation_rate: 360.985_623_5,
+        terrestrial_time: false,
+    };
+
+    /// Mars after the Mars24 algorithm, with areocentric latitudes and east longitudes.
+    pub const MARS: Self = Self {
+        elements: OrbitalElements {
+            mean_anomaly_at_epoch: 19.387_1,
+            mean_anomaly_rate: 0.524_020_73,
+            equation_of_center: [10.691, 0.623, 0.050, 0.005, 0.000_5],
+            perihelion: 71.0,
+            perihelion_rate: 0.000_017_766,
+            obliquity: 25.191_9,
+            distance: [
+                1.523_679_34 * 1.004_36,
+                1.523_679_34 * -0.093_09,
+                1.523_679_34 * -0.004_336,
+                1.523_679_34 * -0.000_31,
+                1.523_679_34 * -0.000_03,
+            ],
+        },
+        rotation_at_epoch: 313.384_807_8,
+        rotation_rate: 350.891_985_09,
+        terrestrial_time: true,
+    };
+
+    /// Length of the mean solar day of the planet in earth days.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sun::body::Body;
+    ///
+    /// // a sol lasts 24 h 39 min 35 s
+    /// assert!((Body::MARS.solar_day() * 86_400.0 - 88_775.2).abs() < 0.1);
+    /// ```
+    #[must_use]
+    pub fn solar_day(&self) -> f64 {
+        360.0
+            / (self.rotation_rate - self.elements.mean_anomaly_rate - self.elements.perihelion_rate)
+    }
+
+    /// Days since J2000 in the time scale of the body.
+    pub(crate) fn days(&self, unixtime_in_ms: i64) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let days = to_days(unixtime_in_ms as f64);
+        if self.terrestrial_time {
+            days + delta_t(decimal_year(unixtime_in_ms)) / SECONDS_PER_DAY
+        } else {
+            days
+        }
+    }
+
+    /// Rotation angle in radians at the given days plus the longitude.
+    pub(crate) fn local_rotation(&self, days: f64, lon: f64) -> f64 {
+        (self.rotation_at_epoch + self.rotation_rate * days).to_radians() + lon.to_radians()
+    }
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Self::EARTH
+    }
+}
+
+/// Hour angle of the sun in radians with a correction of the rotation in seconds.
+fn hour_angle(body: &Body, days: f64, lon: f64, right_ascension: f64, rotation_offset: f64) -> f64 {
+    let rotation_days = days + rotation_offset * 1_000.0 / MILLISECONDS_PER_DAY;
+    body.local_rotation(rotation_days, lon) - right_ascension
+}
+
+/// Shared implementation of the azimuth and altitude on a body, with a correction of
+/// the rotation in seconds, e.g. DUT1 on earth.
+pub(crate) fn pos_at(
+    body: &Body,
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    rotation_offset: f64,
+) -> Position {
+    let latitude_rad = lat.to_radians();
+    let days = body.days(unixtime_in_ms);
+    let (right_ascension, declination) = body.elements.equatorial(days);
+    let hour_angle = hour_angle(body, days, lon, right_ascension, rotation_offset);
+    Position {
+        azimuth: azimuth(hour_angle, latitude_rad, declination),
+        altitude: altitude(hour_angle, latitude_rad, declination),
+    }
+}
+
+/// Like [`pos_at`] together with the equatorial coordinates, the hour angle and the
+/// distance of the sun.
+pub(crate) fn full_pos_at(
+    body: &Body,
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    rotation_offset: f64,
+) -> FullPosition {
+    let latitude_rad = lat.to_radians();
+    let days = body.days(unixtime_in_ms);
+    let (right_ascension, declination) = body.elements.equatorial(days);
+    let hour_angle = hour_angle(body, days, lon, right_ascension, rotation_offset);
+    FullPosition {
+        azimuth: azimuth(hour_angle, latitude_rad, declination),
+        altitude: altitude(hour_angle, latitude_rad, declination),
+        right_ascension: right_ascension.rem_euclid(2.0 * PI),
+        declination,
+        hour_angle: (hour_angle + PI).rem_euclid(2.0 * PI) - PI,
+        distance: body.elements.distance(days),
+    }
+}
+
+/// Calculates the position of the sun as seen from a body like [`full_pos`](crate::full_pos).
+///
+/// * `body`      - planet of the observer.
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - latitude on the body in degrees.
+/// * `lon`       - east longitude on the body in degrees.
+#[must_use]
+pub fn full_pos_on(body: &Body, unixtime_in_ms: i64, lat: f64, lon: f64) -> FullPosition {
+    full_pos_at(body, unixtime_in_ms, lat, lon, 0.0)
+}
+
+/// Calculates the position of the sun as seen from a body like [`pos`](crate::pos).
+#[must_use]
+pub fn pos_on(body: &Body, unixtime_in_ms: i64, lat: f64, lon: f64) -> Position {
+    pos_at(body, unixtime_in_ms, lat, lon, 0.0)
+}
+
+/// Calculates the local mean solar time in hours of the body, i.e. in 24ths of its
+/// mean solar day, 12.0 being the mean noon.
+#[must_use]
+pub fn mean_solar_time(body: &Body, unixtime_in_ms: i64, lon: f64) -> f64 {
+    let days = body.days(unixtime_in_ms);
+    let elements = &body.elements;
+    let mean_longitude = elements.mean_anomaly(days)
+        + (elements.perihelion + elements.perihelion_rate * days).to_radians()
+        + PI;
+    let hour_angle = body.local_rotation(days, lon) - mean_longitude;
+    (hour_angle.to_degrees() / 15.0 + 12.0).rem_euclid(24.0)
+}
+
+/// Calculates the local true solar time in hours of the body, i.e. the time shown by a
+/// sundial, 12.0 being the transit of the sun.
+#[must_use]
+pub fn true_solar_time(body: &Body, unixtime_in_ms: i64, lon: f64) -> f64 {
+    let hour_angle = full_pos_on(body, unixtime_in_ms, 0.0, lon).hour_angle;
+    (hour_angle.to_degrees() / 15.0 + 12.0).rem_euclid(24.0)
+}
+
+/// Calculates the [Mars Sol Date](https://en.wikipedia.org/wiki/Timekeeping_on_Mars#Sols),
+/// the number of sols since 1873-12-29.
+#[must_use]
+pub fn mars_sol_date(unixtime_in_ms: i64) -> f64 {
+    let days = Body::MARS.days(unixtime_in_ms);
+    (days + crate::JULIAN_2000 - MARS_SOL_DATE_EPOCH) / MARS_SOL_IN_DAYS
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // 2000-01-06 00:00 UTC, example A of Allison and McEwen
+    const EXAMPLE: i64 = 947_116_800_000;
+
+    #[test]
+    fn test_mars() {
+        let days = Body::MARS.days(EXAMPLE);
+        assert!((days - 4.500_742_8).abs() < 1e-5);
+        let solar_longitude = Body::MARS.elements.ecliptic_longitude(days).to_degrees();
+        assert!((solar_longitude.rem_euclid(360.0) - 277.187_58).abs() < 0.01);
+        assert!((mars_sol_date(EXAMPLE) - 44_795.999_80).abs() < 1e-4);
+        // coordinated Mars time 23:59:39
+        let mean_time = mean_solar_time(&Body::MARS, EXAMPLE, 0.0);
+        assert!((mean_time - 23.994_2).abs() < 0.005);
+        // the equation of time is -5.19°, i.e. the true sun lags by 20.75 minutes
+        let true_time = true_solar_time(&Body::MARS, EXAMPLE, 0.0);
+        assert!((true_time - (mean_time - 5.187_74 / 15.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_earth_body_reproduces_pos() {
+        let position = pos_on(&Body::default(), 1_362_441_600_000, 50.5, 30.5);
+        let expected = crate::pos(1_362_441_600_000, 50.5, 30.5);
+        assert!((position.azimuth - expected.azimuth).abs() < f64::EPSILON);
+        assert!((position.altitude - expected.altitude).abs() < f64::EPSILON);
+        assert!((Body::EARTH.solar_day() - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,336 @@
+//! Shading between two buildings, e.g. to answer when a planned building shades the
+//! facade or the roof of its neighbour.
+//!
+//! Buildings are prisms of a footprint polygon and a height. Footprints are given in a
+//! local plane in meters, with `x` pointing east and `y` pointing north, as found in
+//! site plans. The sun position is calculated once for the whole site.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::buildings::{building_shading, Building, Surface};
+//!
+//! let tower = Building {
+//!     footprint: vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)],
+//!     height: 20.0,
+//! };
+//! let house = Building {
+//!     footprint: vec![(0.0, 20.0), (10.0, 20.0), (10.0, 30.0), (0.0, 30.0)],
+//!     height: 10.0,
+//! };
+//! // the facade of the house facing the tower at the winter solstice
+//! let periods = building_shading(1_387_584_000_000, 48.0, 9.0, &tower, &house, Surface::Facade(0));
+//! for period in periods {
+//!     println!("shaded from {} to {}", period.start_in_ms, period.end_in_ms);
+//! }
+//! ```
+
+use crate::{pos, Position, MILLISECONDS_PER_DAY_INT};
+
+const STEP_MS: i64 = 60 * 1_000;
+/// Number of sample points along each side of a surface.
+const SAMPLES: usize = 10;
+/// Distance in meters by which the sample points are moved off their surface.
+const CLEARANCE: f64 = 1e-6;
+
+/// A point or a direction as `(x, y, z)` with `z` pointing up.
+type Vector = (f64, f64, f64);
+
+/// A building as a prism of a footprint and a height.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Building {
+    /// Corners of the footprint as `(x, y)` in meters, `x` to the east and `y` to the
+    /// north, in either orientation.
+    pub footprint: Vec<(f64, f64)>,
+    /// Height of the building in meters.
+    pub height: f64,
+}
+
+/// A surface of a [`Building`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    /// The wall between the corners `i` and `i + 1` of the footprint.
+    Facade(usize),
+    /// The flat roof.
+    Roof,
+}
+
+/// A period in which a building shades a surface of another, see [`building_shading`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadingPeriod {
+    /// Start as [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    pub start_in_ms: i64,
+    /// End as unix time in milliseconds.
+    pub end_in_ms: i64,
+    /// Largest shaded fraction of the surface during the period.
+    pub max_shaded_fraction: f64,
+}
+
+impl Building {
+    /// Twice the signed area of the footprint, positive if it's counterclockwise.
+    fn orientation(&self) -> f64 {
+        let n = self.footprint.len();
+        (0..n)
+            .map(|i| {
+                let (a, b) = (self.footprint[i], self.footprint[(i + 1) % n]);
+                a.0 * b.1 - b.0 * a.1
+            })
+            .sum()
+    }
+
+    fn contains(&self, (x, y): (f64, f64)) -> bool {
+        let n = self.footprint.len();
+        let mut inside = false;
+        for i in 0..n {
+            let (from, to) = (self.footprint[i], self.footprint[(i + 1) % n]);
+            if (from.1 > y) != (to.1 > y)
+                && x < from.0 + (y - from.1) / (to.1 - from.1) * (to.0 - from.0)
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Whether the ray from `point` towards the sun `direction` passes through the building.
+    fn blocks(&self, point: Vector, direction: Vector) -> bool {
+        if point.2 >= self.height || direction.2 <= 0.0 {
+            return false;
+        }
+        // the ray leaves the height of the building at `reach`
+        let reach = (self.height - point.2.max(0.0)) / direction.2;
+        let start = (point.0, point.1);
+        let end = (point.0 + reach * direction.0, point.1 + reach * direction.1);
+        if self.contains(start) || self.contains(end) {
+            return true;
+        }
+        let n = self.footprint.len();
+        (0..n)
+            .any(|i| segments_intersect(start, end, self.footprint[i], self.footprint[(i + 1) % n]))
+    }
+
+    /// Sample points on a surface with the outward normal of the surface.
+    fn samples(&self, surface: Surface) -> (Vec<Vector>, Vector) {
+        #[allow(clippy::cast_precision_loss)]
+        let fraction = |i: usize| (i as f64 + 0.5) / SAMPLES as f64;
+        match surface {
+            Surface::Facade(index) => {
+                let n = self.footprint.len();
+                let (a, b) = (self.footprint[index % n], self.footprint[(index + 1) % n]);
+                let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+                let length = dx.hypot(dy);
+                let sign = self.orientation().signum();
+                let normal = (sign * dy / length, -sign * dx / length, 0.0);
+                let points = (0..SAMPLES)
+                    .flat_map(|i| (0..SAMPLES).map(move |j| (fraction(i), fraction(j))))
+                    .map(|(along, up)| {
+                        (
+                            a.0 + along * dx + CLEARANCE * normal.0,
+                            a.1 + along * dy + CLEARANCE * normal.1,
+                            up * self.height,
+                        )
+                    })
+                    .collect();
+                (points, normal)
+            }
+            Surface::Roof => {
+                let (min, max) = self.footprint.iter().fold(
+                    (
+                        (f64::INFINITY, f64::INFINITY),
+                        (f64::NEG_INFINITY, f64::NEG_INFINITY),
+                    ),
+                    |(min, max), &(x, y)| {
+                        ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)))
+                    },
+                );
+                let points = (0..SAMPLES)
+                    .flat_map(|i| (0..SAMPLES).map(move |j| (fraction(i), fraction(j))))
+                    .map(|(u, v)| (min.0 + u * (max.0 - min.0), min.1 + v * (max.1 - min.1)))
+                    .filter(|&point| self.contains(point))
+                    .map(|(x, y)| (x, y, self.height + CLEARANCE))
+                    .collect();
+                (points, (0.0, 0.0, 1.0))
+            }
+        }
+    }
+}
+
+fn segments_intersect(p1: (f64, f64), p2: (f64, f64), q1: (f64, f64), q2: (f64, f64)) -> bool {
+    let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let d1 = cross(q1, q2, p1);
+    let d2 = cross(q1, q2, p2);
+    let d3 = cross(p1, p2, q1);
+    let d4 = cross(p1, p2, q2);
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+/// Calculates the fraction of a surface of `target` that lies in the shadow of `caster`
+/// for the sun at `sun`, between 0 and 1.
+///
+/// Returns 0 if the sun doesn't shine on the surface anyway, i.e. if it's below the
+/// horizon or behind the surface.
+///
+/// # Panics
+///
+/// Panics if the footprint of `target` has less than three corners.
+#[must_use]
+pub fn shaded_fraction(
+    caster: &Building,
+    target: &Building,
+    surface: Surface,
+    sun: Position,
+) -> f64 {
+    assert!(
+        target.footprint.len() >= 3,
+        "a footprint needs at least three corners"
+    );
+    let direction = (
+        sun.azimuth.sin() * sun.altitude.cos(),
+        sun.azimuth.cos() * sun.altitude.cos(),
+        sun.altitude.sin(),
+    );
+    let (points, normal) = target.samples(surface);
+    let facing = normal.0 * direction.0 + normal.1 * direction.1 + normal.2 * direction.2;
+    if sun.altitude <= 0.0 || facing <= 0.0 || points.is_empty() {
+        return 0.0;
+    }
+    let shaded = points
+        .iter()
+        .filter(|&&point| caster.blocks(point, direction))
+        .count();
+    #[allow(clippy::cast_precision_loss)]
+    let fraction = shaded as f64 / points.len() as f64;
+    fraction
+}
+
+/// Finds the periods within the UTC day of `unixtime_in_ms` in which `caster` shades a
+/// surface of `target`, with a resolution of one minute.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) of the site in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) of the site in degrees.
+///
+/// # Panics
+///
+/// Panics if the footprint of `target` has less than three corners.
+#[must_use]
+pub fn building_shading(
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    caster: &Building,
+    target: &Building,
+    surface: Surface,
+) -> Vec<ShadingPeriod> {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let mut periods = Vec::new();
+    let mut open: Option<ShadingPeriod> = None;
+    for step in 0..=MILLISECONDS_PER_DAY_INT / STEP_MS {
+        let time = start + step * STEP_MS;
+        let fraction = shaded_fraction(caster, target, surface, pos(time, lat, lon));
+        match (fraction > 0.0, open.as_mut()) {
+            (true, Some(period)) => {
+                period.max_shaded_fraction = period.max_shaded_fraction.max(fraction);
+            }
+            (true, None) => {
+                open = Some(ShadingPeriod {
+                    start_in_ms: time,
+                    end_in_ms: time,
+                    max_shaded_fraction: fraction,
+                });
+            }
+            (false, Some(_)) => {
+                if let Some(mut period) = open.take() {
+                    period.end_in_ms = time;
+                    periods.push(period);
+                }
+            }
+            (false, None) => {}
+        }
+    }
+    if let Some(mut period) = open {
+        period.end_in_ms = start + MILLISECONDS_PER_DAY_INT;
+        periods.push(period);
+    }
+    periods
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn tower() -> Building {
+        Building {
+            footprint: vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)],
+            height: 20.0,
+        }
+    }
+
+    fn house() -> Building {
+        // clockwise, the first wall faces south
+        Building {
+            footprint: vec![(10.0, 20.0), (0.0, 20.0), (0.0, 30.0), (10.0, 30.0)],
+            height: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_shaded_fraction() {
+        let sun = |altitude_deg: f64| Position {
+            azimuth: PI,
+            altitude: altitude_deg.to_radians(),
+        };
+        let south = Surface::Facade(0);
+        // the shadow of the tower ends 20 m / tan(alt) north of it
+        assert!((shaded_fraction(&tower(), &house(), south, sun(20.0)) - 1.0).abs() < 1e-9);
+        assert!(shaded_fraction(&tower(), &house(), south, sun(70.0)).abs() < 1e-9);
+        // at 45° the shadow climbs 10 m up the facade, i.e. its whole height
+        let half = shaded_fraction(&tower(), &house(), south, sun(50.0));
+        assert!(half > 0.5 && half < 1.0, "{half}");
+        // the northern wall faces away from the sun
+        assert!(shaded_fraction(&tower(), &house(), Surface::Facade(2), sun(20.0)).abs() < 1e-9);
+        // the roof is shaded from 10 m above its height: up to (20 - 10) / tan(alt)
+        let roof = shaded_fraction(&tower(), &house(), Surface::Roof, sun(30.0));
+        assert!(
+            (roof - (10.0 / 30_f64.to_radians().tan() - 10.0) / 10.0).abs() < 0.1,
+            "{roof}"
+        );
+    }
+
+    #[test]
+    fn test_building_shading() {
+        // 2013-12-21 UTC, the low winter sun shades the house around noon
+        let winter = building_shading(
+            1_387_584_000_000,
+            48.0,
+            9.0,
+            &tower(),
+            &house(),
+            Surface::Facade(0),
+        );
+        let noon = crate::solar_noon(1_387_584_000_000 + MILLISECONDS_PER_DAY_INT / 2, 9.0);
+        let period = winter
+            .iter()
+            .find(|period| period.start_in_ms < noon && noon < period.end_in_ms)
+            .unwrap();
+        assert!((period.max_shaded_fraction - 1.0).abs() < 1e-9);
+        // 2013-06-21 UTC, the high summer sun at noon doesn't
+        let summer_noon = crate::solar_noon(1_371_816_000_000, 9.0);
+        let summer = building_shading(
+            1_371_772_800_000,
+            48.0,
+            9.0,
+            &tower(),
+            &house(),
+            Surface::Facade(0),
+        );
+        assert!(summer
+            .iter()
+            .all(|period| period.end_in_ms < summer_noon || summer_noon < period.start_in_ms));
+    }
+}
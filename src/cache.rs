@@ -0,0 +1,263 @@
+//! A thread-safe cache of positions and event times for servers answering many
+//! repeated queries.
+//!
+//! The inputs are quantized before the lookup, so requests within the same second and
+//! within about ten meters share an entry. Event times only depend on the day, so all
+//! requests of a day share an entry. The cached result is the one of the quantized
+//! inputs, i.e. it doesn't depend on which request filled the entry.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::num::NonZeroUsize;
+//! use sun::cache::Cache;
+//!
+//! let cache = Cache::new(NonZeroUsize::new(10_000).unwrap());
+//! let first = cache.pos(1_362_441_600_000, 48.0, 9.0);
+//! let second = cache.pos(1_362_441_600_400, 48.000_01, 9.0);
+//! assert_eq!(first.altitude, second.altitude);
+//! assert_eq!(cache.len(), 1);
+//! ```
+
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use lru::LruCache;
+
+use crate::{pos, PhaseOutcome, Position, SunPhase, Transit};
+
+/// Resolution of the cache keys.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantization {
+    /// Resolution of the time in milliseconds.
+    pub time_ms: i64,
+    /// Resolution of latitude and longitude in degrees, at least [`f64::EPSILON`].
+    pub degrees: f64,
+}
+
+impl Default for Quantization {
+    /// One second and 0.0001°, i.e. about ten meters.
+    fn default() -> Self {
+        Self {
+            time_ms: 1_000,
+            degrees: 0.000_1,
+        }
+    }
+}
+
+impl Quantization {
+    fn time(&self, unixtime_in_ms: i64) -> i64 {
+        let step = self.time_ms.max(1);
+        (unixtime_in_ms + step / 2).div_euclid(step)
+    }
+
+    fn step(&self) -> f64 {
+        self.degrees.max(f64::EPSILON)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn angle(&self, degrees: f64) -> i64 {
+        (degrees / self.step()).round() as i64
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn location(&self, lat: i64, lon: i64) -> (f64, f64) {
+        (lat as f64 * self.step(), lon as f64 * self.step())
+    }
+}
+
+type PositionKey = (i64, i64, i64);
+/// Julian cycle, location, angle and direction of the phase and height.
+type PhaseKey = (i64, i64, i64, u64, bool, u64);
+
+/// Least recently used cache of [`pos`] and [`try_time_at_phase`](crate::try_time_at_phase).
+#[derive(Debug)]
+pub struct Cache {
+    quantization: Quantization,
+    positions: Mutex<LruCache<PositionKey, Position>>,
+    phases: Mutex<LruCache<PhaseKey, PhaseOutcome>>,
+}
+
+/// Locks a cache, ignoring that another thread panicked while holding the lock, as the
+/// entries are complete values anyway.
+fn lock<K: std::hash::Hash + Eq, V>(
+    cache: &Mutex<LruCache<K, V>>,
+) -> MutexGuard<'_, LruCache<K, V>> {
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Cache {
+    /// Creates a cache holding up to `capacity` positions and `capacity` event times
+    /// with the [default](Quantization::default) quantization.
+    #[must_use]
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self::with_quantization(capacity, Quantization::default())
+    }
+
+    /// Creates a cache with the given quantization.
+    #[must_use]
+    pub fn with_quantization(capacity: NonZeroUsize, quantization: Quantization) -> Self {
+        Self {
+            quantization,
+            positions: Mutex::new(LruCache::new(capacity)),
+            phases: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the [`pos`] at the quantized time and location.
+    pub fn pos(&self, unixtime_in_ms: i64, lat: f64, lon: f64) -> Position {
+        let q = &self.quantization;
+        let key = (q.time(unixtime_in_ms), q.angle(lat), q.angle(lon));
+        if let Some(&position) = lock(&self.positions).get(&key) {
+            return position;
+        }
+        let (lat, lon) = q.location(key.1, key.2);
+        let position = pos(key.0 * q.time_ms.max(1), lat, lon);
+        lock(&self.positions).put(key, position);
+        position
+    }
+
+    /// Returns the [`try_time_at_phase`](crate::try_time_at_phase) at the quantized
+    /// location, shared by all times of the day.
+    pub fn try_time_at_phase(
+        &self,
+        unixtime_in_ms: i64,
+        sun_phase: SunPhase,
+        lat: f64,
+        lon: f64,
+        height: f64,
+    ) -> PhaseOutcome {
+        let q = &self.quantization;
+        let (lat_key, lon_key) = (q.angle(lat), q.angle(lon));
+        let (lat, lon) = q.location(lat_key, lon_key);
+        let cycle = Transit::cycle(unixtime_in_ms, lon);
+        #[allow(clippy::cast_possible_truncation)]
+        let key = (
+            cycle as i64,
+            lat_key,
+            lon_key,
+            sun_phase.angle_deg().to_bits(),
+            sun_phase.is_rise(),
+            height.to_bits(),
+        );
+        if let Some(&outcome) = lock(&self.phases).get(&key) {
+            return outcome;
+        }
+        let outcome = Transit::of_cycle(cycle, lon).phase_outcome(sun_phase, lat, height);
+        lock(&self.phases).put(key, outcome);
+        outcome
+    }
+
+    /// Number of cached positions and event times.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        lock(&self.positions).len() + lock(&self.phases).len()
+    }
+
+    /// Whether the cache is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all entries.
+    pub fn clear(&self) {
+        lock(&self.positions).clear();
+        lock(&self.phases).clear();
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+
+    use super::*;
+    use crate::try_time_at_phase;
+
+    #[test]
+    fn test_cache_hits() {
+        let cache = Cache::new(NonZeroUsize::new(2).unwrap());
+        let date = 1_362_441_600_000;
+        let position = cache.pos(date + 300, 50.5, 30.5);
+        assert_eq!(position.azimuth, pos(date, 50.5, 30.5).azimuth);
+        assert_eq!(
+            cache.pos(date - 300, 50.500_01, 30.5).altitude,
+            position.altitude
+        );
+        assert_eq!(cache.len(), 1);
+        let sunrise = cache.try_time_at_phase(date, SunPhase::Sunrise, 50.5, 30.5, 0.0);
+        assert_eq!(
+            sunrise,
+            try_time_at_phase(date, SunPhase::Sunrise, 50.5, 30.5, 0.0)
+        );
+        // all times of the day share the entry
+        let evening = date + 18 * 60 * 60 * 1_000;
+        assert_eq!(
+            cache.try_time_at_phase(evening, SunPhase::Sunrise, 50.5, 30.5, 0.0),
+            sunrise
+        );
+        assert_eq!(cache.len(), 2);
+        // a custom phase at the same angle but the other direction has its own entry
+        cache.try_time_at_phase(date, SunPhase::Custom(-0.833, false), 50.5, 30.5, 0.0);
+        assert_eq!(cache.len(), 3);
+        // least recently used entries are evicted
+        cache.pos(date + 60_000, 50.5, 30.5);
+        cache.pos(date + 120_000, 50.5, 30.5);
+        assert_eq!(cache.len(), 4);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_polar_night() {
+        let cache = Cache::new(NonZeroUsize::new(10).unwrap());
+        let date = 1_387_584_000_000;
+        assert_eq!(
+            cache.try_time_at_phase(date, SunPhase::Sunrise, 78.2, 15.6, 0.0),
+            PhaseOutcome::AlwaysBelow
+        );
+        assert_eq!(
+            cache.try_time_at_phase(date + 60_000, SunPhase::Sunrise, 78.2, 15.6, 0.0),
+            PhaseOutcome::AlwaysBelow
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_non_positive_resolution() {
+        let date = 1_362_441_600_000;
+        for degrees in [0.0, -1.0, f64::NAN] {
+            let cache = Cache::with_quantization(
+                NonZeroUsize::new(10).unwrap(),
+                Quantization {
+                    time_ms: 0,
+                    degrees,
+                },
+            );
+            let position = cache.pos(date, 50.5, 30.5);
+            assert!((position.azimuth - pos(date, 50.5, 30.5).azimuth).abs() < 1e-12);
+            assert_ne!(cache.pos(date, 50.6, 30.5).altitude, position.altitude);
+            assert_eq!(cache.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let cache = Cache::new(NonZeroUsize::new(100).unwrap());
+        std::thread::scope(|scope| {
+            for i in 0..4 {
+                let cache = &cache;
+                scope.spawn(move || {
+                    for minute in 0..10 {
+                        cache.pos(
+                            1_362_441_600_000 + minute * 60_000,
+                            50.5,
+                            30.5 + f64::from(i),
+                        );
+                    }
+                });
+            }
+        });
+        assert_eq!(cache.len(), 40);
+    }
+}
@@ -0,0 +1,568 @@
+//! Month calendars of sun events, ready to feed calendar UIs and print layouts,
+//! comparisons of a date across many years and conversions of historical dates.
+//!
+//! Years use astronomical numbering, i.e. the year 0 is 1 BC. Unless a [`Calendar`]
+//! is given, dates are in the proleptic Gregorian calendar.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::calendar::month_calendar;
+//!
+//! let calendar = month_calendar(2013, 6, 48.0, 9.0);
+//! for week in calendar.weeks() {
+//!     for day in week.iter().flatten() {
+//!         print!("{:>3} ", day.day);
+//!     }
+//!     println!();
+//! }
+//! ```
+
+#[cfg(feature = "chrono-tz")]
+use chrono::TimeZone;
+
+use std::ops::RangeInclusive;
+
+use crate::report::sun_events;
+use crate::{local_noon, MILLISECONDS_PER_DAY_INT};
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+pub(crate) fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Julian calendar.
+fn days_from_julian_calendar(year: i32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(4);
+    let year_of_era = year - era * 4;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    era * 1_461 + year_of_era * 365 + day_of_year - 719_470
+}
+
+/// Year, month and day from the day of a year starting on March 1st.
+fn from_day_of_year(year: i64, day_of_year: i64) -> (i32, u32, u32) {
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year + i64::from(month <= 2);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    (year as i32, month as u32, day as u32)
+}
+
+/// Date in the proleptic Gregorian calendar of the days since 1970-01-01.
+pub(crate) fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    from_day_of_year(year_of_era + era * 400, day_of_year)
+}
+
+/// Date in the proleptic Julian calendar of the days since 1970-01-01.
+fn julian_calendar_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719_470;
+    let era = days.div_euclid(1_461);
+    let day_of_era = days - era * 1_461;
+    let year_of_era = (day_of_era - day_of_era / 1_460) / 365;
+    from_day_of_year(year_of_era + era * 4, day_of_era - 365 * year_of_era)
+}
+
+/// Number of days of a month in the proleptic Gregorian calendar.
+pub(crate) const fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Calendar in which a civil date is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Calendar {
+    /// The [proleptic Gregorian calendar](https://en.wikipedia.org/wiki/Proleptic_Gregorian_calendar),
+    /// also for dates before its introduction.
+    Gregorian,
+    /// The [proleptic Julian calendar](https://en.wikipedia.org/wiki/Proleptic_Julian_calendar),
+    /// also for dates after the Gregorian reform.
+    Julian,
+    /// The Julian calendar until 1582-10-04 followed by the Gregorian calendar from
+    /// 1582-10-15, as used by most historical sources.
+    Historical,
+}
+
+impl Calendar {
+    /// Days since 1970-01-01 of the first day of the Gregorian calendar, 1582-10-15.
+    const GREGORIAN_REFORM: i64 = -141_427;
+
+    /// Number of days of a month in this calendar.
+    ///
+    /// The historical October 1582 is reported with its 31 nominal days, although the
+    /// days from the 5th to the 14th were skipped.
+    #[must_use]
+    pub const fn days_in_month(self, year: i32, month: u32) -> u32 {
+        let julian = match self {
+            Calendar::Gregorian => false,
+            Calendar::Julian => true,
+            Calendar::Historical => year < 1582 || (year == 1582 && month < 10),
+        };
+        if julian && month == 2 {
+            if year % 4 == 0 {
+                29
+            } else {
+                28
+            }
+        } else {
+            days_in_month(year, month)
+        }
+    }
+
+    /// Calculates the [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds
+    /// of the start of a date (00:00 UTC) in this calendar.
+    ///
+    /// The dates skipped by the historical calendar, 1582-10-05 to 1582-10-14, are
+    /// interpreted as Julian dates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sun::calendar::Calendar;
+    ///
+    /// // the fall of Constantinople on 29 May 1453 (Julian)
+    /// let historical = Calendar::Historical.unixtime_from_date(1453, 5, 29);
+    /// assert_eq!(historical, Calendar::Gregorian.unixtime_from_date(1453, 6, 7));
+    /// ```
+    #[must_use]
+    pub fn unixtime_from_date(self, year: i32, month: u32, day: u32) -> i64 {
+        let days = match self {
+            Calendar::Gregorian => days_from_civil(year, month, day),
+            Calendar::Julian => days_from_julian_calendar(year, month, day),
+            Calendar::Historical => {
+                if (year, month, day) < (1582, 10, 15) {
+                    days_from_julian_calendar(year, month, day)
+                } else {
+                    days_from_civil(year, month, day)
+                }
+            }
+        };
+        days * MILLISECONDS_PER_DAY_INT
+    }
+
+    /// Calculates the date (year, month, day) in this calendar of a
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    #[must_use]
+    pub fn date_from_unixtime(self, unixtime_in_ms: i64) -> (i32, u32, u32) {
+        let days = unixtime_in_ms.div_euclid(MILLISECONDS_PER_DAY_INT);
+        let julian = match self {
+            Calendar::Gregorian => false,
+            Calendar::Julian => true,
+            Calendar::Historical => days < Self::GREGORIAN_REFORM,
+        };
+        if julian {
+            julian_calendar_from_days(days)
+        } else {
+            civil_from_days(days)
+        }
+    }
+}
+
+/// A [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds, built from
+/// the components of a UTC date and time in the proleptic Gregorian calendar without
+/// any date library.
+///
+/// # Example
+///
+/// ```rust
+/// use sun::calendar::UnixTime;
+///
+/// let time = UnixTime::from_ymd_hms_utc(2013, 3, 5, 12, 0, 0).unwrap();
+/// let position = sun::pos(time.as_millis(), 48.0, 9.0);
+/// assert_eq!(time.to_ymd_hms_utc(), (2013, 3, 5, 12, 0, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnixTime(pub i64);
+
+impl UnixTime {
+    /// Creates the time of the start of a date (00:00 UTC), `None` for an invalid date.
+    #[must_use]
+    pub fn from_ymd_utc(year: i32, month: u32, day: u32) -> Option<Self> {
+        Self::from_ymd_hms_utc(year, month, day, 0, 0, 0)
+    }
+
+    /// Creates the time of a date and time of day in UTC, `None` for an invalid date or
+    /// time. Leap seconds are not supported, like in unix time.
+    #[must_use]
+    pub fn from_ymd_hms_utc(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        let valid = (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day)
+            && hour < 24
+            && minute < 60
+            && second < 60;
+        valid.then(|| {
+            let seconds = i64::from(hour * 3_600 + minute * 60 + second);
+            Self(days_from_civil(year, month, day) * MILLISECONDS_PER_DAY_INT + seconds * 1_000)
+        })
+    }
+
+    /// Returns the milliseconds since 1970-01-01 00:00 UTC.
+    #[must_use]
+    pub const fn as_millis(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the UTC date and time of day (year, month, day, hour, minute, second),
+    /// dropping the milliseconds.
+    #[must_use]
+    pub fn to_ymd_hms_utc(self) -> (i32, u32, u32, u32, u32, u32) {
+        let (year, month, day) = civil_from_days(self.0.div_euclid(MILLISECONDS_PER_DAY_INT));
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let seconds = (self.0.rem_euclid(MILLISECONDS_PER_DAY_INT) / 1_000) as u32;
+        (
+            year,
+            month,
+            day,
+            seconds / 3_600,
+            seconds / 60 % 60,
+            seconds % 60,
+        )
+    }
+}
+
+impl From<UnixTime> for i64 {
+    fn from(time: UnixTime) -> Self {
+        time.0
+    }
+}
+
+/// Sun events of one day of a [`MonthCalendar`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarDay {
+    /// Day of the month, starting at 1.
+    pub day: u32,
+    /// Day of the week, 0 for Monday to 6 for Sunday.
+    pub weekday: u32,
+    /// Start of the day in UTC.
+    pub unixtime_in_ms: i64,
+    /// Sunrise, `None` during polar day or polar night.
+    pub sunrise: Option<i64>,
+    /// Sunset, `None` during polar day or polar night.
+    pub sunset: Option<i64>,
+    /// Transit of the sun through the meridian.
+    pub solar_noon: i64,
+    /// Time between sunrise and sunset in milliseconds.
+    pub day_length_ms: i64,
+}
+
+/// Local times of the events of a [`CalendarDay`], formatted for display.
+#[cfg(feature = "chrono-tz")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalTimes {
+    /// Formatted sunrise, `None` during polar day or polar night.
+    pub sunrise: Option<String>,
+    /// Formatted sunset, `None` during polar day or polar night.
+    pub sunset: Option<String>,
+    /// Formatted solar noon.
+    pub solar_noon: String,
+}
+
+#[cfg(feature = "chrono-tz")]
+impl CalendarDay {
+    /// Formats the events in the given time zone with a
+    /// [`chrono` format string](https://docs.rs/chrono/latest/chrono/format/strftime/index.html),
+    /// e.g. `"%H:%M"`.
+    #[must_use]
+    pub fn format_local(&self, tz: chrono_tz::Tz, format: &str) -> LocalTimes {
+        let local = |time: i64| {
+            tz.timestamp_millis_opt(time)
+                .single()
+                .map(|time| time.format(format).to_string())
+                .unwrap_or_default()
+        };
+        LocalTimes {
+            sunrise: self.sunrise.map(local),
+            sunset: self.sunset.map(local),
+            solar_noon: local(self.solar_noon),
+        }
+    }
+}
+
+/// Sun events of all days of a month.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonthCalendar {
+    /// Year of the month.
+    pub year: i32,
+    /// Month, 1 for January to 12 for December.
+    pub month: u32,
+    /// Entries of all days of the month.
+    pub days: Vec<CalendarDay>,
+}
+
+impl MonthCalendar {
+    /// Arranges the days in weeks starting on Monday, padding the first and the last week with `None`.
+    #[must_use]
+    pub fn weeks(&self) -> Vec<[Option<CalendarDay>; 7]> {
+        let mut weeks = Vec::new();
+        let mut week = [None; 7];
+        for day in &self.days {
+            week[day.weekday as usize] = Some(*day);
+            if day.weekday == 6 {
+                weeks.push(week);
+                week = [None; 7];
+            }
+        }
+        if week.iter().any(Option::is_some) {
+            weeks.push(week);
+        }
+        weeks
+    }
+}
+
+/// Calculates the sun events of every day of a month at a given latitude/longitude.
+///
+/// The events of a day are the ones around the local solar noon of that date.
+///
+/// * `year`  - year in the proleptic Gregorian calendar.
+/// * `month` - month from 1 to 12.
+/// * `lat`   - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`   - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Panics
+///
+/// Panics if `month` is not within 1 to 12.
+///
+/// # Examples
+///
+/// ```rust
+/// let calendar = sun::calendar::month_calendar(2024, 2, 48.0, 9.0);
+/// assert_eq!(calendar.days.len(), 29);
+/// ```
+#[must_use]
+pub fn month_calendar(year: i32, month: u32, lat: f64, lon: f64) -> MonthCalendar {
+    assert!((1..=12).contains(&month), "invalid month {month}");
+    let first = days_from_civil(year, month, 1);
+    let days = (1..=days_in_month(year, month))
+        .map(|day| {
+            let days = first + i64::from(day) - 1;
+            let unixtime_in_ms = days * MILLISECONDS_PER_DAY_INT;
+            let events = sun_events(local_noon(unixtime_in_ms, lon), lat, lon);
+            CalendarDay {
+                day,
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                weekday: (days + 3).rem_euclid(7) as u32,
+                unixtime_in_ms,
+                sunrise: events.sunrise,
+                sunset: events.sunset,
+                solar_noon: events.solar_noon,
+                day_length_ms: events.day_length_ms,
+            }
+        })
+        .collect();
+    MonthCalendar { year, month, days }
+}
+
+/// Sun events of a date in one year, see [`compare_years`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct YearEntry {
+    /// The year.
+    pub year: i32,
+    /// Start of the date in UTC.
+    pub unixtime_in_ms: i64,
+    /// Sunrise, `None` during polar day or polar night.
+    pub sunrise: Option<i64>,
+    /// Sunset, `None` during polar day or polar night.
+    pub sunset: Option<i64>,
+    /// Time between sunrise and sunset in milliseconds.
+    pub day_length_ms: i64,
+}
+
+/// Calculates the sun events of the same date in each of the given years, e.g. June 21
+/// from 1990 to 2030.
+///
+/// Years in which the date does not exist, i.e. February 29 in common years, are skipped.
+///
+/// * `month` - month from 1 to 12.
+/// * `day`   - day of the month.
+/// * `years` - years in the proleptic Gregorian calendar.
+/// * `lat`   - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`   - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Panics
+///
+/// Panics if `month` is not within 1 to 12.
+///
+/// # Examples
+///
+/// ```rust
+/// let entries = sun::calendar::compare_years(6, 21, 1990..=2030, 51.5, 0.0);
+/// assert_eq!(entries.len(), 41);
+/// ```
+#[must_use]
+pub fn compare_years(
+    month: u32,
+    day: u32,
+    years: RangeInclusive<i32>,
+    lat: f64,
+    lon: f64,
+) -> Vec<YearEntry> {
+    assert!((1..=12).contains(&month), "invalid month {month}");
+    let (first_year, last_year) = years.into_inner();
+    let mut entries = Vec::new();
+    if first_year > last_year {
+        return entries;
+    }
+    // day of the year of the date in a common and a leap year
+    let offset = |year: i32| days_from_civil(year, month, 1) - days_from_civil(year, 1, 1);
+    let offsets = [offset(2001), offset(2000)];
+    let mut new_year = days_from_civil(first_year, 1, 1);
+    for year in first_year..=last_year {
+        let leap = days_in_month(year, 2) == 29;
+        if day <= days_in_month(year, month) {
+            let days = new_year + offsets[usize::from(leap)] + i64::from(day) - 1;
+            let unixtime_in_ms = days * MILLISECONDS_PER_DAY_INT;
+            let events = sun_events(local_noon(unixtime_in_ms, lon), lat, lon);
+            entries.push(YearEntry {
+                year,
+                unixtime_in_ms,
+                sunrise: events.sunrise,
+                sunset: events.sunset,
+                day_length_ms: events.day_length_ms,
+            });
+        }
+        new_year += if leap { 366 } else { 365 };
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(
+            days_from_civil(2013, 3, 5) * MILLISECONDS_PER_DAY_INT,
+            1_362_441_600_000
+        );
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+    }
+
+    #[test]
+    fn test_unixtime() {
+        let time = UnixTime::from_ymd_hms_utc(2013, 3, 5, 0, 0, 0).unwrap();
+        assert_eq!(i64::from(time), 1_362_441_600_000);
+        assert_eq!(UnixTime::from_ymd_utc(2013, 3, 5), Some(time));
+        let time = UnixTime::from_ymd_hms_utc(1969, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(time.as_millis(), -1_000);
+        assert_eq!(time.to_ymd_hms_utc(), (1969, 12, 31, 23, 59, 59));
+        assert_eq!(UnixTime(-1).to_ymd_hms_utc(), (1969, 12, 31, 23, 59, 59));
+        assert_eq!(UnixTime::from_ymd_utc(2023, 2, 29), None);
+        assert!(UnixTime::from_ymd_utc(2024, 2, 29).is_some());
+        assert_eq!(UnixTime::from_ymd_hms_utc(2024, 13, 1, 0, 0, 0), None);
+        assert_eq!(UnixTime::from_ymd_hms_utc(2024, 1, 1, 24, 0, 0), None);
+        assert_eq!(UnixTime::from_ymd_hms_utc(2024, 1, 1, 23, 60, 0), None);
+    }
+
+    #[test]
+    fn test_calendars() {
+        // the Gregorian reform
+        let reform = Calendar::Gregorian.unixtime_from_date(1582, 10, 15);
+        assert_eq!(
+            reform,
+            Calendar::GREGORIAN_REFORM * MILLISECONDS_PER_DAY_INT
+        );
+        assert_eq!(
+            Calendar::Julian.unixtime_from_date(1582, 10, 4),
+            reform - MILLISECONDS_PER_DAY_INT
+        );
+        assert_eq!(
+            Calendar::Historical.unixtime_from_date(1582, 10, 15),
+            reform
+        );
+        assert_eq!(
+            Calendar::Historical.date_from_unixtime(reform - 1),
+            (1582, 10, 4)
+        );
+        // 1970-01-01 is 1969-12-19 in the Julian calendar
+        assert_eq!(Calendar::Julian.date_from_unixtime(0), (1969, 12, 19));
+        assert_eq!(Calendar::Julian.days_in_month(1900, 2), 29);
+        assert_eq!(Calendar::Gregorian.days_in_month(1900, 2), 28);
+        assert_eq!(Calendar::Historical.days_in_month(1500, 2), 29);
+        for calendar in [Calendar::Gregorian, Calendar::Julian, Calendar::Historical] {
+            for days in (-800_000..800_000).step_by(997) {
+                let unixtime = days * MILLISECONDS_PER_DAY_INT;
+                let (year, month, day) = calendar.date_from_unixtime(unixtime);
+                assert!(day <= calendar.days_in_month(year, month));
+                assert_eq!(calendar.unixtime_from_date(year, month, day), unixtime);
+            }
+        }
+    }
+
+    #[test]
+    fn test_month_calendar() {
+        let calendar = month_calendar(2013, 3, 50.5, 30.5);
+        assert_eq!(calendar.days.len(), 31);
+        // 2013-03-01 was a Friday
+        assert_eq!(calendar.days[0].weekday, 4);
+        let weeks = calendar.weeks();
+        assert_eq!(weeks.len(), 5);
+        assert!(weeks[0][3].is_none());
+        assert_eq!(weeks[0][4].map(|day| day.day), Some(1));
+        let fifth = calendar.days[4];
+        assert_eq!(fifth.unixtime_in_ms, 1_362_441_600_000);
+        assert!((fifth.sunrise.unwrap() - 1_362_458_096_440).abs() < 60_000);
+        assert!(calendar.days[30].day_length_ms > fifth.day_length_ms);
+    }
+
+    #[test]
+    fn test_compare_years() {
+        let entries = compare_years(3, 5, 2010..=2016, 50.5, 30.5);
+        assert_eq!(entries.len(), 7);
+        let entry = entries[3];
+        assert_eq!(entry.year, 2013);
+        assert_eq!(entry.unixtime_in_ms, 1_362_441_600_000);
+        for entry in &entries {
+            assert_eq!(
+                entry.unixtime_in_ms,
+                days_from_civil(entry.year, 3, 5) * MILLISECONDS_PER_DAY_INT
+            );
+            assert!((entry.day_length_ms - entries[3].day_length_ms).abs() < 5 * 60 * 1_000);
+        }
+        let leap_days = compare_years(2, 29, 1896..=1912, 0.0, 0.0);
+        let years: Vec<_> = leap_days.iter().map(|entry| entry.year).collect();
+        assert_eq!(years, [1896, 1904, 1908, 1912]);
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_format_local() {
+        let calendar = month_calendar(2013, 3, 50.5, 30.5);
+        let local = calendar.days[4].format_local(chrono_tz::Europe::Kiev, "%H:%M");
+        assert_eq!(local.sunrise.as_deref(), Some("06:34"));
+    }
+}
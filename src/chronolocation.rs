@@ -0,0 +1,343 @@
+//! Inverse solvers that derive the time or the latitude from an observed shadow,
+//! e.g. to verify when or where a photo was taken, or the time from the azimuth
+//! of the sun, like reading a solar compass in reverse. [`position_matches`] finds the
+//! days on which the sun returns to the position of a reference photo.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::chronolocation::{times_from_shadow, ShadowObservation};
+//!
+//! // a pole casts a shadow 1.5 times its height towards the north-east
+//! let observation = ShadowObservation {
+//!     azimuth_deg: Some(45.0),
+//!     length_ratio: Some(1.5),
+//! };
+//! let times = times_from_shadow(1_371_772_800_000, 48.0, 9.0, observation, 2.0);
+//! for time in times {
+//!     println!("the photo was taken around {time}");
+//! }
+//! ```
+
+use std::f64::consts::PI;
+
+use crate::{pos, Position, MILLISECONDS_PER_DAY_INT};
+
+const STEP_MS: i64 = 60 * 1_000;
+const LATITUDE_STEP: f64 = 0.05;
+
+/// An observed shadow of a vertical object.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ShadowObservation {
+    /// Direction in which the shadow points in degrees clockwise from north.
+    pub azimuth_deg: Option<f64>,
+    /// Length of the shadow divided by the height of the object.
+    pub length_ratio: Option<f64>,
+}
+
+impl ShadowObservation {
+    /// Largest deviation in degrees between the observation and the shadow of the sun at `pos`,
+    /// `None` if the sun is below the horizon.
+    #[must_use]
+    pub fn residual(&self, pos: Position) -> Option<f64> {
+        if pos.altitude <= 0.0 {
+            return None;
+        }
+        let azimuth = self.azimuth_deg.map_or(0.0, |azimuth| {
+            let shadow = (pos.azimuth + PI).to_degrees();
+            ((shadow - azimuth + 180.0).rem_euclid(360.0) - 180.0).abs()
+        });
+        let altitude = self.length_ratio.map_or(0.0, |ratio| {
+            let observed = (1.0 / ratio).atan().to_degrees();
+            (pos.altitude.to_degrees() - observed).abs()
+        });
+        Some(azimuth.max(altitude))
+    }
+}
+
+/// Finds the times within the UTC day of `unixtime_in_ms` at which the sun casts a shadow
+/// matching the observation within `tolerance_deg` at the given location.
+///
+/// Every returned time is a best match, i.e. a local minimum of the deviation.
+#[must_use]
+pub fn times_from_shadow(
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    observation: ShadowObservation,
+    tolerance_deg: f64,
+) -> Vec<i64> {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let residual = |time: i64| {
+        observation
+            .residual(pos(time, lat, lon))
+            .unwrap_or(f64::INFINITY)
+    };
+    let samples: Vec<(i64, f64)> = (0..=MILLISECONDS_PER_DAY_INT / STEP_MS)
+        .map(|i| {
+            let time = start + i * STEP_MS;
+            (time, residual(time))
+        })
+        .collect();
+
+    let mut times = Vec::new();
+    for window in samples.windows(3) {
+        let [(_, before), (time, value), (_, after)] = [window[0], window[1], window[2]];
+        if value <= tolerance_deg && value <= before && value < after {
+            times.push(refine(time - STEP_MS, time + STEP_MS, residual));
+        }
+    }
+    times
+}
+
+/// Finds the times within the UTC day of `unixtime_in_ms` at which the sun is above the
+/// horizon at the given azimuth in degrees clockwise from north.
+///
+/// Usually there is one such time; close to the tropics the sun can pass an azimuth
+/// more than once.
+///
+/// # Examples
+///
+/// ```rust
+/// // when is the sun due south in Stuttgart on 2013-06-21?
+/// let times = sun::chronolocation::times_at_azimuth(1_371_772_800_000, 48.8, 9.2, 180.0);
+/// assert_eq!(times.len(), 1);
+/// ```
+#[must_use]
+pub fn times_at_azimuth(unixtime_in_ms: i64, lat: f64, lon: f64, azimuth_deg: f64) -> Vec<i64> {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let delta = |time: i64| {
+        let sun = pos(time, lat, lon);
+        (
+            (sun.azimuth.to_degrees() - azimuth_deg + 180.0).rem_euclid(360.0) - 180.0,
+            sun,
+        )
+    };
+    let mut times = Vec::new();
+    let (mut previous, _) = delta(start);
+    for i in 1..=MILLISECONDS_PER_DAY_INT / STEP_MS {
+        let time = start + i * STEP_MS;
+        let (current, sun) = delta(time);
+        // a jump between +180° and -180° is the opposite azimuth, not a crossing
+        if (previous < 0.0) != (current < 0.0) && (current - previous).abs() < 180.0 {
+            let crossing = bisect(time - STEP_MS, time, |t| delta(t).0);
+            if sun.altitude > 0.0 || pos(crossing, lat, lon).altitude > 0.0 {
+                times.push(crossing);
+            }
+        }
+        previous = current;
+    }
+    times
+}
+
+/// A time at which the sun returns close to a reference position, see [`position_matches`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionMatch {
+    /// [Unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Angle between the sun and the reference position in degrees.
+    pub deviation_deg: f64,
+}
+
+/// Finds the times on `days` UTC days from the day of `start_in_ms` at which the sun
+/// stands within `tolerance_deg` of `reference`, e.g. to recreate a photo or to match
+/// the shadows in it.
+///
+/// * `reference` - position of the sun in the reference, e.g. [`pos`] at the time of the
+///   photo.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// Each day contributes at most the closest approach of the sun per pass through the
+/// azimuth of the reference. The sun returns to a position twice a year at the same
+/// declination, so the matches form windows around two dates, or one around a solstice.
+#[must_use]
+pub fn position_matches(
+    reference: Position,
+    start_in_ms: i64,
+    days: u32,
+    lat: f64,
+    lon: f64,
+    tolerance_deg: f64,
+) -> Vec<PositionMatch> {
+    let deviation = |time: i64| pos(time, lat, lon).separation(reference);
+    let azimuth_deg = reference.azimuth.to_degrees().rem_euclid(360.0);
+    (0..i64::from(days))
+        .flat_map(|day| {
+            times_at_azimuth(
+                start_in_ms + day * MILLISECONDS_PER_DAY_INT,
+                lat,
+                lon,
+                azimuth_deg,
+            )
+        })
+        .map(|time| {
+            // the closest approach is near the crossing of the azimuth
+            let unixtime_in_ms = refine(time - 30 * STEP_MS, time + 30 * STEP_MS, deviation);
+            PositionMatch {
+                unixtime_in_ms,
+                deviation_deg: deviation(unixtime_in_ms).to_degrees(),
+            }
+        })
+        .filter(|found| found.deviation_deg <= tolerance_deg)
+        .collect()
+}
+
+/// Narrows a sign change of `f` in `[low, high]` down to a second by bisection.
+fn bisect(mut low: i64, mut high: i64, f: impl Fn(i64) -> f64) -> i64 {
+    let negative = f(low) < 0.0;
+    while high - low > 1_000 {
+        let middle = (low + high) / 2;
+        if (f(middle) < 0.0) == negative {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2
+}
+
+/// Narrows a minimum of `f` in `[low, high]` down to a second by ternary search.
+fn refine(mut low: i64, mut high: i64, f: impl Fn(i64) -> f64) -> i64 {
+    while high - low > 1_000 {
+        let third = (high - low) / 3;
+        if f(low + third) < f(high - third) {
+            high -= third;
+        } else {
+            low += third;
+        }
+    }
+    (low + high) / 2
+}
+
+/// Finds the bands of latitudes (south, north) in degrees in which the sun casts a shadow
+/// matching the observation within `tolerance_deg` at a known time and longitude.
+#[must_use]
+pub fn latitudes_from_shadow(
+    unixtime_in_ms: i64,
+    lon: f64,
+    observation: ShadowObservation,
+    tolerance_deg: f64,
+) -> Vec<(f64, f64)> {
+    let mut bands = Vec::new();
+    let mut open: Option<f64> = None;
+    let mut previous = -90.0;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let steps = (180.0 / LATITUDE_STEP).round() as usize;
+    for i in 0..=steps {
+        #[allow(clippy::cast_precision_loss)]
+        let lat = -90.0 + i as f64 * LATITUDE_STEP;
+        let matches = observation
+            .residual(pos(unixtime_in_ms, lat, lon))
+            .is_some_and(|residual| residual <= tolerance_deg);
+        match (matches, open) {
+            (true, None) => open = Some(lat),
+            (false, Some(south)) => {
+                bands.push((south, previous));
+                open = None;
+            }
+            _ => {}
+        }
+        previous = lat;
+    }
+    if let Some(south) = open {
+        bands.push((south, previous));
+    }
+    bands
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn observe(time: i64, lat: f64, lon: f64) -> ShadowObservation {
+        let sun = pos(time, lat, lon);
+        ShadowObservation {
+            azimuth_deg: Some((sun.azimuth + PI).to_degrees().rem_euclid(360.0)),
+            length_ratio: Some(1.0 / sun.altitude.tan()),
+        }
+    }
+
+    #[test]
+    fn test_times_from_shadow() {
+        // 2013-06-21 09:30 UTC
+        let time = 1_371_807_000_000;
+        let observation = observe(time, 48.0, 9.0);
+        let times = times_from_shadow(time, 48.0, 9.0, observation, 0.5);
+        assert_eq!(times.len(), 1);
+        assert!((times[0] - time).abs() < 10_000);
+        // the length alone matches in the morning and in the afternoon
+        let length_only = ShadowObservation {
+            azimuth_deg: None,
+            ..observation
+        };
+        assert_eq!(
+            times_from_shadow(time, 48.0, 9.0, length_only, 0.5).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_latitudes_from_shadow() {
+        let time = 1_371_807_000_000;
+        let bands = latitudes_from_shadow(time, 9.0, observe(time, 48.0, 9.0), 0.5);
+        assert_eq!(bands.len(), 1);
+        let (south, north) = bands[0];
+        assert!(south <= 48.0 && 48.0 <= north);
+        assert!(north - south < 2.0);
+    }
+
+    #[test]
+    fn test_times_at_azimuth() {
+        let time = 1_371_807_000_000;
+        let azimuth = pos(time, 48.0, 9.0).azimuth.to_degrees();
+        let times = times_at_azimuth(time, 48.0, 9.0, azimuth);
+        assert_eq!(times.len(), 1);
+        assert!((times[0] - time).abs() < 2_000);
+        // the sun never stands in the north during a mid-latitude winter day
+        assert!(times_at_azimuth(1_387_584_000_000, 48.0, 9.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_position_matches() {
+        // 2013-04-15 10:00 UTC
+        let reference = pos(1_366_020_000_000, 48.0, 9.0);
+        // a year from 2014-01-01 UTC
+        let matches = position_matches(reference, 1_388_534_400_000, 365, 48.0, 9.0, 0.5);
+        let months: Vec<u32> = matches
+            .iter()
+            .map(|found| {
+                crate::calendar::civil_from_days(found.unixtime_in_ms / MILLISECONDS_PER_DAY_INT).1
+            })
+            .collect();
+        // the same declination in mid April and late August
+        assert!(months.contains(&4) && months.contains(&8));
+        assert!(months.iter().all(|month| matches!(month, 4 | 8)));
+        let best = matches
+            .iter()
+            .filter(|found| found.unixtime_in_ms < 1_400_000_000_000)
+            .min_by(|a, b| a.deviation_deg.total_cmp(&b.deviation_deg))
+            .unwrap();
+        assert!(best.deviation_deg < 0.2);
+        // a year later the sun returns at about the same time of day
+        let time_of_day = best.unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+        assert!((time_of_day - 10 * 60 * 60 * 1_000).abs() < 5 * 60 * 1_000);
+        // the sun never gets that low at noon in June
+        let low = Position {
+            azimuth: PI,
+            altitude: 10_f64.to_radians(),
+        };
+        assert!(position_matches(low, 1_401_580_800_000, 30, 48.0, 9.0, 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_residual_below_horizon() {
+        let observation = ShadowObservation::default();
+        let night = Position {
+            azimuth: 0.0,
+            altitude: -0.1,
+        };
+        assert!(observation.residual(night).is_none());
+    }
+}
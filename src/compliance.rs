@@ -0,0 +1,200 @@
+//! Checks of daylight requirements like "at least two hours of direct sun on the facade
+//! between 09:00 and 17:00 on March 21st", as found in building regulations.
+//!
+//! A point receives direct sun when the center of the sun is above the obstructions
+//! around it and, for a surface, in front of the surface.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::compliance::{check_daylight, DaylightRule};
+//! use sun::slope::Slope;
+//!
+//! let rule = DaylightRule {
+//!     year: 2024,
+//!     month: 3,
+//!     day: 21,
+//!     from_minute: 9 * 60,
+//!     to_minute: 17 * 60,
+//!     utc_offset_minutes: 60,
+//!     required_minutes: 2 * 60,
+//!     continuous: false,
+//! };
+//! // a facade facing south-east without obstructions
+//! let facade = Slope {
+//!     slope_deg: 90.0,
+//!     aspect_deg: 135.0,
+//! };
+//! let result = check_daylight(&rule, 48.1, 11.6, Some(facade), &|_| 0.0);
+//! assert!(result.passed);
+//! ```
+
+use std::f64::consts::FRAC_PI_2;
+
+use crate::calendar::days_from_civil;
+use crate::slope::Slope;
+use crate::terrain::HorizonProvider;
+use crate::{observed_pos, AltitudeKind, MILLISECONDS_PER_DAY_INT};
+
+const MILLISECONDS_PER_MINUTE: i64 = 60 * 1_000;
+
+/// A requirement of direct sun within a period of a day in local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DaylightRule {
+    /// Year of the day.
+    pub year: i32,
+    /// Month of the day, 1 for January to 12 for December.
+    pub month: u32,
+    /// Day of the month.
+    pub day: u32,
+    /// Start of the period in minutes after local midnight.
+    pub from_minute: u32,
+    /// End of the period in minutes after local midnight.
+    pub to_minute: u32,
+    /// Offset of the local time from UTC in minutes.
+    pub utc_offset_minutes: i32,
+    /// Minutes of direct sun needed to pass.
+    pub required_minutes: u32,
+    /// Whether the minutes have to be reached in a single interval.
+    pub continuous: bool,
+}
+
+/// Outcome of [`check_daylight`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplianceResult {
+    /// Whether the rule is met.
+    pub passed: bool,
+    /// Minutes of direct sun within the period.
+    pub sunlit_minutes: u32,
+    /// Intervals of direct sun as pairs of [unix times](https://en.wikipedia.org/wiki/Unix_time)
+    /// in milliseconds.
+    pub intervals: Vec<(i64, i64)>,
+}
+
+/// Checks a daylight rule for a point with a resolution of one minute.
+///
+/// * `rule`         - requirement to check.
+/// * `lat`          - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`          - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `orientation`  - orientation of the surface, e.g. a vertical facade, or `None` for
+///   a point that receives the sun from all directions.
+/// * `obstructions` - horizon formed by the surrounding buildings and terrain, e.g.
+///   `&|_| 0.0` for a free horizon.
+#[must_use]
+pub fn check_daylight(
+    rule: &DaylightRule,
+    lat: f64,
+    lon: f64,
+    orientation: Option<Slope>,
+    obstructions: &impl HorizonProvider,
+) -> ComplianceResult {
+    let midnight = days_from_civil(rule.year, rule.month, rule.day) * MILLISECONDS_PER_DAY_INT
+        - i64::from(rule.utc_offset_minutes) * MILLISECONDS_PER_MINUTE;
+    let sunlit = |time: i64| {
+        let sun = observed_pos(time, lat, lon);
+        let in_front = orientation.is_none_or(|surface| {
+            surface.incidence(sun.position(AltitudeKind::Apparent)) < FRAC_PI_2
+        });
+        sun.apparent_altitude > obstructions.horizon_altitude(sun.azimuth).max(0.0) && in_front
+    };
+
+    let mut intervals = Vec::new();
+    let mut open: Option<i64> = None;
+    let mut sunlit_minutes = 0;
+    for minute in rule.from_minute..rule.to_minute {
+        let start = midnight + i64::from(minute) * MILLISECONDS_PER_MINUTE;
+        if sunlit(start + MILLISECONDS_PER_MINUTE / 2) {
+            sunlit_minutes += 1;
+            open.get_or_insert(start);
+        } else if let Some(from) = open.take() {
+            intervals.push((from, start));
+        }
+    }
+    if let Some(from) = open {
+        intervals.push((
+            from,
+            midnight + i64::from(rule.to_minute) * MILLISECONDS_PER_MINUTE,
+        ));
+    }
+
+    let achieved = if rule.continuous {
+        intervals
+            .iter()
+            .map(|(from, to)| (to - from) / MILLISECONDS_PER_MINUTE)
+            .max()
+            .unwrap_or(0)
+    } else {
+        i64::from(sunlit_minutes)
+    };
+    ComplianceResult {
+        passed: achieved >= i64::from(rule.required_minutes),
+        sunlit_minutes,
+        intervals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    const RULE: DaylightRule = DaylightRule {
+        year: 2024,
+        month: 3,
+        day: 21,
+        from_minute: 9 * 60,
+        to_minute: 17 * 60,
+        utc_offset_minutes: 60,
+        required_minutes: 2 * 60,
+        continuous: false,
+    };
+
+    #[test]
+    fn test_free_horizon() {
+        let result = check_daylight(&RULE, 48.1, 11.6, None, &|_| 0.0);
+        assert!(result.passed);
+        assert_eq!(result.sunlit_minutes, 8 * 60);
+        // 2024-03-21 08:00 to 16:00 UTC
+        assert_eq!(result.intervals, [(1_711_008_000_000, 1_711_036_800_000)]);
+        // a facade facing north doesn't see the sun in March
+        let north = Slope {
+            slope_deg: 90.0,
+            aspect_deg: 0.0,
+        };
+        let result = check_daylight(&RULE, 48.1, 11.6, Some(north), &|_| 0.0);
+        assert!(!result.passed);
+        assert_eq!(result.sunlit_minutes, 0);
+        assert!(result.intervals.is_empty());
+    }
+
+    #[test]
+    fn test_obstructions() {
+        // a tower blocks the sky between south-east and south up to 45°
+        let tower = |azimuth: f64| {
+            if (3.0 * PI / 4.0..PI).contains(&azimuth) {
+                PI / 4.0
+            } else {
+                0.0
+            }
+        };
+        let facade = Slope {
+            slope_deg: 90.0,
+            aspect_deg: 250.0,
+        };
+        let result = check_daylight(&RULE, 48.1, 11.6, Some(facade), &tower);
+        assert_eq!(result.intervals.len(), 1);
+        assert!(result.passed);
+        let continuous = DaylightRule {
+            required_minutes: 5 * 60,
+            continuous: true,
+            ..RULE
+        };
+        let result = check_daylight(&continuous, 48.1, 11.6, None, &tower);
+        // the tower splits the day into a morning and an afternoon interval
+        assert_eq!(result.intervals.len(), 2);
+        assert!(result.sunlit_minutes >= 5 * 60);
+        assert!(!result.passed);
+    }
+}
@@ -0,0 +1,135 @@
+//! Azimuth conventions for interoperability with other libraries.
+//!
+//! [`pos`](crate::pos) measures the azimuth clockwise from north.
+//! Other libraries use different origins and directions, which easily leads
+//! to results that are off by 90° or 180°. [`pos_with_convention`] returns
+//! positions that carry their convention along.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::convention::{pos_with_convention, AzimuthConvention};
+//!
+//! let pos = pos_with_convention(1_362_441_600_000, 48.0, 9.0, AzimuthConvention::SouthClockwise);
+//! let north = pos.to_convention(AzimuthConvention::NorthClockwise);
+//! assert!((north.azimuth - sun::pos(1_362_441_600_000, 48.0, 9.0).azimuth).abs() < 1e-12);
+//! ```
+
+use std::f64::consts::PI;
+
+use crate::pos;
+
+/// Origin and direction of azimuth angles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AzimuthConvention {
+    /// 0 is north, increasing towards east (compass bearing), as returned by [`pos`](crate::pos).
+    #[default]
+    NorthClockwise,
+    /// 0 is south, increasing towards west, as used by suncalc.
+    SouthClockwise,
+    /// 0 is east, increasing towards north (mathematical orientation).
+    EastCounterclockwise,
+}
+
+impl AzimuthConvention {
+    /// Converts an azimuth in radians measured clockwise from north into this convention.
+    /// The result is normalized to `[0, 2π)`.
+    #[must_use]
+    pub fn from_north_clockwise(self, azimuth: f64) -> f64 {
+        match self {
+            AzimuthConvention::NorthClockwise => azimuth,
+            AzimuthConvention::SouthClockwise => azimuth - PI,
+            AzimuthConvention::EastCounterclockwise => PI / 2.0 - azimuth,
+        }
+        .rem_euclid(2.0 * PI)
+    }
+
+    /// Converts an azimuth in radians in this convention into an azimuth measured
+    /// clockwise from north. The result is normalized to `[0, 2π)`.
+    #[must_use]
+    pub fn to_north_clockwise(self, azimuth: f64) -> f64 {
+        match self {
+            AzimuthConvention::NorthClockwise => azimuth,
+            AzimuthConvention::SouthClockwise => azimuth + PI,
+            AzimuthConvention::EastCounterclockwise => PI / 2.0 - azimuth,
+        }
+        .rem_euclid(2.0 * PI)
+    }
+}
+
+/// A sun position whose azimuth is given in an explicit [`AzimuthConvention`].
+#[derive(Debug, Clone, Copy)]
+pub struct ConventionalPosition {
+    /// Azimuth in radians according to `convention`.
+    pub azimuth: f64,
+    /// Altitude in radians.
+    pub altitude: f64,
+    /// Convention of `azimuth`.
+    pub convention: AzimuthConvention,
+}
+
+impl ConventionalPosition {
+    /// Returns the same position with the azimuth converted into another convention.
+    #[must_use]
+    pub fn to_convention(self, convention: AzimuthConvention) -> Self {
+        let north = self.convention.to_north_clockwise(self.azimuth);
+        Self {
+            azimuth: convention.from_north_clockwise(north),
+            altitude: self.altitude,
+            convention,
+        }
+    }
+}
+
+/// Calculates the sun position like [`pos`](crate::pos) with the azimuth in the given convention.
+#[must_use]
+pub fn pos_with_convention(
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    convention: AzimuthConvention,
+) -> ConventionalPosition {
+    let position = pos(unixtime_in_ms, lat, lon);
+    ConventionalPosition {
+        azimuth: convention.from_north_clockwise(position.azimuth),
+        altitude: position.altitude,
+        convention,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_conventions() {
+        let east = PI / 2.0;
+        let south_clockwise = AzimuthConvention::SouthClockwise.from_north_clockwise(east);
+        assert!((south_clockwise - 3.0 * PI / 2.0).abs() < 1e-12);
+        let math = AzimuthConvention::EastCounterclockwise.from_north_clockwise(east);
+        assert!(math.abs() < 1e-12);
+        let north = AzimuthConvention::EastCounterclockwise.from_north_clockwise(0.0);
+        assert!((north - PI / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let pos = pos_with_convention(
+            1_362_441_600_000,
+            50.5,
+            30.5,
+            AzimuthConvention::SouthClockwise,
+        );
+        for convention in [
+            AzimuthConvention::NorthClockwise,
+            AzimuthConvention::EastCounterclockwise,
+        ] {
+            let back = pos
+                .to_convention(convention)
+                .to_convention(AzimuthConvention::SouthClockwise);
+            assert!((back.azimuth - pos.azimuth).abs() < 1e-12);
+            assert_eq!(back.convention, AzimuthConvention::SouthClockwise);
+        }
+    }
+}
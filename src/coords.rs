@@ -0,0 +1,188 @@
+//! Transforms between horizontal and equatorial coordinates and angular separations, e.g.
+//! to combine the sun with the stars of a catalog.
+//!
+//! All angles are in radians. Azimuths are measured clockwise from north like in
+//! [`pos`](crate::pos), right ascensions and declinations refer to the same equinox as
+//! the solar model, J2000.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::coords::{equatorial_to_horizontal, Equatorial};
+//!
+//! // Sirius
+//! let sirius = Equatorial {
+//!     right_ascension: 101.287_f64.to_radians(),
+//!     declination: (-16.716_f64).to_radians(),
+//! };
+//! let star = equatorial_to_horizontal(sirius, 1_362_441_600_000, 48.0, 9.0);
+//! let sun = sun::pos(1_362_441_600_000, 48.0, 9.0);
+//! println!("Sirius is {:.1}° from the sun", star.separation(sun).to_degrees());
+//! ```
+
+use core::f64::consts::PI;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+use crate::{sidereal_time, to_days, Position};
+
+/// A direction in equatorial coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equatorial {
+    /// [Right ascension](https://en.wikipedia.org/wiki/Right_ascension) in radians.
+    pub right_ascension: f64,
+    /// [Declination](https://en.wikipedia.org/wiki/Declination) in radians.
+    pub declination: f64,
+}
+
+impl Equatorial {
+    /// Calculates the angle to another direction in radians.
+    #[must_use]
+    pub fn separation(&self, other: Equatorial) -> f64 {
+        angular_separation(
+            self.right_ascension,
+            self.declination,
+            other.right_ascension,
+            other.declination,
+        )
+    }
+}
+
+impl Position {
+    /// Calculates the angle to another position in the sky in radians.
+    #[must_use]
+    pub fn separation(&self, other: Position) -> f64 {
+        angular_separation(self.azimuth, self.altitude, other.azimuth, other.altitude)
+    }
+}
+
+/// Calculates the angle in radians between two directions given as longitude and
+/// latitude in radians of any spherical system, e.g. azimuth and altitude, right
+/// ascension and declination, or longitude and latitude on the earth.
+///
+/// Uses the haversine formula, which stays accurate for small angles.
+#[must_use]
+pub fn angular_separation(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let haversine = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * haversine.sqrt().clamp(0.0, 1.0).asin()
+}
+
+/// Calculates the local sidereal time in radians, in `[0, 2π)`.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub fn local_sidereal_time(unixtime_in_ms: i64, lon: f64) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    sidereal_time(days, -lon.to_radians()).rem_euclid(2.0 * PI)
+}
+
+/// Transforms equatorial coordinates into the horizontal coordinates of an observer.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub fn equatorial_to_horizontal(
+    equatorial: Equatorial,
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+) -> Position {
+    let hour_angle = local_sidereal_time(unixtime_in_ms, lon) - equatorial.right_ascension;
+    let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+    let (sin_dec, cos_dec) = equatorial.declination.sin_cos();
+    let altitude = (sin_lat * sin_dec + cos_lat * cos_dec * hour_angle.cos())
+        .clamp(-1.0, 1.0)
+        .asin();
+    let azimuth = (-hour_angle.sin() * cos_dec)
+        .atan2(cos_lat * sin_dec - sin_lat * cos_dec * hour_angle.cos());
+    Position {
+        azimuth: azimuth.rem_euclid(2.0 * PI),
+        altitude,
+    }
+}
+
+/// Transforms the horizontal coordinates of an observer into equatorial coordinates.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub fn horizontal_to_equatorial(
+    position: Position,
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+) -> Equatorial {
+    let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+    let (sin_alt, cos_alt) = position.altitude.sin_cos();
+    let declination = (sin_lat * sin_alt + cos_lat * cos_alt * position.azimuth.cos())
+        .clamp(-1.0, 1.0)
+        .asin();
+    let hour_angle = (-position.azimuth.sin() * cos_alt)
+        .atan2(cos_lat * sin_alt - sin_lat * cos_alt * position.azimuth.cos());
+    Equatorial {
+        right_ascension: (local_sidereal_time(unixtime_in_ms, lon) - hour_angle)
+            .rem_euclid(2.0 * PI),
+        declination,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_angular_separation() {
+        let pole = Position {
+            azimuth: 1.0,
+            altitude: PI / 2.0,
+        };
+        let horizon = Position {
+            azimuth: 2.0,
+            altitude: 0.0,
+        };
+        assert!((pole.separation(horizon) - PI / 2.0).abs() < 1e-12);
+        // an arc second stays an arc second
+        let arc_second = (1.0 / 3_600_f64).to_radians();
+        let a = Equatorial {
+            right_ascension: 1.0,
+            declination: 0.5,
+        };
+        let b = Equatorial {
+            declination: 0.5 + arc_second,
+            ..a
+        };
+        assert!((a.separation(b) - arc_second).abs() < 1e-15);
+        assert!((angular_separation(0.0, 0.0, PI, 0.0) - PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let time = 1_362_441_600_000;
+        let geometry = crate::solar_geometry(time, 9.0);
+        let sun = Equatorial {
+            right_ascension: geometry.right_ascension,
+            declination: geometry.declination,
+        };
+        let position = equatorial_to_horizontal(sun, time, 48.0, 9.0);
+        // the same as the position of the sun
+        let tolerance = if cfg!(feature = "fast-math") {
+            1e-4
+        } else {
+            1e-9
+        };
+        assert!(position.separation(crate::pos(time, 48.0, 9.0)) < tolerance);
+        let back = horizontal_to_equatorial(position, time, 48.0, 9.0);
+        assert!(back.separation(sun) < 1e-9);
+        assert!(
+            (local_sidereal_time(time, 9.0) - geometry.hour_angle - sun.right_ascension)
+                .rem_euclid(2.0 * PI)
+                < 1e-9
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Visibility of the young lunar crescent after
+//! [Yallop (1997)](https://webspace.science.uu.nl/~gent0113/islam/downloads/naotn69.pdf).
+//!
+//! Lunar calendars start a new month with the first sighting of the crescent
+//! after new moon. Yallop's `q`-criterion estimates that visibility from the
+//! geometry of sun and moon at the "best time" shortly after sunset.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::crescent::crescent_visibility;
+//!
+//! // 2013-03-12, one day after new moon
+//! if let Some(visibility) = crescent_visibility(1_363_046_400_000, 21.4, 39.8) {
+//!     println!("{:?} (q = {:.3})", visibility.category, visibility.q);
+//! }
+//! ```
+
+use crate::moon::{moon_horizontal, moon_times};
+use crate::{pos, try_time_at_phase, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+const EARTH_RADIUS_IN_KM: f64 = 6_378.14;
+
+/// Visibility categories of Yallop's criterion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VisibilityCategory {
+    /// Easily visible to the naked eye.
+    A,
+    /// Visible under perfect conditions.
+    B,
+    /// May need optical aid to find the crescent.
+    C,
+    /// Will need optical aid to find the crescent.
+    D,
+    /// Not visible with a telescope.
+    E,
+    /// Not visible, below the Danjon limit.
+    F,
+}
+
+impl VisibilityCategory {
+    /// Classifies a `q` value.
+    #[must_use]
+    pub fn from_q(q: f64) -> Self {
+        if q > 0.216 {
+            VisibilityCategory::A
+        } else if q > -0.014 {
+            VisibilityCategory::B
+        } else if q > -0.160 {
+            VisibilityCategory::C
+        } else if q > -0.232 {
+            VisibilityCategory::D
+        } else if q > -0.293 {
+            VisibilityCategory::E
+        } else {
+            VisibilityCategory::F
+        }
+    }
+}
+
+/// Result of the crescent visibility estimate.
+#[derive(Debug, Clone, Copy)]
+pub struct CrescentVisibility {
+    /// Yallop's `q` value.
+    pub q: f64,
+    /// Visibility category derived from `q`.
+    pub category: VisibilityCategory,
+    /// Best time for the observation as [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    pub best_time: i64,
+    /// Arc of vision, the altitude difference of moon and sun, in degrees.
+    pub arcv: f64,
+    /// Arc of light, the elongation of the moon from the sun, in degrees.
+    pub arcl: f64,
+    /// Topocentric width of the crescent in arc minutes.
+    pub width: f64,
+}
+
+/// Estimates the visibility of the lunar crescent on the evening of the UTC day
+/// of `unixtime_in_ms`.
+///
+/// Returns `None` if the sun does not set or the moon sets before the sun.
+#[must_use]
+pub fn crescent_visibility(unixtime_in_ms: i64, lat: f64, lon: f64) -> Option<CrescentVisibility> {
+    let sunset = try_time_at_phase(unixtime_in_ms, SunPhase::Sunset, lat, lon, 0.0).time()?;
+    let moonset = [sunset, sunset + MILLISECONDS_PER_DAY_INT]
+        .into_iter()
+        .filter_map(|day| moon_times(day, lat, lon).set)
+        .find(|set| *set > sunset)?;
+    if moonset - sunset > MILLISECONDS_PER_DAY_INT / 2 {
+        // the moon is not a young crescent in the evening sky
+        return None;
+    }
+    let best_time = sunset + (moonset - sunset) * 4 / 9;
+
+    let sun = pos(best_time, lat, lon);
+    let (moon_azimuth, moon_altitude, moon) = moon_horizontal(best_time, lat, lon);
+    let arc_of_vision = (moon_altitude - sun.altitude).to_degrees();
+    let daz = moon_azimuth - sun.azimuth;
+    let elongation = (sun.altitude.sin() * moon_altitude.sin()
+        + sun.altitude.cos() * moon_altitude.cos() * daz.cos())
+    .clamp(-1.0, 1.0)
+    .acos();
+
+    // semi-diameter in arc minutes, corrected to the observer
+    let parallax = (EARTH_RADIUS_IN_KM / moon.distance).asin().to_degrees() * 60.0;
+    let semi_diameter = 0.272_45 * parallax;
+    let topocentric =
+        semi_diameter * (1.0 + moon_altitude.sin() * parallax.to_radians().sin() / 60.0);
+    let width = topocentric * (1.0 - elongation.cos());
+
+    let q = (arc_of_vision
+        - (11.837_1 - 6.322_6 * width + 0.731_9 * width.powi(2) - 0.101_8 * width.powi(3)))
+        / 10.0;
+    Some(CrescentVisibility {
+        q,
+        category: VisibilityCategory::from_q(q),
+        best_time,
+        arcv: arc_of_vision,
+        arcl: elongation.to_degrees(),
+        width,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_categories() {
+        assert_eq!(VisibilityCategory::from_q(0.5), VisibilityCategory::A);
+        assert_eq!(VisibilityCategory::from_q(0.0), VisibilityCategory::B);
+        assert_eq!(VisibilityCategory::from_q(-0.1), VisibilityCategory::C);
+        assert_eq!(VisibilityCategory::from_q(-0.2), VisibilityCategory::D);
+        assert_eq!(VisibilityCategory::from_q(-0.25), VisibilityCategory::E);
+        assert_eq!(VisibilityCategory::from_q(-1.0), VisibilityCategory::F);
+    }
+
+    #[test]
+    fn test_crescent_after_new_moon() {
+        // new moon on 2013-03-11 19:51 UTC, observed from Mecca
+        let day_after = crescent_visibility(1_363_046_400_000, 21.4, 39.8).unwrap();
+        let two_days_after = crescent_visibility(1_363_132_800_000, 21.4, 39.8).unwrap();
+        assert!(day_after.q < two_days_after.q);
+        assert!(day_after.width < two_days_after.width);
+        assert!(two_days_after.category <= VisibilityCategory::B);
+        // the day of the new moon itself
+        let same_day = crescent_visibility(1_362_960_000_000, 21.4, 39.8);
+        assert!(same_day.is_none_or(|v| v.category == VisibilityCategory::F));
+        // no sunset in the midnight sun of Longyearbyen
+        assert!(crescent_visibility(1_371_772_800_000, 78.2, 15.6).is_none());
+    }
+}
@@ -0,0 +1,288 @@
+//! A stream of sun events that can be persisted and resumed, e.g. by a service that
+//! triggers actions at the events and must not miss or repeat one after a restart.
+//!
+//! Events are ordered by their time and, for events at the same millisecond, by their
+//! phase, so the last emitted [`EventKey`] identifies the position in the stream exactly.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::cursor::{EventCursor, EventKey};
+//!
+//! let mut cursor = EventCursor::new(1_362_441_600_000, 48.0, 9.0);
+//! let first = cursor.next().unwrap();
+//! // store the key, e.g. in a database, and resume after a restart
+//! let key: EventKey = cursor.key();
+//! let mut resumed = EventCursor::resume(key, 48.0, 9.0);
+//! assert!(resumed.next().unwrap().unixtime_in_ms >= first.unixtime_in_ms);
+//! ```
+//!
+//! For a bounded period, e.g. all events of the next week, use [`events`].
+
+use std::iter::FusedIterator;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::times::{SunTimes, PHASES};
+use crate::{SunPhase, MILLISECONDS_PER_DAY_INT};
+
+/// Days searched for the next event before the stream ends.
+const MAX_DAYS: i64 = 366;
+
+/// Position in the event stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EventKey {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the event in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Index of the phase, from 0 for the end of the night to 11 for the start of the
+    /// night in the order of the day, followed by 12 for the end and 13 for the start of
+    /// the blue hour.
+    pub phase_index: u8,
+}
+
+/// A sun event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Event {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the event in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// The phase that starts.
+    pub phase: SunPhase,
+}
+
+/// Iterator over the sun events after a key, see the [module](self) documentation.
+#[derive(Debug, Clone)]
+pub struct EventCursor {
+    key: EventKey,
+    lat: f64,
+    lon: f64,
+    height: f64,
+}
+
+impl EventCursor {
+    /// Creates a cursor emitting the events after `unixtime_in_ms`.
+    ///
+    /// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    /// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    #[must_use]
+    pub const fn new(unixtime_in_ms: i64, lat: f64, lon: f64) -> Self {
+        // the last phase sorts after all events at the same time
+        Self::resume(
+            EventKey {
+                unixtime_in_ms,
+                phase_index: u8::MAX,
+            },
+            lat,
+            lon,
+        )
+    }
+
+    /// Creates a cursor emitting the events after the given key.
+    #[must_use]
+    pub const fn resume(key: EventKey, lat: f64, lon: f64) -> Self {
+        Self {
+            key,
+            lat,
+            lon,
+            height: 0.0,
+        }
+    }
+
+    /// Calculates the events for an observer `height` meters above the horizon.
+    #[must_use]
+    pub const fn with_height(self, height: f64) -> Self {
+        Self { height, ..self }
+    }
+
+    /// Key of the last emitted event, or the start of the stream if none was emitted.
+    #[must_use]
+    pub const fn key(&self) -> EventKey {
+        self.key
+    }
+
+    /// First event after the key among the events of the given UTC days.
+    fn first_after(&self, first_day: i64, last_day: i64) -> Option<EventKey> {
+        (first_day..=last_day)
+            .flat_map(|day| {
+                SunTimes::at_height(
+                    day * MILLISECONDS_PER_DAY_INT,
+                    self.lat,
+                    self.lon,
+                    self.height,
+                )
+                .phase_times()
+                .into_iter()
+                .zip(0..)
+                .filter_map(|(time, phase_index)| {
+                    time.map(|unixtime_in_ms| EventKey {
+                        unixtime_in_ms,
+                        phase_index,
+                    })
+                })
+            })
+            .filter(|&key| key > self.key)
+            .min()
+    }
+}
+
+impl Iterator for EventCursor {
+    type Item = Event;
+
+    /// Returns the next event, or `None` if no event occurs within a year, e.g. close
+    /// to the poles.
+    fn next(&mut self) -> Option<Event> {
+        let day = self.key.unixtime_in_ms.div_euclid(MILLISECONDS_PER_DAY_INT);
+        // events of a UTC day may lie on the neighbouring days
+        let key = self.first_after(day - 1, day + 1).or_else(|| {
+            (day + 2..day + MAX_DAYS).find_map(|next_day| self.first_after(next_day, next_day + 1))
+        })?;
+        self.key = key;
+        Some(Event {
+            unixtime_in_ms: key.unixtime_in_ms,
+            phase: PHASES[usize::from(key.phase_index)],
+        })
+    }
+}
+
+/// Iterator over the sun events of a period, see [`events`].
+#[derive(Debug, Clone)]
+pub struct Events {
+    cursor: EventCursor,
+    end_in_ms: i64,
+}
+
+impl Iterator for Events {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        if self.cursor.key().unixtime_in_ms > self.end_in_ms {
+            return None;
+        }
+        self.cursor
+            .next()
+            .filter(|event| event.unixtime_in_ms <= self.end_in_ms)
+    }
+}
+
+impl FusedIterator for Events {}
+
+/// Returns an iterator over the sun events from `start_in_ms` to `end_in_ms`, both
+/// included, in chronological order.
+///
+/// * `start_in_ms` - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the start in milliseconds.
+/// * `end_in_ms`   - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the end in milliseconds.
+/// * `lat`         - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`         - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`      - Observer height in meters above the horizon
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::cursor::events;
+/// use sun::SunPhase;
+///
+/// // the next week in Stuttgart
+/// let start = 1_362_441_600_000;
+/// let week = events(start, start + 7 * 24 * 60 * 60 * 1_000, 48.8, 9.2, 0.0);
+/// let sunrises = week.filter(|event| event.phase == SunPhase::Sunrise);
+/// assert_eq!(sunrises.count(), 7);
+/// ```
+#[must_use]
+pub const fn events(start_in_ms: i64, end_in_ms: i64, lat: f64, lon: f64, height: f64) -> Events {
+    Events {
+        // the cursor emits the events after its key
+        cursor: EventCursor::new(start_in_ms - 1, lat, lon).with_height(height),
+        end_in_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_events_are_ordered() {
+        // 2013-03-05 UTC, across the date line
+        let start = 1_362_441_600_000;
+        let events: Vec<Event> = EventCursor::new(start, -16.5, 179.9).take(42).collect();
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].unixtime_in_ms < pair[1].unixtime_in_ms));
+        assert!(events[0].unixtime_in_ms > start);
+        // three days with fourteen phases each
+        assert!(
+            events.last().unwrap().unixtime_in_ms - start
+                < 3 * MILLISECONDS_PER_DAY_INT + 12 * 60 * 60 * 1_000
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.phase == SunPhase::Sunrise)
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_resume() {
+        let mut cursor = EventCursor::new(1_362_441_600_000, 50.5, 30.5);
+        let expected: Vec<Event> = cursor.clone().take(10).collect();
+        let first: Vec<Event> = cursor.by_ref().take(4).collect();
+        let mut resumed = EventCursor::resume(cursor.key(), 50.5, 30.5);
+        let rest: Vec<Event> = resumed.by_ref().take(6).collect();
+        assert_eq!([first, rest].concat(), expected);
+        // polar night in Longyearbyen skips to the twilight phases
+        let polar = EventCursor::new(1_387_584_000_000, 78.2, 15.6)
+            .next()
+            .unwrap();
+        assert_eq!(polar.phase, SunPhase::NightEnd);
+    }
+
+    #[test]
+    fn test_stable_phase_indices() {
+        // keys persisted before the blue hour phases were added resolve to the same phases
+        let mut cursor = EventCursor::new(1_362_441_600_000, 50.5, 30.5);
+        let sunrise = cursor
+            .by_ref()
+            .find(|event| event.phase == SunPhase::Sunrise)
+            .unwrap();
+        assert_eq!(
+            cursor.key(),
+            EventKey {
+                unixtime_in_ms: sunrise.unixtime_in_ms,
+                phase_index: 3
+            }
+        );
+        assert_eq!(PHASES[11], SunPhase::Night);
+        assert_eq!(PHASES[12], SunPhase::BlueHourEnd(SunPhase::BLUE_HOUR_DEG));
+    }
+
+    #[test]
+    fn test_events() {
+        // 2013-03-05 UTC
+        let start = 1_362_441_600_000;
+        let end = start + 3 * MILLISECONDS_PER_DAY_INT;
+        let bounded: Vec<Event> = events(start, end, 50.5, 30.5, 0.0).collect();
+        let endless: Vec<Event> = EventCursor::new(start, 50.5, 30.5)
+            .take_while(|event| event.unixtime_in_ms <= end)
+            .collect();
+        assert_eq!(bounded, endless);
+        assert!(bounded
+            .iter()
+            .all(|event| (start..=end).contains(&event.unixtime_in_ms)));
+        // the start is included
+        let first = bounded[0].unixtime_in_ms;
+        assert_eq!(events(first, end, 50.5, 30.5, 0.0).next(), Some(bounded[0]));
+        // an elevated observer sees the sun rise earlier
+        let sunrise = |height| {
+            events(start, end, 50.5, 30.5, height)
+                .find(|event| event.phase == SunPhase::Sunrise)
+                .unwrap()
+                .unixtime_in_ms
+        };
+        assert!(sunrise(1_000.0) < sunrise(0.0));
+    }
+}
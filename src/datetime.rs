@@ -0,0 +1,291 @@
+//! Variants of [`pos`] and [`time_at_phase`](crate::time_at_phase) taking and returning
+//! other date types instead of unix times in milliseconds:
+//!
+//! * [`SystemTime`] of the standard library,
+//! * `DateTime<Utc>` of [chrono](https://docs.rs/chrono) with the `chrono` feature,
+//! * `OffsetDateTime` of [time](https://docs.rs/time) with the `time` feature.
+//!
+//! The phase times are `None` if the sun doesn't reach the altitude of the phase on that
+//! day, unlike [`time_at_phase`](crate::time_at_phase).
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "time")]
+use time::OffsetDateTime;
+
+use crate::{pos, try_time_at_phase, Position, SunPhase};
+
+/// Converts a [`SystemTime`] into a [unix time](https://en.wikipedia.org/wiki/Unix_time)
+/// in milliseconds, rounded down, also before 1970.
+#[must_use]
+pub fn unixtime_from_system_time(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => i64::try_from(after.as_millis()).unwrap_or(i64::MAX),
+        Err(error) => {
+            let before = error.duration().as_nanos().div_ceil(1_000_000);
+            i64::try_from(before).map_or(i64::MIN, |millis| -millis)
+        }
+    }
+}
+
+/// Converts a [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds into a
+/// [`SystemTime`], `None` if the platform can't represent it.
+#[must_use]
+pub fn system_time_from_unixtime(unixtime_in_ms: i64) -> Option<SystemTime> {
+    let offset = Duration::from_millis(unixtime_in_ms.unsigned_abs());
+    if unixtime_in_ms < 0 {
+        UNIX_EPOCH.checked_sub(offset)
+    } else {
+        UNIX_EPOCH.checked_add(offset)
+    }
+}
+
+/// Calculates the position of the sun at `time`, see [`pos`].
+///
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::SystemTime;
+///
+/// let position = sun::datetime::pos_at_system_time(SystemTime::now(), 48.0, 9.0);
+/// ```
+#[must_use]
+pub fn pos_at_system_time(time: SystemTime, lat: f64, lon: f64) -> Position {
+    pos(unixtime_from_system_time(time), lat, lon)
+}
+
+/// Calculates the time of a [`SunPhase`] on the day of `time`, see
+/// [`time_at_phase`](crate::time_at_phase).
+///
+/// Returns `None` if the sun doesn't reach the altitude of the phase on that day.
+///
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`    - Observer height in meters above the horizon
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::SystemTime;
+/// use sun::SunPhase;
+///
+/// let sunset = sun::datetime::time_at_phase_system_time(SystemTime::now(), SunPhase::Sunset, 48.0, 9.0, 0.0);
+/// ```
+#[must_use]
+pub fn time_at_phase_system_time(
+    time: SystemTime,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<SystemTime> {
+    try_time_at_phase(unixtime_from_system_time(time), sun_phase, lat, lon, height)
+        .time()
+        .and_then(system_time_from_unixtime)
+}
+
+/// Calculates the position of the sun at `date`, see [`pos`].
+///
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Examples
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+///
+/// let date = Utc.with_ymd_and_hms(2013, 3, 5, 0, 0, 0).unwrap();
+/// let position = sun::datetime::pos_at(date, 48.0, 9.0);
+/// ```
+#[cfg(feature = "chrono")]
+#[must_use]
+pub fn pos_at(date: DateTime<Utc>, lat: f64, lon: f64) -> Position {
+    pos(date.timestamp_millis(), lat, lon)
+}
+
+/// Calculates the time of a [`SunPhase`] on the day of `date`, see
+/// [`time_at_phase`](crate::time_at_phase).
+///
+/// Returns `None` if the sun doesn't reach the altitude of the phase on that day.
+///
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`    - Observer height in meters above the horizon
+///
+/// # Examples
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use sun::SunPhase;
+///
+/// let date = Utc.with_ymd_and_hms(2013, 3, 5, 0, 0, 0).unwrap();
+/// let sunrise = sun::datetime::time_at_phase_dt(date, SunPhase::Sunrise, 48.0, 9.0, 0.0);
+/// assert_eq!(sunrise.unwrap().timestamp_millis(), 1_362_463_116_241);
+/// ```
+#[cfg(feature = "chrono")]
+#[must_use]
+pub fn time_at_phase_dt(
+    date: DateTime<Utc>,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<DateTime<Utc>> {
+    try_time_at_phase(date.timestamp_millis(), sun_phase, lat, lon, height)
+        .time()
+        .and_then(DateTime::from_timestamp_millis)
+}
+
+/// Milliseconds since 1970-01-01 00:00 UTC of an `OffsetDateTime`, rounded down.
+#[cfg(feature = "time")]
+fn odt_millis(date: OffsetDateTime) -> i64 {
+    let millis = date.unix_timestamp_nanos().div_euclid(1_000_000);
+    i64::try_from(millis).unwrap_or(if millis < 0 { i64::MIN } else { i64::MAX })
+}
+
+/// Calculates the position of the sun at `date`, see [`pos`].
+///
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Examples
+///
+/// ```rust
+/// use time::macros::datetime;
+///
+/// let position = sun::datetime::pos_at_odt(datetime!(2013-03-05 01:00 +1), 48.0, 9.0);
+/// ```
+#[cfg(feature = "time")]
+#[must_use]
+pub fn pos_at_odt(date: OffsetDateTime, lat: f64, lon: f64) -> Position {
+    pos(odt_millis(date), lat, lon)
+}
+
+/// Calculates the time of a [`SunPhase`] on the day of `date`, see
+/// [`time_at_phase`](crate::time_at_phase).
+///
+/// The time has the same UTC offset as `date`. Returns `None` if the sun doesn't reach
+/// the altitude of the phase on that day.
+///
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`    - Observer height in meters above the horizon
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::SunPhase;
+/// use time::macros::datetime;
+///
+/// let date = datetime!(2013-03-05 01:00 +1);
+/// let sunrise = sun::datetime::time_at_phase_odt(date, SunPhase::Sunrise, 48.0, 9.0, 0.0);
+/// assert_eq!(sunrise.unwrap().offset(), date.offset());
+/// ```
+#[cfg(feature = "time")]
+#[must_use]
+pub fn time_at_phase_odt(
+    date: OffsetDateTime,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<OffsetDateTime> {
+    let millis = try_time_at_phase(odt_millis(date), sun_phase, lat, lon, height).time()?;
+    OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000)
+        .ok()
+        .map(|time| time.to_offset(date.offset()))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::time_at_phase;
+
+    #[test]
+    fn test_system_time() {
+        let date = UNIX_EPOCH + Duration::from_secs(1_362_441_600);
+        assert_eq!(unixtime_from_system_time(date), 1_362_441_600_000);
+        let position = pos_at_system_time(date, 50.5, 30.5);
+        let expected = pos(1_362_441_600_000, 50.5, 30.5);
+        assert!((position.azimuth - expected.azimuth).abs() < 1e-12);
+        let sunset = time_at_phase_system_time(date, SunPhase::Sunset, 50.5, 30.5, 0.0).unwrap();
+        assert_eq!(
+            unixtime_from_system_time(sunset),
+            time_at_phase(1_362_441_600_000, SunPhase::Sunset, 50.5, 30.5, 0.0)
+        );
+        // before 1970, 1969-07-20 20:17 UTC
+        let landing = system_time_from_unixtime(-14_182_980_000).unwrap();
+        assert_eq!(unixtime_from_system_time(landing), -14_182_980_000);
+        let rounded = UNIX_EPOCH - Duration::from_micros(1_500);
+        assert_eq!(unixtime_from_system_time(rounded), -2);
+        let sunrise = time_at_phase_system_time(landing, SunPhase::Sunrise, 28.6, -80.6, 0.0);
+        assert!(sunrise.unwrap() < landing);
+        let winter = system_time_from_unixtime(1_387_584_000_000).unwrap();
+        assert_eq!(
+            time_at_phase_system_time(winter, SunPhase::Sunrise, 78.2, 15.6, 0.0),
+            None
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_pos_at() {
+        use chrono::TimeZone;
+
+        let date = Utc.with_ymd_and_hms(2013, 3, 5, 0, 0, 0).unwrap();
+        let position = pos_at(date, 50.5, 30.5);
+        let expected = pos(1_362_441_600_000, 50.5, 30.5);
+        assert!((position.azimuth - expected.azimuth).abs() < 1e-12);
+        assert!((position.altitude - expected.altitude).abs() < 1e-12);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_time_at_phase_dt() {
+        use chrono::TimeZone;
+
+        let date = Utc.with_ymd_and_hms(2013, 3, 5, 0, 0, 0).unwrap();
+        let sunset = time_at_phase_dt(date, SunPhase::Sunset, 50.5, 30.5, 0.0).unwrap();
+        assert_eq!(
+            sunset.timestamp_millis(),
+            time_at_phase(1_362_441_600_000, SunPhase::Sunset, 50.5, 30.5, 0.0)
+        );
+        // polar night in Longyearbyen
+        let winter = Utc.with_ymd_and_hms(2013, 12, 21, 0, 0, 0).unwrap();
+        assert_eq!(
+            time_at_phase_dt(winter, SunPhase::Sunrise, 78.2, 15.6, 0.0),
+            None
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_odt() {
+        use time::macros::datetime;
+
+        // 2013-03-05 00:00 UTC in Kyiv
+        let date = datetime!(2013-03-05 02:00 +2);
+        let position = pos_at_odt(date, 50.5, 30.5);
+        let expected = pos(1_362_441_600_000, 50.5, 30.5);
+        assert!((position.azimuth - expected.azimuth).abs() < 1e-12);
+        let sunset = time_at_phase_odt(date, SunPhase::Sunset, 50.5, 30.5, 0.0).unwrap();
+        assert_eq!(
+            odt_millis(sunset),
+            time_at_phase(1_362_441_600_000, SunPhase::Sunset, 50.5, 30.5, 0.0)
+        );
+        assert_eq!(sunset.offset(), date.offset());
+        // before 1970 the milliseconds are rounded down
+        assert_eq!(odt_millis(datetime!(1969-12-31 23:59:59.9995 UTC)), -1);
+        let winter = datetime!(2013-12-21 00:00 UTC);
+        assert_eq!(
+            time_at_phase_odt(winter, SunPhase::Sunrise, 78.2, 15.6, 0.0),
+            None
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! [ΔT](https://en.wikipedia.org/wiki/%CE%94T_(timekeeping)), the difference between
+//! terrestrial time and universal time caused by the irregular rotation of the earth.
+//!
+//! Orbital positions are computed in terrestrial time while the rotation of the earth,
+//! and thus the local position of the sun, follows universal time. ΔT is about a minute
+//! today but amounts to hours in antiquity, which makes it the dominant error source for
+//! ancient dates, e.g. solstice alignments of prehistoric monuments.
+//!
+//! The values follow the polynomial expressions of Espenak and Meeus (2006), which are
+//! based on Morrison and Stephenson (2004). Years use astronomical numbering, i.e.
+//! 1 BC is the year 0 and 2500 BC is the year -2499, see [`Calendar`](crate::calendar::Calendar)
+//! to convert such dates.
+//!
+//! Note that the fast model of [`pos`](crate::pos) is only meant for dates within a few
+//! centuries of J2000; use the `reference` model for historical dates.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::delta_t::{delta_t, delta_t_uncertainty};
+//!
+//! // the ancient Egyptians of 2500 BC
+//! let year = -2499.0;
+//! println!("ΔT = {:.0} ± {:.0} s", delta_t(year), delta_t_uncertainty(year));
+//! ```
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+use crate::{to_julian, JULIAN_2000};
+
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Calculates the decimal year, e.g. `2013.17`, of a
+/// [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[must_use]
+pub fn decimal_year(unixtime_in_ms: i64) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_julian(unixtime_in_ms as f64) - JULIAN_2000;
+    2000.0 + days / DAYS_PER_YEAR
+}
+
+fn polynomial(t: f64, coefficients: &[f64]) -> f64 {
+    coefficients.iter().rev().fold(0.0, |sum, c| sum * t + c)
+}
+
+/// Calculates ΔT = TT - UT in seconds for a decimal year.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!((sun::delta_t::delta_t(2000.0) - 63.8).abs() < 0.1);
+/// ```
+#[must_use]
+#[allow(clippy::unreadable_literal)]
+pub fn delta_t(decimal_year: f64) -> f64 {
+    let y = decimal_year;
+    let long_term = |y: f64| -20.0 + 32.0 * ((y - 1820.0) / 100.0).powi(2);
+    match y {
+        y if y < -500.0 => long_term(y),
+        y if y < 500.0 => polynomial(
+            y / 100.0,
+            &[
+                10583.6,
+                -1014.41,
+                33.78311,
+                -5.952053,
+                -0.1798452,
+                0.022174192,
+                0.0090316521,
+            ],
+        ),
+        y if y < 1600.0 => polynomial(
+            (y - 1000.0) / 100.0,
+            &[
+                1574.2,
+                -556.01,
+                71.23472,
+                0.319781,
+                -0.8503463,
+                -0.005050998,
+                0.0083572073,
+            ],
+        ),
+        y if y < 1700.0 => polynomial(y - 1600.0, &[120.0, -0.9808, -0.01532, 1.0 / 7129.0]),
+        y if y < 1800.0 => polynomial(
+            y - 1700.0,
+            &[8.83, 0.1603, -0.0059285, 0.00013336, -1.0 / 1174000.0],
+        ),
+        y if y < 1860.0 => polynomial(
+            y - 1800.0,
+            &[
+                13.72,
+                -0.332447,
+                0.0068612,
+                0.0041116,
+                -0.00037436,
+                0.0000121272,
+                -0.0000001699,
+                0.000000000875,
+            ],
+        ),
+        y if y < 1900.0 => polynomial(
+            y - 1860.0,
+            &[
+                7.62,
+                0.5737,
+                -0.251754,
+                0.01680668,
+                -0.0004473624,
+                1.0 / 233174.0,
+            ],
+        ),
+        y if y < 1920.0 => polynomial(
+            y - 1900.0,
+            &[-2.79, 1.494119, -0.0598939, 0.0061966, -0.000197],
+        ),
+        y if y < 1941.0 => polynomial(y - 1920.0, &[21.20, 0.84493, -0.076100, 0.0020936]),
+        y if y < 1961.0 => polynomial(y - 1950.0, &[29.07, 0.407, -1.0 / 233.0, 1.0 / 2547.0]),
+        y if y < 1986.0 => polynomial(y - 1975.0, &[45.45, 1.067, -1.0 / 260.0, -1.0 / 718.0]),
+        y if y < 2005.0 => polynomial(
+            y - 2000.0,
+            &[
+                63.86,
+                0.3345,
+                -0.060374,
+                0.0017275,
+                0.000651814,
+                0.00002373599,
+            ],
+        ),
+        y if y < 2050.0 => polynomial(y - 2000.0, &[62.92, 0.32217, 0.005589]),
+        y if y < 2150.0 => long_term(y) - 0.5628 * (2150.0 - y),
+        y => long_term(y),
+    }
+}
+
+/// Estimates the uncertainty (one standard deviation) of [`delta_t`] in seconds.
+///
+/// The uncertainty grows with the square of the distance to the telescopic era, from
+/// below a second since the 17th century to about 20 minutes at 2000 BC and two hours
+/// at 6000 BC; it applies to extrapolations into the future as well.
+#[must_use]
+pub fn delta_t_uncertainty(decimal_year: f64) -> f64 {
+    let centuries = (decimal_year - 1820.0) / 100.0;
+    (0.8 * centuries * centuries).max(0.1)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_delta_t() {
+        // values tabulated by Espenak and Meeus
+        for (year, expected, tolerance) in [
+            (-500.0, 17_190.0, 20.0),
+            (0.0, 10_580.0, 10.0),
+            (1000.0, 1_570.0, 10.0),
+            (1700.0, 9.0, 1.0),
+            (1900.0, -2.7, 0.5),
+            (1950.0, 29.1, 0.5),
+            (2000.0, 63.8, 0.5),
+        ] {
+            assert!((delta_t(year) - expected).abs() < tolerance, "{year}");
+        }
+        // the pieces join continuously
+        for year in [
+            500.0, 1600.0, 1800.0, 1860.0, 1920.0, 1961.0, 2005.0, 2050.0,
+        ] {
+            assert!((delta_t(year - 1e-6) - delta_t(year)).abs() < 5.0, "{year}");
+        }
+    }
+
+    #[test]
+    fn test_decimal_year() {
+        assert!((decimal_year(946_728_000_000) - 2000.0).abs() < 1e-9);
+        assert!((decimal_year(1_362_441_600_000) - 2013.17).abs() < 0.01);
+        assert!(delta_t_uncertainty(-1999.0) > 1_000.0);
+    }
+}
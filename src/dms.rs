@@ -0,0 +1,256 @@
+//! Parsing and formatting of angles in degrees, minutes and seconds.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::dms::{format_dms, parse_dms};
+//!
+//! let lat = parse_dms("48°7′30″N").unwrap();
+//! assert_eq!(lat, 48.125);
+//! assert_eq!(format_dms(lat, 1), "48°07′30.0″");
+//! ```
+
+use std::fmt;
+
+/// An angle split into degrees, minutes and seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dms {
+    pub negative: bool,
+    pub degrees: u32,
+    pub minutes: u32,
+    pub seconds: f64,
+}
+
+impl Dms {
+    /// Splits a decimal angle in degrees.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn from_degrees(angle_deg: f64) -> Self {
+        let abs = angle_deg.abs();
+        let degrees = abs.trunc();
+        let minutes = ((abs - degrees) * 60.0).trunc();
+        let seconds = (abs - degrees - minutes / 60.0) * 3_600.0;
+        Self {
+            negative: angle_deg.is_sign_negative() && abs > 0.0,
+            degrees: degrees as u32,
+            minutes: minutes as u32,
+            seconds,
+        }
+    }
+
+    /// Returns the decimal angle in degrees.
+    #[must_use]
+    pub fn to_degrees(&self) -> f64 {
+        let abs = f64::from(self.degrees) + f64::from(self.minutes) / 60.0 + self.seconds / 3_600.0;
+        if self.negative {
+            -abs
+        } else {
+            abs
+        }
+    }
+}
+
+impl fmt::Display for Dms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(0);
+        f.write_str(&format_dms(self.to_degrees(), precision))
+    }
+}
+
+/// Error returned by [`parse_dms`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDmsError {
+    /// The input contains no number.
+    Empty,
+    /// A component is not a valid number.
+    InvalidNumber(String),
+    /// More than three numeric components were given.
+    TooManyComponents,
+    /// Minutes or seconds are not in `[0, 60)`.
+    OutOfRange,
+    /// A hemisphere letter was combined with a negative sign.
+    ConflictingSign,
+}
+
+impl fmt::Display for ParseDmsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseDmsError::Empty => write!(f, "no angle given"),
+            ParseDmsError::InvalidNumber(s) => write!(f, "invalid number: {s}"),
+            ParseDmsError::TooManyComponents => write!(f, "too many angle components"),
+            ParseDmsError::OutOfRange => write!(f, "minutes and seconds must be below 60"),
+            ParseDmsError::ConflictingSign => {
+                write!(f, "hemisphere conflicts with the sign of the angle")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseDmsError {}
+
+/// Parses an angle given as decimal degrees, degrees and decimal minutes or degrees, minutes and seconds.
+///
+/// Components may be separated by whitespace or by the symbols `°`, `'`, `′`, `"`, `″`.
+/// A leading `-` or a trailing (or leading) hemisphere letter `N`, `S`, `E` or `W` sets the sign,
+/// where `S` and `W` are negative.
+///
+/// # Errors
+///
+/// Returns a [`ParseDmsError`] if the input is not a valid angle.
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::dms::parse_dms;
+///
+/// assert_eq!(parse_dms("9.5").unwrap(), 9.5);
+/// assert_eq!(parse_dms("9°30.0'W").unwrap(), -9.5);
+/// assert_eq!(parse_dms("-33 52 12").unwrap(), -33.87);
+/// ```
+pub fn parse_dms(input: &str) -> Result<f64, ParseDmsError> {
+    let mut s = input.trim();
+    let mut hemisphere_negative = None;
+    for (letter, negative) in [('N', false), ('S', true), ('E', false), ('W', true)] {
+        let lower = letter.to_ascii_lowercase();
+        if let Some(rest) = s
+            .strip_suffix([letter, lower])
+            .or_else(|| s.strip_prefix([letter, lower]))
+        {
+            hemisphere_negative = Some(negative);
+            s = rest.trim();
+            break;
+        }
+    }
+    let (sign_negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    if sign_negative && hemisphere_negative.is_some() {
+        return Err(ParseDmsError::ConflictingSign);
+    }
+    let components = s
+        .split(|c: char| c.is_whitespace() || matches!(c, '°' | '\'' | '′' | '"' | '″' | ':'))
+        .filter(|c| !c.is_empty())
+        .map(|c| {
+            c.parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .ok_or_else(|| ParseDmsError::InvalidNumber(c.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let abs = match components.as_slice() {
+        [] => return Err(ParseDmsError::Empty),
+        [d] => *d,
+        [d, m] if *m < 60.0 => d + m / 60.0,
+        [d, m, s] if *m < 60.0 && *s < 60.0 => d + m / 60.0 + s / 3_600.0,
+        [_, _] | [_, _, _] => return Err(ParseDmsError::OutOfRange),
+        _ => return Err(ParseDmsError::TooManyComponents),
+    };
+    if sign_negative || hemisphere_negative == Some(true) {
+        Ok(-abs)
+    } else {
+        Ok(abs)
+    }
+}
+
+fn round_to(value: f64, precision: usize) -> f64 {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let factor = 10f64.powi(precision as i32);
+    (value * factor).round() / factor
+}
+
+fn format_parts(angle_deg: f64, precision: usize) -> (bool, u32, u32, f64) {
+    // round first so that 59.99999″ does not show up as 60″
+    let total_seconds = round_to(angle_deg.abs() * 3_600.0, precision);
+    let seconds = total_seconds % 60.0;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let total_minutes = ((total_seconds - seconds) / 60.0).round() as u64;
+    #[allow(clippy::cast_possible_truncation)]
+    let degrees = (total_minutes / 60) as u32;
+    #[allow(clippy::cast_possible_truncation)]
+    let minutes = (total_minutes % 60) as u32;
+    let negative = angle_deg < 0.0 && total_seconds > 0.0;
+    (negative, degrees, minutes, seconds)
+}
+
+/// Formats an angle in degrees as `ddd°mm′ss.s″` with `precision` decimal places for the seconds.
+#[must_use]
+pub fn format_dms(angle_deg: f64, precision: usize) -> String {
+    let (negative, degrees, minutes, seconds) = format_parts(angle_deg, precision);
+    let sign = if negative { "-" } else { "" };
+    let width = if precision > 0 { precision + 3 } else { 2 };
+    format!("{sign}{degrees}°{minutes:02}′{seconds:0width$.precision$}″")
+}
+
+/// Formats an angle in degrees as `ddd°mm.m′` with `precision` decimal places for the minutes.
+#[must_use]
+pub fn format_dm(angle_deg: f64, precision: usize) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let factor = 10f64.powi(precision as i32);
+    let total_minutes = (angle_deg.abs() * 60.0 * factor).round() / factor;
+    let minutes = total_minutes % 60.0;
+    let degrees = ((total_minutes - minutes) / 60.0).round();
+    let sign = if angle_deg < 0.0 && total_minutes > 0.0 {
+        "-"
+    } else {
+        ""
+    };
+    let width = if precision > 0 { precision + 3 } else { 2 };
+    format!("{sign}{degrees}°{minutes:0width$.precision$}′")
+}
+
+/// Formats a latitude in degrees as DMS with a `N`/`S` suffix.
+#[must_use]
+pub fn format_lat(lat: f64, precision: usize) -> String {
+    let hemisphere = if lat < 0.0 { 'S' } else { 'N' };
+    format!("{}{hemisphere}", format_dms(lat.abs(), precision))
+}
+
+/// Formats a longitude in degrees as DMS with an `E`/`W` suffix.
+#[must_use]
+pub fn format_lon(lon: f64, precision: usize) -> String {
+    let hemisphere = if lon < 0.0 { 'W' } else { 'E' };
+    format!("{}{hemisphere}", format_dms(lon.abs(), precision))
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_dms() {
+        assert_eq!(parse_dms("48°7′30″N").unwrap(), 48.125);
+        assert_eq!(parse_dms("48°7'30\"S").unwrap(), -48.125);
+        assert_eq!(parse_dms("W 9 30").unwrap(), -9.5);
+        assert_eq!(parse_dms(" -9.5 ").unwrap(), -9.5);
+        assert_eq!(parse_dms(""), Err(ParseDmsError::Empty));
+        assert_eq!(parse_dms("9 60"), Err(ParseDmsError::OutOfRange));
+        assert_eq!(parse_dms("-9S"), Err(ParseDmsError::ConflictingSign));
+        assert_eq!(parse_dms("1 2 3 4"), Err(ParseDmsError::TooManyComponents));
+        assert!(matches!(
+            parse_dms("9°x"),
+            Err(ParseDmsError::InvalidNumber(_))
+        ));
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format_dms(48.125, 1), "48°07′30.0″");
+        assert_eq!(format_dms(-0.5, 0), "-0°30′00″");
+        assert_eq!(format_dms(9.999_999_9, 1), "10°00′00.0″");
+        assert_eq!(format_dm(48.125, 2), "48°07.50′");
+        assert_eq!(format_lat(-33.87, 0), "33°52′12″S");
+        assert_eq!(format_lon(-9.5, 0), "9°30′00″W");
+    }
+
+    #[test]
+    fn test_dms_roundtrip() {
+        let dms = Dms::from_degrees(-33.87);
+        assert!(dms.negative);
+        assert_eq!((dms.degrees, dms.minutes), (33, 52));
+        assert!((dms.to_degrees() + 33.87).abs() < 1e-9);
+        assert_eq!(format!("{dms:.1}"), "-33°52′12.0″");
+    }
+}
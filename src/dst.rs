@@ -0,0 +1,185 @@
+//! Jumps of the wall-clock times of sunrise and sunset across the daylight saving time
+//! transitions of a year, e.g. for news items and notifications around the clock changes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::dst::dst_report;
+//!
+//! for transition in dst_report(2024, 52.5, 13.4, chrono_tz::Europe::Berlin) {
+//!     println!(
+//!         "{}: sunrise moves by {:?} minutes, sunset by {:?} minutes",
+//!         transition.date,
+//!         transition.sunrise_jump_minutes(),
+//!         transition.sunset_jump_minutes()
+//!     );
+//! }
+//! ```
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Offset, TimeZone};
+use chrono_tz::Tz;
+
+use crate::calendar::days_from_civil;
+use crate::report::event;
+use crate::{local_noon, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+/// A change of the UTC offset of a time zone and the wall-clock times of sunrise and
+/// sunset on the day before and on the day of the change.
+///
+/// Transitions happen at night in practically all time zones, so the events of the
+/// day of the change are already shifted by the new offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DstTransition {
+    /// First [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds with
+    /// the new offset.
+    pub unixtime_in_ms: i64,
+    /// Local date of the transition.
+    pub date: NaiveDate,
+    /// UTC offset in seconds before the transition.
+    pub offset_before_s: i32,
+    /// UTC offset in seconds after the transition.
+    pub offset_after_s: i32,
+    /// Local sunrise on the day before, `None` during polar day or polar night.
+    pub sunrise_before: Option<NaiveTime>,
+    /// Local sunrise on the day of the transition.
+    pub sunrise_after: Option<NaiveTime>,
+    /// Local sunset on the day before.
+    pub sunset_before: Option<NaiveTime>,
+    /// Local sunset on the day of the transition.
+    pub sunset_after: Option<NaiveTime>,
+}
+
+impl DstTransition {
+    /// Change of the UTC offset in minutes, positive when the clocks are set forward.
+    #[must_use]
+    pub const fn offset_change_minutes(&self) -> i32 {
+        (self.offset_after_s - self.offset_before_s) / 60
+    }
+
+    /// Minutes by which the wall-clock time of sunrise moves from the day before to the
+    /// day of the transition, including the change over one day, rounded.
+    #[must_use]
+    pub fn sunrise_jump_minutes(&self) -> Option<i64> {
+        jump(self.sunrise_before, self.sunrise_after)
+    }
+
+    /// Minutes by which the wall-clock time of sunset moves from the day before to the
+    /// day of the transition, including the change over one day, rounded.
+    #[must_use]
+    pub fn sunset_jump_minutes(&self) -> Option<i64> {
+        jump(self.sunset_before, self.sunset_after)
+    }
+}
+
+fn jump(before: Option<NaiveTime>, after: Option<NaiveTime>) -> Option<i64> {
+    // rounded like the displayed times
+    Some(((after? - before?).num_seconds() + 30).div_euclid(60))
+}
+
+fn offset_s(tz: Tz, unixtime_in_ms: i64) -> i32 {
+    tz.timestamp_millis_opt(unixtime_in_ms)
+        .single()
+        .map_or(0, |time| time.offset().fix().local_minus_utc())
+}
+
+fn local_time(tz: Tz, unixtime_in_ms: i64) -> Option<NaiveTime> {
+    tz.timestamp_millis_opt(unixtime_in_ms)
+        .single()
+        .map(|time| time.time())
+}
+
+/// Finds the daylight saving time transitions of `tz` in `year` and reports the local
+/// times of sunrise and sunset around each of them.
+///
+/// * `year`  - year of the transitions.
+/// * `lat`   - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`   - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `tz`    - [IANA time zone](https://www.iana.org/time-zones) of the location.
+///
+/// Any change of the UTC offset counts as a transition, including permanent changes of
+/// the standard time.
+#[must_use]
+pub fn dst_report(year: i32, lat: f64, lon: f64, tz: Tz) -> Vec<DstTransition> {
+    let mut transitions = Vec::new();
+    for day in days_from_civil(year, 1, 1)..days_from_civil(year + 1, 1, 1) {
+        let (mut before, mut after) = (
+            day * MILLISECONDS_PER_DAY_INT,
+            (day + 1) * MILLISECONDS_PER_DAY_INT,
+        );
+        let offset_before_s = offset_s(tz, before);
+        let offset_after_s = offset_s(tz, after);
+        if offset_before_s == offset_after_s {
+            continue;
+        }
+        while after - before > 1 {
+            let middle = before + (after - before) / 2;
+            if offset_s(tz, middle) == offset_before_s {
+                before = middle;
+            } else {
+                after = middle;
+            }
+        }
+        let Some(date) = tz
+            .timestamp_millis_opt(after)
+            .single()
+            .map(|time| time.date_naive())
+        else {
+            continue;
+        };
+        let local_day = days_from_civil(date.year(), date.month(), date.day());
+        let events = |day: i64, phase| {
+            event(
+                local_noon(day * MILLISECONDS_PER_DAY_INT, lon),
+                phase,
+                lat,
+                lon,
+            )
+            .and_then(|time| local_time(tz, time))
+        };
+        transitions.push(DstTransition {
+            unixtime_in_ms: after,
+            date,
+            offset_before_s,
+            offset_after_s,
+            sunrise_before: events(local_day - 1, SunPhase::Sunrise),
+            sunrise_after: events(local_day, SunPhase::Sunrise),
+            sunset_before: events(local_day - 1, SunPhase::Sunset),
+            sunset_after: events(local_day, SunPhase::Sunset),
+        });
+    }
+    transitions
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_berlin() {
+        let transitions = dst_report(2024, 52.5, 13.4, chrono_tz::Europe::Berlin);
+        assert_eq!(transitions.len(), 2);
+        let spring = &transitions[0];
+        // 2024-03-31 01:00 UTC
+        assert_eq!(spring.unixtime_in_ms, 1_711_846_800_000);
+        assert_eq!(spring.date, NaiveDate::from_ymd_opt(2024, 3, 31).unwrap());
+        assert_eq!(spring.offset_change_minutes(), 60);
+        // an hour later, shortened or extended by the two minutes the day grows on each end
+        assert_eq!(spring.sunrise_jump_minutes(), Some(58));
+        assert_eq!(spring.sunset_jump_minutes(), Some(62));
+        let autumn = &transitions[1];
+        assert_eq!(autumn.date, NaiveDate::from_ymd_opt(2024, 10, 27).unwrap());
+        assert_eq!(autumn.offset_change_minutes(), -60);
+        assert!(autumn.sunrise_jump_minutes().unwrap() < -55);
+    }
+
+    #[test]
+    fn test_without_transitions() {
+        assert!(dst_report(2024, 35.7, 139.7, chrono_tz::Asia::Tokyo).is_empty());
+        // polar night in Longyearbyen during the autumn transition
+        let transitions = dst_report(2024, 78.2, 15.6, chrono_tz::Arctic::Longyearbyen);
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[1].sunrise_after, None);
+        assert_eq!(transitions[1].sunrise_jump_minutes(), None);
+    }
+}
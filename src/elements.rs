@@ -0,0 +1,174 @@
+//! Orbital elements of the solar model, for experiments with modified constants.
+//!
+//! [`OrbitalElements::EARTH`] holds the constants used by [`pos`](crate::pos). Overriding
+//! them allows to keep the constants current without forking the crate, or to ask
+//! what-if questions, e.g. how the seasons would look without the tilt of the earth's axis.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::elements::{pos_with_elements, OrbitalElements};
+//!
+//! let untilted = OrbitalElements {
+//!     obliquity: 0.0,
+//!     ..OrbitalElements::EARTH
+//! };
+//! // midsummer noon in Stuttgart without the tilt: the sun stands at the colatitude
+//! let noon = pos_with_elements(1_371_812_400_000, 48.8, 9.2, &untilted);
+//! assert!((noon.altitude.to_degrees() - 41.2).abs() < 0.5);
+//! ```
+
+use core::f64::consts::PI;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+use crate::{altitude, azimuth, sidereal_time, to_days, Position, ASTRONOMICAL_UNIT_IN_KM};
+
+/// Parameters of the orbit of the observer's planet around the sun, relative to J2000.
+///
+/// The model follows the one of [`pos`](crate::pos): the mean anomaly grows linearly, the
+/// equation of center and the distance are series in multiples of the mean anomaly, and the
+/// ecliptic longitude of the sun is measured from the vernal equinox of the planet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    /// Mean anomaly at J2000 in degrees.
+    pub mean_anomaly_at_epoch: f64,
+    /// Increase of the mean anomaly in degrees per day.
+    pub mean_anomaly_rate: f64,
+    /// Coefficients in degrees of `sin(k * M)` for `k = 1..=5` of the equation of center.
+    pub equation_of_center: [f64; 5],
+    /// Longitude of the perihelion at J2000 in degrees.
+    pub perihelion: f64,
+    /// Increase of the longitude of the perihelion in degrees per day.
+    pub perihelion_rate: f64,
+    /// Tilt of the rotation axis against the orbit in degrees.
+    pub obliquity: f64,
+    /// Coefficients in astronomical units of `cos(k * M)` for `k = 0..=4` of the distance to the sun.
+    pub distance: [f64; 5],
+}
+
+impl OrbitalElements {
+    /// The constants of the earth's orbit used by [`pos`](crate::pos).
+    pub const EARTH: Self = Self {
+        mean_anomaly_at_epoch: 357.529_1,
+        mean_anomaly_rate: 0.985_600_28,
+        equation_of_center: [1.914_8, 0.02, 0.000_3, 0.0, 0.0],
+        perihelion: 102.937_2,
+        perihelion_rate: 0.0,
+        obliquity: 23.439_7,
+        distance: [1.000_14, -0.016_71, -0.000_14, 0.0, 0.0],
+    };
+
+    /// Mean anomaly in radians at the given days since J2000.
+    #[must_use]
+    pub fn mean_anomaly(&self, days: f64) -> f64 {
+        (self.mean_anomaly_at_epoch + self.mean_anomaly_rate * days).to_radians()
+    }
+
+    /// Ecliptic longitude of the sun in radians at the given days since J2000.
+    #[must_use]
+    pub fn ecliptic_longitude(&self, days: f64) -> f64 {
+        let mean_anomaly = self.mean_anomaly(days);
+        let [c1, c2, c3, c4, c5] = self.equation_of_center;
+        let center = (c1 * mean_anomaly.sin()
+            + c2 * (2.0 * mean_anomaly).sin()
+            + c3 * (3.0 * mean_anomaly).sin()
+            + c4 * (4.0 * mean_anomaly).sin()
+            + c5 * (5.0 * mean_anomaly).sin())
+        .to_radians();
+        let perihelion = (self.perihelion + self.perihelion_rate * days).to_radians();
+        mean_anomaly + center + perihelion + PI
+    }
+
+    /// Right ascension and declination of the sun in radians at the given days since J2000.
+    #[must_use]
+    pub fn equatorial(&self, days: f64) -> (f64, f64) {
+        let longitude = self.ecliptic_longitude(days);
+        let obliquity = self.obliquity.to_radians();
+        let right_ascension = (longitude.sin() * obliquity.cos()).atan2(longitude.cos());
+        let declination = (obliquity.sin() * longitude.sin()).asin();
+        (right_ascension, declination)
+    }
+
+    /// Distance to the sun in kilometers at the given days since J2000.
+    #[must_use]
+    pub fn distance(&self, days: f64) -> f64 {
+        let mean_anomaly = self.mean_anomaly(days);
+        let [d0, d1, d2, d3, d4] = self.distance;
+        (d0 + d1 * mean_anomaly.cos()
+            + d2 * (2.0 * mean_anomaly).cos()
+            + d3 * (3.0 * mean_anomaly).cos()
+            + d4 * (4.0 * mean_anomaly).cos())
+            * ASTRONOMICAL_UNIT_IN_KM
+    }
+}
+
+impl Default for OrbitalElements {
+    fn default() -> Self {
+        Self::EARTH
+    }
+}
+
+/// Calculates the sun position like [`pos`](crate::pos) with the given orbital elements.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `elements`  - orbital elements of the earth.
+#[must_use]
+pub fn pos_with_elements(
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    elements: &OrbitalElements,
+) -> Position {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let (right_ascension, declination) = elements.equatorial(days);
+    let hour_angle = sidereal_time(days, -lon.to_radians()) - right_ascension;
+    let latitude_rad = lat.to_radians();
+    Position {
+        azimuth: azimuth(hour_angle, latitude_rad, declination),
+        altitude: altitude(hour_angle, latitude_rad, declination),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_earth_elements_reproduce_pos() {
+        let date = 1_362_441_600_000;
+        for i in 0..8 {
+            let time = date + i * 3 * 60 * 60 * 1_000;
+            let position = pos_with_elements(time, 50.5, 30.5, &OrbitalElements::default());
+            let full = crate::full_pos(time, 50.5, 30.5);
+            assert_eq!(position.azimuth, full.azimuth);
+            assert_eq!(position.altitude, full.altitude);
+            #[allow(clippy::cast_precision_loss)]
+            let days = to_days(time as f64);
+            assert_eq!(OrbitalElements::EARTH.distance(days), full.distance);
+        }
+    }
+
+    #[test]
+    fn test_modified_elements() {
+        // 2013-06-21 UTC, at the solstice the declination equals the obliquity
+        let days = to_days(1_371_816_000_000.0);
+        let tilted = OrbitalElements {
+            obliquity: 30.0,
+            ..OrbitalElements::EARTH
+        };
+        assert!((tilted.equatorial(days).1.to_degrees() - 30.0).abs() < 0.01);
+        // a circular orbit
+        let circular = OrbitalElements {
+            equation_of_center: [0.0; 5],
+            distance: [1.0, 0.0, 0.0, 0.0, 0.0],
+            ..OrbitalElements::EARTH
+        };
+        assert_eq!(circular.distance(days), ASTRONOMICAL_UNIT_IN_KM);
+    }
+}
@@ -0,0 +1,151 @@
+//! Polynomial approximations of the trigonometric functions, which replace the functions
+//! of the standard library in the calculation of the altitude and azimuth of the sun with
+//! the `fast-math` feature.
+//!
+//! The horizontal coordinates are the part of [`pos`](crate::pos) that depends on the
+//! location, so they dominate the cost of [`pos_many`](crate::pos_many) and of rasters.
+//! The coordinates of the sun and the event times keep the full precision: an error in
+//! the declination would move sunrise and sunset by seconds.
+//!
+//! The approximations are taken from Abramowitz and Stegun, *Handbook of Mathematical
+//! Functions*. The sine uses formula 4.3.97 after reducing the angle to `[-π/2, π/2]`,
+//! which is accurate to a few nanoradians. The arcsine uses the much cheaper formula
+//! 4.4.45, which dominates the error: the altitude is an arcsine, so the direction to the
+//! sun deviates by at most [`POSITION_MAX_ERROR_ARCMIN`] from the exact calculation. A
+//! cheaper sine isn't used as its error is amplified by the arcsine close to the zenith
+//! up to about a degree.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::fast_math::{asin, sin, ASIN_MAX_ERROR};
+//!
+//! assert!((asin(sin(0.5)) - 0.5).abs() < ASIN_MAX_ERROR);
+//! ```
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+use core::f64::consts::{FRAC_PI_2, PI, TAU};
+
+/// Largest absolute error of [`sin`] and [`cos`] in radians.
+pub const SIN_MAX_ERROR: f64 = 4e-9;
+/// Largest absolute error of [`asin`] in radians, about 0.24′.
+pub const ASIN_MAX_ERROR: f64 = 7e-5;
+/// Largest angle between the sun positions calculated with and without the
+/// approximations in arcminutes.
+pub const POSITION_MAX_ERROR_ARCMIN: f64 = 0.5;
+
+/// Approximates the sine of `x` in radians.
+#[must_use]
+pub fn sin(x: f64) -> f64 {
+    // reduce to [-π, π] and then to [-π/2, π/2] with sin(π - x) = sin(x)
+    let x = x - TAU * (x / TAU).round();
+    let x = if x > FRAC_PI_2 {
+        PI - x
+    } else if x < -FRAC_PI_2 {
+        -PI - x
+    } else {
+        x
+    };
+    let x2 = x * x;
+    x * (1.0
+        + x2 * (-0.166_666_666_4
+            + x2 * (0.008_333_331_5
+                + x2 * (-0.000_198_409_0 + x2 * (0.000_002_752_6 - x2 * 0.000_000_023_9)))))
+}
+
+/// Approximates the cosine of `x` in radians.
+#[must_use]
+pub fn cos(x: f64) -> f64 {
+    sin(x + FRAC_PI_2)
+}
+
+/// Approximates the arcsine of `x` in radians, clamping `x` to `[-1, 1]`.
+#[must_use]
+pub fn asin(x: f64) -> f64 {
+    let a = x.abs().min(1.0);
+    let polynomial = 1.570_728_8 + a * (-0.212_114_4 + a * (0.074_261_0 - a * 0.018_729_3));
+    (FRAC_PI_2 - (1.0 - a).sqrt() * polynomial).copysign(x)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_max_errors() {
+        for i in -100_000..=100_000 {
+            let x = f64::from(i) * 1e-4;
+            assert!((sin(x) - x.sin()).abs() < SIN_MAX_ERROR, "sin({x})");
+            assert!((cos(x) - x.cos()).abs() < SIN_MAX_ERROR, "cos({x})");
+            let y = f64::from(i) * 1e-5;
+            assert!((asin(y) - y.asin()).abs() < ASIN_MAX_ERROR, "asin({y})");
+        }
+        // the hour angle grows by 2π a day
+        let x = 1e5 * TAU + 1.0;
+        assert!((sin(x) - x.sin()).abs() < SIN_MAX_ERROR);
+        assert!((asin(1.5) - FRAC_PI_2).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_position_error() {
+        let exact = |days: f64, lat: f64, lon: f64| {
+            let mean_anomaly = (357.5291 + 0.985_600_28 * days).to_radians();
+            let center = (1.9148 * mean_anomaly.sin()
+                + 0.02 * (2.0 * mean_anomaly).sin()
+                + 0.0003 * (3.0 * mean_anomaly).sin())
+            .to_radians();
+            let longitude = mean_anomaly + center + 102.9372_f64.to_radians() + PI;
+            let obliquity = 23.4397_f64.to_radians();
+            let declination = (obliquity.sin() * longitude.sin()).asin();
+            let right_ascension = (longitude.sin() * obliquity.cos()).atan2(longitude.cos());
+            let hour_angle = (280.16 + 360.985_623_5 * days + lon).to_radians() - right_ascension;
+            let lat = lat.to_radians();
+            let altitude = (lat.sin() * declination.sin()
+                + lat.cos() * declination.cos() * hour_angle.cos())
+            .asin();
+            let azimuth = hour_angle
+                .sin()
+                .atan2(hour_angle.cos() * lat.sin() - declination.tan() * lat.cos());
+            (azimuth, altitude)
+        };
+        let approximated = |days: f64, lat: f64, lon: f64| {
+            // the coordinates of the sun are exact, only the horizontal ones approximated
+            let mean_anomaly = (357.5291 + 0.985_600_28 * days).to_radians();
+            let center = (1.9148 * mean_anomaly.sin()
+                + 0.02 * (2.0 * mean_anomaly).sin()
+                + 0.0003 * (3.0 * mean_anomaly).sin())
+            .to_radians();
+            let longitude = mean_anomaly + center + 102.9372_f64.to_radians() + PI;
+            let obliquity = 23.4397_f64.to_radians();
+            let declination = (obliquity.sin() * longitude.sin()).asin();
+            let right_ascension = (longitude.sin() * obliquity.cos()).atan2(longitude.cos());
+            let hour_angle = (280.16 + 360.985_623_5 * days + lon).to_radians() - right_ascension;
+            let lat = lat.to_radians();
+            let altitude =
+                asin(sin(lat) * sin(declination) + cos(lat) * cos(declination) * cos(hour_angle));
+            let azimuth =
+                sin(hour_angle).atan2(cos(hour_angle) * sin(lat) - declination.tan() * cos(lat));
+            (azimuth, altitude)
+        };
+        let mut max_error: f64 = 0.0;
+        for step in 0..20_000 {
+            let step = f64::from(step);
+            // spread over 60 years, all latitudes and longitudes
+            let (days, lat, lon) = (
+                -5_000.0 + step * 1.097_3,
+                -90.0 + (step * 7.31) % 180.0,
+                -180.0 + (step * 13.7) % 360.0,
+            );
+            let (a0, h0) = exact(days, lat, lon);
+            let (a1, h1) = approximated(days, lat, lon);
+            let separation = (h0.sin() * h1.sin() + h0.cos() * h1.cos() * (a1 - a0).cos())
+                .min(1.0)
+                .acos();
+            max_error = max_error.max(separation.to_degrees() * 60.0);
+        }
+        assert!(max_error < POSITION_MAX_ERROR_ARCMIN, "{max_error}");
+        assert!(max_error > POSITION_MAX_ERROR_ARCMIN / 10.0);
+    }
+}
@@ -0,0 +1,234 @@
+//! Configurable text output of sun positions.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::format::{AngleUnit, PositionFormat};
+//!
+//! let pos = sun::Position { azimuth: 1.0, altitude: 0.5 };
+//! assert_eq!(pos.to_string(), "azimuth: 57.30°, altitude: 28.65°");
+//!
+//! let format = PositionFormat {
+//!     unit: AngleUnit::Radians,
+//!     precision: 3,
+//!     compass: true,
+//!     ..PositionFormat::default()
+//! };
+//! assert_eq!(pos.display(format).to_string(), "azimuth: 1.000 rad (ENE), altitude: 0.500 rad");
+//! ```
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::dms::format_dms;
+use crate::Position;
+
+/// Unit of formatted angles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+    /// [NATO mils](https://en.wikipedia.org/wiki/Milliradian#Mils), 6400 per full turn.
+    Mils,
+    /// [Gradians](https://en.wikipedia.org/wiki/Gradian), 400 per full turn.
+    Gradians,
+}
+
+impl AngleUnit {
+    /// Converts an angle in radians into this unit.
+    #[must_use]
+    pub fn from_radians(self, angle_rad: f64) -> f64 {
+        match self {
+            AngleUnit::Degrees => angle_rad.to_degrees(),
+            AngleUnit::Radians => angle_rad,
+            AngleUnit::Mils => angle_rad * 3_200.0 / PI,
+            AngleUnit::Gradians => angle_rad * 200.0 / PI,
+        }
+    }
+
+    /// Converts an angle in this unit into radians.
+    #[must_use]
+    pub fn to_radians(self, angle: f64) -> f64 {
+        match self {
+            AngleUnit::Degrees => angle.to_radians(),
+            AngleUnit::Radians => angle,
+            AngleUnit::Mils => angle * PI / 3_200.0,
+            AngleUnit::Gradians => angle * PI / 200.0,
+        }
+    }
+
+    /// Symbol appended to formatted angles.
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "°",
+            AngleUnit::Radians => " rad",
+            AngleUnit::Mils => " mil",
+            AngleUnit::Gradians => " gon",
+        }
+    }
+}
+
+/// Options for formatting a [`Position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionFormat {
+    /// Unit of the angles, ignored if `dms` is set.
+    pub unit: AngleUnit,
+    /// Number of decimal places (of the seconds if `dms` is set).
+    pub precision: usize,
+    /// Format angles as degrees, minutes and seconds.
+    pub dms: bool,
+    /// Append the compass direction of the azimuth.
+    pub compass: bool,
+}
+
+impl Default for PositionFormat {
+    fn default() -> Self {
+        Self {
+            unit: AngleUnit::Degrees,
+            precision: 2,
+            dms: false,
+            compass: false,
+        }
+    }
+}
+
+impl PositionFormat {
+    fn format_angle(&self, angle_rad: f64) -> String {
+        if self.dms {
+            format_dms(angle_rad.to_degrees(), self.precision)
+        } else {
+            format!(
+                "{:.*}{}",
+                self.precision,
+                self.unit.from_radians(angle_rad),
+                self.unit.symbol()
+            )
+        }
+    }
+}
+
+/// Returns the 16-point compass direction (e.g. `"NNE"`) of an azimuth in radians
+/// measured clockwise from north.
+#[must_use]
+pub fn compass_point(azimuth_rad: f64) -> &'static str {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+    let sector = (azimuth_rad.rem_euclid(2.0 * PI) / (PI / 8.0)).round();
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let index = sector as usize % POINTS.len();
+    POINTS[index]
+}
+
+/// Helper returned by [`Position::display`] to format a position with [`PositionFormat`].
+#[derive(Debug, Clone, Copy)]
+pub struct PositionDisplay<'a> {
+    position: &'a Position,
+    format: PositionFormat,
+}
+
+impl fmt::Display for PositionDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "azimuth: {}",
+            self.format.format_angle(self.position.azimuth)
+        )?;
+        if self.format.compass {
+            write!(f, " ({})", compass_point(self.position.azimuth))?;
+        }
+        write!(
+            f,
+            ", altitude: {}",
+            self.format.format_angle(self.position.altitude)
+        )
+    }
+}
+
+impl Position {
+    /// Returns an object implementing [`fmt::Display`] that formats the position
+    /// according to `format`.
+    #[must_use]
+    pub const fn display(&self, format: PositionFormat) -> PositionDisplay<'_> {
+        PositionDisplay {
+            position: self,
+            format,
+        }
+    }
+}
+
+/// Formats the angles in degrees, the precision defaults to two decimal places.
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut format = PositionFormat::default();
+        if let Some(precision) = f.precision() {
+            format.precision = precision;
+        }
+        self.display(format).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let pos = Position {
+            azimuth: PI,
+            altitude: -PI / 4.0,
+        };
+        assert_eq!(pos.to_string(), "azimuth: 180.00°, altitude: -45.00°");
+        assert_eq!(format!("{pos:.0}"), "azimuth: 180°, altitude: -45°");
+        let format = PositionFormat {
+            dms: true,
+            compass: true,
+            precision: 0,
+            ..PositionFormat::default()
+        };
+        assert_eq!(
+            pos.display(format).to_string(),
+            "azimuth: 180°00′00″ (S), altitude: -45°00′00″"
+        );
+    }
+
+    #[test]
+    fn test_angle_units() {
+        for unit in [
+            AngleUnit::Degrees,
+            AngleUnit::Radians,
+            AngleUnit::Mils,
+            AngleUnit::Gradians,
+        ] {
+            assert!((unit.to_radians(unit.from_radians(1.234)) - 1.234).abs() < 1e-12);
+        }
+        assert!((AngleUnit::Mils.from_radians(PI / 2.0) - 1_600.0).abs() < 1e-9);
+        assert!((AngleUnit::Gradians.from_radians(PI) - 200.0).abs() < 1e-9);
+        let pos = Position {
+            azimuth: PI,
+            altitude: PI / 4.0,
+        };
+        let format = PositionFormat {
+            unit: AngleUnit::Mils,
+            precision: 0,
+            ..PositionFormat::default()
+        };
+        assert_eq!(
+            pos.display(format).to_string(),
+            "azimuth: 3200 mil, altitude: 800 mil"
+        );
+    }
+
+    #[test]
+    fn test_compass_point() {
+        assert_eq!(compass_point(0.0), "N");
+        assert_eq!(compass_point(2.0 * PI - 0.01), "N");
+        assert_eq!(compass_point(PI / 2.0), "E");
+        assert_eq!(compass_point(5.0 * PI / 8.0), "ESE");
+        assert_eq!(compass_point(-PI / 2.0), "W");
+    }
+}
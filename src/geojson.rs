@@ -0,0 +1,128 @@
+//! [GeoJSON](https://datatracker.ietf.org/doc/html/rfc7946) features of the
+//! [terminator](crate::terminator) and the areas of night and twilight, ready to be added
+//! to a web map.
+//!
+//! Every feature has the properties `timestamp`, the unix time in milliseconds, and
+//! `depression`, the depression of the sun below the horizon in degrees.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::geojson::{night_feature, terminator_feature, twilight_collection};
+//!
+//! let now = 1_362_484_800_000;
+//! let line = terminator_feature(now, 0.0, 360);
+//! assert!(line.contains(r#""type":"MultiLineString""#));
+//! let night = night_feature(now, 18.0, 360);
+//! assert!(night.contains(r#""depression":18"#));
+//! let layers = twilight_collection(now, 360);
+//! assert!(layers.starts_with(r#"{"type":"FeatureCollection""#));
+//! ```
+
+use crate::terminator::{night_polygons, terminator_lines, GeoPoint};
+
+/// Depressions of the sun at the end of the day, of civil, of nautical and of
+/// astronomical twilight.
+const TWILIGHT_DEPRESSIONS: [f64; 4] = [0.0, 6.0, 12.0, 18.0];
+
+fn position(point: &GeoPoint) -> String {
+    format!("[{:.6},{:.6}]", point.lon, point.lat)
+}
+
+fn positions(points: &[GeoPoint]) -> String {
+    let positions: Vec<String> = points.iter().map(position).collect();
+    format!("[{}]", positions.join(","))
+}
+
+fn feature(
+    geometry_type: &str,
+    coordinates: &[String],
+    unixtime_in_ms: i64,
+    depression_deg: f64,
+) -> String {
+    format!(
+        r#"{{"type":"Feature","geometry":{{"type":"{geometry_type}","coordinates":[{}]}},"properties":{{"timestamp":{unixtime_in_ms},"depression":{depression_deg}}}}}"#,
+        coordinates.join(",")
+    )
+}
+
+/// Returns the lines of [`terminator_lines`] as a feature with a `MultiLineString`
+/// geometry.
+///
+/// * `unixtime`       - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `depression_deg` - depression of the sun below the horizon in degrees.
+/// * `n_points`       - number of points on the whole line.
+#[must_use]
+pub fn terminator_feature(unixtime_in_ms: i64, depression_deg: f64, n_points: usize) -> String {
+    let lines: Vec<String> = terminator_lines(unixtime_in_ms, depression_deg, n_points)
+        .iter()
+        .map(|line| positions(line))
+        .collect();
+    feature("MultiLineString", &lines, unixtime_in_ms, depression_deg)
+}
+
+/// Returns the areas of [`night_polygons`] as a feature with a `MultiPolygon` geometry.
+///
+/// * `unixtime`       - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `depression_deg` - depression of the sun below the horizon in degrees.
+/// * `n_points`       - number of points on the whole boundary.
+#[must_use]
+pub fn night_feature(unixtime_in_ms: i64, depression_deg: f64, n_points: usize) -> String {
+    let polygons: Vec<String> = night_polygons(unixtime_in_ms, depression_deg, n_points)
+        .iter()
+        .map(|ring| format!("[{}]", positions(ring)))
+        .collect();
+    feature("MultiPolygon", &polygons, unixtime_in_ms, depression_deg)
+}
+
+/// Returns a feature collection with the terminator line followed by the areas
+/// beyond the terminator and beyond the end of civil, nautical and astronomical
+/// twilight, i.e. with a depression of `0°`, `6°`, `12°` and `18°`.
+///
+/// The areas are nested, so drawing them with a translucent fill darkens the map
+/// gradually towards the night.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `n_points`  - number of points on each line and boundary.
+#[must_use]
+pub fn twilight_collection(unixtime_in_ms: i64, n_points: usize) -> String {
+    let mut features = vec![terminator_feature(unixtime_in_ms, 0.0, n_points)];
+    features.extend(
+        TWILIGHT_DEPRESSIONS
+            .iter()
+            .map(|&depression| night_feature(unixtime_in_ms, depression, n_points)),
+    );
+    format!(
+        r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+        features.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_terminator_feature() {
+        let json = terminator_feature(1_362_484_800_000, 0.0, 8);
+        assert!(json.starts_with(
+            r#"{"type":"Feature","geometry":{"type":"MultiLineString","coordinates":[[["#
+        ));
+        assert!(json.ends_with(r#"]]]},"properties":{"timestamp":1362484800000,"depression":0}}"#));
+        let line = &terminator_lines(1_362_484_800_000, 0.0, 8)[0];
+        assert!(json.contains(&format!("[{:.6},{:.6}]", line[1].lon, line[1].lat)));
+        assert_eq!(json.matches('[').count(), json.matches(']').count());
+    }
+
+    #[test]
+    fn test_twilight_collection() {
+        let json = twilight_collection(1_362_484_800_000, 36);
+        assert_eq!(json.matches(r#""type":"Feature""#).count(), 5);
+        assert_eq!(json.matches(r#""type":"MultiPolygon""#).count(), 4);
+        assert!(json.contains(r#""depression":6}"#));
+        let night = night_feature(1_362_484_800_000, 12.0, 36);
+        assert!(json.contains(&night));
+        assert!(night.contains(r#""coordinates":[[[["#));
+    }
+}
@@ -0,0 +1,204 @@
+//! Geometry of specular sun glint on water surfaces.
+//!
+//! Sun glint is the mirror reflection of the sun on water. It saturates
+//! cameras of drones and satellites and has to be avoided in surveys.
+//! The glint angle is the angle between the viewing direction and the
+//! direction of the mirrored sun; glint is likely below roughly 20–30°
+//! depending on the wave slopes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::glint::{glint_windows, ViewGeometry};
+//!
+//! // a drone camera looking straight down
+//! let view = ViewGeometry::NADIR;
+//! let start = 1_371_772_800_000; // 2013-06-21 UTC
+//! let windows = glint_windows(start, start + 24 * 60 * 60 * 1_000, 54.0, 10.0, view, 30.0);
+//! for (from, to) in windows {
+//!     println!("avoid flying between {from} and {to}");
+//! }
+//! ```
+
+use std::f64::consts::PI;
+
+use crate::pos;
+
+const EARTH_RADIUS_IN_M: f64 = 6_371_000.0;
+const STEP_MS: i64 = 60 * 1_000;
+
+/// Direction from the water surface towards the observer or sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewGeometry {
+    /// Zenith angle of the sensor as seen from the surface in radians.
+    pub zenith: f64,
+    /// Azimuth of the sensor as seen from the surface in radians, clockwise from north.
+    pub azimuth: f64,
+}
+
+impl ViewGeometry {
+    /// A sensor looking straight down.
+    pub const NADIR: Self = Self {
+        zenith: 0.0,
+        azimuth: 0.0,
+    };
+}
+
+/// Calculates the glint angle in radians between the direction to the sensor and
+/// the direction of the mirrored sun.
+///
+/// * `view`         - direction from the surface to the sensor.
+/// * `sun_azimuth`  - azimuth of the sun in radians.
+/// * `sun_altitude` - altitude of the sun in radians.
+#[must_use]
+pub fn glint_angle(view: ViewGeometry, sun_azimuth: f64, sun_altitude: f64) -> f64 {
+    let sun_zenith = PI / 2.0 - sun_altitude;
+    (view.zenith.cos() * sun_zenith.cos()
+        - view.zenith.sin() * sun_zenith.sin() * (view.azimuth - sun_azimuth).cos())
+    .clamp(-1.0, 1.0)
+    .acos()
+}
+
+/// Location of the specular reflection of the sun on a flat water surface.
+#[derive(Debug, Clone, Copy)]
+pub struct SpecularPoint {
+    /// Azimuth from the observer to the specular point in radians.
+    pub azimuth: f64,
+    /// Angle below the horizon at which the glint is seen in radians.
+    pub depression: f64,
+    /// Distance from the observer to the specular point in meters.
+    pub distance: f64,
+    /// [latitude](https://en.wikipedia.org/wiki/Latitude) of the specular point in degrees.
+    pub lat: f64,
+    /// [longitude](https://en.wikipedia.org/wiki/Longitude) of the specular point in degrees.
+    pub lon: f64,
+}
+
+/// Calculates where an observer at `height` meters above the water sees the glint of the sun.
+///
+/// Returns `None` if the sun is below the horizon.
+#[must_use]
+pub fn specular_point(
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<SpecularPoint> {
+    let sun = pos(unixtime_in_ms, lat, lon);
+    if sun.altitude <= 0.0 {
+        return None;
+    }
+    let distance = height / sun.altitude.tan();
+    let angular = distance / EARTH_RADIUS_IN_M;
+    let lat_rad = lat.to_radians();
+    let target_lat =
+        (lat_rad.sin() * angular.cos() + lat_rad.cos() * angular.sin() * sun.azimuth.cos()).asin();
+    let target_lon = lon.to_radians()
+        + (sun.azimuth.sin() * angular.sin() * lat_rad.cos())
+            .atan2(angular.cos() - lat_rad.sin() * target_lat.sin());
+    Some(SpecularPoint {
+        azimuth: sun.azimuth,
+        depression: sun.altitude,
+        distance,
+        lat: target_lat.to_degrees(),
+        lon: (target_lon.to_degrees() + 540.0).rem_euclid(360.0) - 180.0,
+    })
+}
+
+/// Finds the periods between `start_in_ms` and `end_in_ms` in which the glint angle for the
+/// given view geometry is below `threshold_deg` and the sun is above the horizon.
+///
+/// The periods are returned as pairs of [unix times](https://en.wikipedia.org/wiki/Unix_time)
+/// in milliseconds with a resolution of one minute.
+#[must_use]
+pub fn glint_windows(
+    start_in_ms: i64,
+    end_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    view: ViewGeometry,
+    threshold_deg: f64,
+) -> Vec<(i64, i64)> {
+    let threshold = threshold_deg.to_radians();
+    let mut windows = Vec::new();
+    let mut open: Option<i64> = None;
+    let mut time = start_in_ms;
+    while time <= end_in_ms {
+        let sun = pos(time, lat, lon);
+        let glint = sun.altitude > 0.0 && glint_angle(view, sun.azimuth, sun.altitude) < threshold;
+        match (glint, open) {
+            (true, None) => open = Some(time),
+            (false, Some(from)) => {
+                windows.push((from, time));
+                open = None;
+            }
+            _ => {}
+        }
+        time += STEP_MS;
+    }
+    if let Some(from) = open {
+        windows.push((from, end_in_ms));
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_glint_angle() {
+        // looking straight down with the sun in the zenith
+        assert!(glint_angle(ViewGeometry::NADIR, 0.0, PI / 2.0).abs() < 1e-9);
+        // the sensor sits exactly in the mirrored sun direction
+        let view = ViewGeometry {
+            zenith: 0.5,
+            azimuth: PI,
+        };
+        assert!(glint_angle(view, 0.0, PI / 2.0 - 0.5).abs() < 1e-9);
+        // and opposite to it
+        let view = ViewGeometry {
+            zenith: 0.5,
+            azimuth: 0.0,
+        };
+        assert!((glint_angle(view, 0.0, PI / 2.0 - 0.5) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_specular_point() {
+        // 2013-06-21 12:00 UTC
+        let point = specular_point(1_371_816_000_000, 0.0, 0.0, 100.0).unwrap();
+        assert!((point.distance - 100.0 / point.depression.tan()).abs() < 1e-9);
+        assert!(point.distance < 100.0);
+        assert!(specular_point(1_371_772_800_000, 0.0, 0.0, 100.0).is_none());
+    }
+
+    #[test]
+    fn test_glint_windows() {
+        // 2013-06-21 UTC, the sun passes close to the zenith at the tropic of cancer
+        let start = 1_371_772_800_000;
+        let windows = glint_windows(
+            start,
+            start + 86_400_000,
+            23.4,
+            0.0,
+            ViewGeometry::NADIR,
+            10.0,
+        );
+        assert_eq!(windows.len(), 1);
+        let (from, to) = windows[0];
+        let noon = crate::solar_noon(start + 43_200_000, 0.0);
+        assert!(from < noon && noon < to);
+        // no glint in winter at high latitudes
+        assert!(glint_windows(
+            1_387_584_000_000,
+            1_387_670_400_000,
+            60.0,
+            0.0,
+            ViewGeometry::NADIR,
+            30.0
+        )
+        .is_empty());
+    }
+}
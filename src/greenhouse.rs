@@ -0,0 +1,269 @@
+//! Planning of supplemental lighting in greenhouses to reach a target
+//! [daily light integral](https://en.wikipedia.org/wiki/Daily_light_integral) or
+//! [photoperiod](https://en.wikipedia.org/wiki/Photoperiodism).
+//!
+//! The natural light is estimated for a clear sky, so the plans are a lower bound of the
+//! lighting needed on cloudy days. The lamps extend the natural day, i.e. they run
+//! before sunrise, after sunset or both.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::greenhouse::{season_schedule, Greenhouse, LightTarget, Placement};
+//!
+//! let greenhouse = Greenhouse {
+//!     transmission: 0.7,
+//!     lamp_ppfd: 150.0,
+//! };
+//! // four weeks from 2024-12-01 UTC in the Netherlands
+//! let plans = season_schedule(
+//!     1_733_011_200_000,
+//!     28,
+//!     52.0,
+//!     4.4,
+//!     &greenhouse,
+//!     LightTarget::Photoperiod(16.0),
+//!     Placement::Split,
+//! );
+//! for plan in plans {
+//!     println!("{:.1} hours of lighting in {:?}", plan.supplemental_hours, plan.windows);
+//! }
+//! ```
+
+use crate::irradiance::clear_sky;
+use crate::report::event;
+use crate::{local_noon, pos, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+const MILLISECONDS_PER_MINUTE: i64 = 60 * 1_000;
+const MILLISECONDS_PER_HOUR: f64 = 60.0 * 60.0 * 1_000.0;
+/// Photosynthetic photon flux per watt of global radiation in µmol/J.
+const PPFD_PER_WATT: f64 = 2.02;
+
+/// Light requirement of a crop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightTarget {
+    /// Daily light integral inside the greenhouse in mol/m² per day.
+    DailyLightIntegral(f64),
+    /// Hours of light per day, natural and supplemental.
+    Photoperiod(f64),
+}
+
+/// When the lamps run relative to the natural day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Before sunrise, ending at sunrise.
+    BeforeSunrise,
+    /// After sunset, starting at sunset.
+    AfterSunset,
+    /// Half before sunrise and half after sunset.
+    Split,
+}
+
+/// Properties of a greenhouse and its lamps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Greenhouse {
+    /// Fraction of the outside light reaching the crop, between 0 and 1.
+    pub transmission: f64,
+    /// Photon flux of the lamps at the crop in µmol/m²/s.
+    pub lamp_ppfd: f64,
+}
+
+/// Supplemental lighting of a day, see [`lighting_plan`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightingPlan {
+    /// Sunrise, `None` during polar day or polar night.
+    pub sunrise: Option<i64>,
+    /// Sunset, `None` during polar day or polar night.
+    pub sunset: Option<i64>,
+    /// Natural daily light integral inside the greenhouse in mol/m².
+    pub natural_dli: f64,
+    /// Hours between sunrise and sunset.
+    pub natural_photoperiod_hours: f64,
+    /// Hours the lamps run, at most the length of the night.
+    pub supplemental_hours: f64,
+    /// Periods in which the lamps run.
+    pub windows: Vec<(i64, i64)>,
+}
+
+/// Estimates the photosynthetic photon flux density of the sun on a horizontal surface
+/// under a clear sky in µmol/m²/s, see [`clear_sky`].
+///
+/// * `altitude` - altitude of the sun in radians.
+#[must_use]
+pub fn clear_sky_ppfd(altitude: f64) -> f64 {
+    clear_sky(altitude).ghi * PPFD_PER_WATT
+}
+
+/// Plans the supplemental lighting for the UTC day of `unixtime_in_ms`.
+///
+/// * `unixtime` - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`      - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`      - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// Without sunrise and sunset the lamps run in a single period around solar noon.
+#[must_use]
+pub fn lighting_plan(
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    greenhouse: &Greenhouse,
+    target: LightTarget,
+    placement: Placement,
+) -> LightingPlan {
+    let day_start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let noon = local_noon(day_start, lon);
+    let sunrise = event(noon, SunPhase::Sunrise, lat, lon);
+    let sunset = event(noon, SunPhase::Sunset, lat, lon);
+    #[allow(clippy::cast_precision_loss)]
+    let natural_photoperiod_hours = match (sunrise, sunset) {
+        (Some(rise), Some(set)) => (set - rise) as f64 / MILLISECONDS_PER_HOUR,
+        _ if pos(noon, lat, lon).altitude > 0.0 => 24.0,
+        _ => 0.0,
+    };
+
+    let minute_start = noon - MILLISECONDS_PER_DAY_INT / 2 + MILLISECONDS_PER_MINUTE / 2;
+    let natural_dli = (0..MILLISECONDS_PER_DAY_INT / MILLISECONDS_PER_MINUTE)
+        .map(|minute| {
+            let sun = pos(minute_start + minute * MILLISECONDS_PER_MINUTE, lat, lon);
+            clear_sky_ppfd(sun.altitude)
+        })
+        .sum::<f64>()
+        * greenhouse.transmission
+        * 60.0
+        / 1e6;
+
+    let needed_hours = match target {
+        LightTarget::DailyLightIntegral(dli) => {
+            (dli - natural_dli).max(0.0) / (greenhouse.lamp_ppfd * 3_600.0 / 1e6)
+        }
+        LightTarget::Photoperiod(hours) => (hours - natural_photoperiod_hours).max(0.0),
+    };
+    let supplemental_hours = needed_hours.min(24.0 - natural_photoperiod_hours);
+    #[allow(clippy::cast_possible_truncation)]
+    let duration = (supplemental_hours * MILLISECONDS_PER_HOUR).round() as i64;
+
+    let windows = if duration == 0 {
+        Vec::new()
+    } else {
+        match (sunrise, sunset, placement) {
+            (Some(rise), Some(_), Placement::BeforeSunrise) => vec![(rise - duration, rise)],
+            (Some(_), Some(set), Placement::AfterSunset) => vec![(set, set + duration)],
+            (Some(rise), Some(set), Placement::Split) => vec![
+                (rise - duration / 2, rise),
+                (set, set + duration - duration / 2),
+            ],
+            _ => vec![(noon - duration / 2, noon + duration - duration / 2)],
+        }
+    };
+
+    LightingPlan {
+        sunrise,
+        sunset,
+        natural_dli,
+        natural_photoperiod_hours,
+        supplemental_hours,
+        windows,
+    }
+}
+
+/// Plans the supplemental lighting for `days` consecutive UTC days starting with the day
+/// of `start_in_ms`, see [`lighting_plan`].
+#[must_use]
+pub fn season_schedule(
+    start_in_ms: i64,
+    days: u32,
+    lat: f64,
+    lon: f64,
+    greenhouse: &Greenhouse,
+    target: LightTarget,
+    placement: Placement,
+) -> Vec<LightingPlan> {
+    (0..i64::from(days))
+        .map(|day| {
+            lighting_plan(
+                start_in_ms + day * MILLISECONDS_PER_DAY_INT,
+                lat,
+                lon,
+                greenhouse,
+                target,
+                placement,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const GREENHOUSE: Greenhouse = Greenhouse {
+        transmission: 0.7,
+        lamp_ppfd: 150.0,
+    };
+
+    #[test]
+    fn test_clear_sky_ppfd() {
+        assert!(clear_sky_ppfd(-0.1).abs() < 1e-9);
+        // about 2100 µmol/m²/s with the sun in the zenith
+        let zenith = clear_sky_ppfd(std::f64::consts::FRAC_PI_2);
+        assert!((2_000.0..2_200.0).contains(&zenith), "{zenith}");
+        assert!(clear_sky_ppfd(0.5) < clear_sky_ppfd(1.0));
+    }
+
+    #[test]
+    fn test_lighting_plan() {
+        // 2024-12-21 UTC in the Netherlands, a day of less than 8 hours
+        let winter = 1_734_739_200_000;
+        let plan = lighting_plan(
+            winter,
+            52.0,
+            4.4,
+            &GREENHOUSE,
+            LightTarget::Photoperiod(16.0),
+            Placement::Split,
+        );
+        assert!((plan.natural_photoperiod_hours + plan.supplemental_hours - 16.0).abs() < 1e-6);
+        let (sunrise, sunset) = (plan.sunrise.unwrap(), plan.sunset.unwrap());
+        assert_eq!(plan.windows.len(), 2);
+        assert_eq!(plan.windows[0].1, sunrise);
+        assert_eq!(plan.windows[1].0, sunset);
+        let lit: i64 = plan.windows.iter().map(|(from, to)| to - from).sum();
+        let lit_hours = f64::from(i32::try_from(lit / 1_000).unwrap()) / 3_600.0;
+        assert!((lit_hours - plan.supplemental_hours).abs() < 1e-3);
+
+        let plan = lighting_plan(
+            winter,
+            52.0,
+            4.4,
+            &GREENHOUSE,
+            LightTarget::DailyLightIntegral(12.0),
+            Placement::AfterSunset,
+        );
+        assert!(
+            plan.natural_dli > 1.0 && plan.natural_dli < 12.0,
+            "{}",
+            plan.natural_dli
+        );
+        let expected = (12.0 - plan.natural_dli) / (150.0 * 3_600.0 / 1e6);
+        assert!((plan.supplemental_hours - expected).abs() < 1e-9);
+        assert_eq!(plan.windows[0].0, plan.sunset.unwrap());
+
+        // the summer sun alone is enough, 2024-06-21 UTC
+        let summer = season_schedule(
+            1_718_928_000_000,
+            7,
+            52.0,
+            4.4,
+            &GREENHOUSE,
+            LightTarget::DailyLightIntegral(12.0),
+            Placement::Split,
+        );
+        assert_eq!(summer.len(), 7);
+        assert!(summer
+            .iter()
+            .all(|plan| plan.windows.is_empty() && plan.natural_dli > 30.0));
+    }
+}
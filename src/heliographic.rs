@@ -0,0 +1,112 @@
+//! Orientation of the solar disc for solar observers, e.g. to align sunspot drawings
+//! and images with [heliographic coordinates](https://en.wikipedia.org/wiki/Solar_coordinate_systems).
+//!
+//! The angles follow Meeus, Astronomical Algorithms, chapter 29, on top of the solar
+//! model of the crate, so they are accurate to a few hundredths of a degree.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::heliographic::solar_orientation;
+//!
+//! let orientation = solar_orientation(1_362_441_600_000);
+//! println!(
+//!     "P {:.2}°, B0 {:.2}°, L0 {:.2}° in Carrington rotation {}",
+//!     orientation.p_deg, orientation.b0_deg, orientation.l0_deg, orientation.carrington_rotation
+//! );
+//! ```
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+use crate::{solar_geometry, JULIAN_2000, OBLIQUITY_OF_EARTH};
+
+/// Inclination of the solar equator against the ecliptic.
+const SOLAR_EQUATOR_INCLINATION_DEG: f64 = 7.25;
+/// Precession of the equinox in ecliptic longitude per Julian century.
+const GENERAL_PRECESSION_DEG: f64 = 1.396_971;
+/// Synodic period of the Carrington rotation in days.
+const CARRINGTON_PERIOD: f64 = 27.275_231_6;
+/// Julian day at which the Carrington rotation number 0 started.
+const CARRINGTON_EPOCH: f64 = 2_398_140.227;
+
+/// Orientation of the solar disc as seen from the earth, see [`solar_orientation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolarOrientation {
+    /// Position angle of the northern rotation axis in degrees, measured from the north
+    /// point of the disc towards east, in `[-180, 180)`.
+    pub p_deg: f64,
+    /// Heliographic latitude of the center of the disc in degrees.
+    pub b0_deg: f64,
+    /// Heliographic longitude of the center of the disc in degrees, in `[0, 360)`.
+    pub l0_deg: f64,
+    /// Number of the current [Carrington rotation](https://en.wikipedia.org/wiki/Solar_rotation#Carrington_rotation).
+    pub carrington_rotation: i64,
+}
+
+/// Calculates the orientation of the solar disc.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[must_use]
+pub fn solar_orientation(unixtime_in_ms: i64) -> SolarOrientation {
+    let geometry = solar_geometry(unixtime_in_ms, 0.0);
+    let julian_day = geometry.julian_day;
+    // the model refers to the equinox of J2000, the angles to the equinox of date
+    let centuries = (julian_day - JULIAN_2000) / 36_525.0;
+    let longitude = geometry.ecliptic_longitude + (GENERAL_PRECESSION_DEG * centuries).to_radians();
+    let inclination = SOLAR_EQUATOR_INCLINATION_DEG.to_radians();
+    // longitude of the ascending node of the solar equator on the ecliptic
+    let node = (73.666_7 + 1.395_833_3 * (julian_day - 2_396_758.0) / 36_525.0).to_radians();
+    // rotation of the sun since the epoch of the heliographic longitudes
+    let rotation = (julian_day - 2_398_220.0) * 360.0 / 25.38;
+
+    let x = (-longitude.cos() * OBLIQUITY_OF_EARTH.tan()).atan();
+    let y = (-(longitude - node).cos() * inclination.tan()).atan();
+    let b0 = ((longitude - node).sin() * inclination.sin()).asin();
+    let eta = (-(longitude - node).sin() * inclination.cos()).atan2(-(longitude - node).cos());
+    let l0_deg = (eta.to_degrees() - rotation).rem_euclid(360.0);
+
+    // the rotation starts when the center of the disc passes longitude 0
+    let elapsed = (julian_day - CARRINGTON_EPOCH) / CARRINGTON_PERIOD;
+    #[allow(clippy::cast_possible_truncation)]
+    let carrington_rotation = (elapsed - (360.0 - l0_deg) / 360.0).round() as i64;
+
+    SolarOrientation {
+        p_deg: ((x + y).to_degrees() + 180.0).rem_euclid(360.0) - 180.0,
+        b0_deg: b0.to_degrees(),
+        l0_deg,
+        carrington_rotation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_meeus_example() {
+        // example 29.a, 1992-10-13 00:00
+        let orientation = solar_orientation(718_934_400_000);
+        assert!((orientation.p_deg - 26.27).abs() < 0.05, "{orientation:?}");
+        assert!((orientation.b0_deg - 5.99).abs() < 0.05, "{orientation:?}");
+        assert!(
+            (orientation.l0_deg - 238.63).abs() < 0.05,
+            "{orientation:?}"
+        );
+        assert_eq!(orientation.carrington_rotation, 1861);
+    }
+
+    #[test]
+    fn test_annual_cycle() {
+        // around June 6 and December 7 the solar equator appears edge-on
+        let june = solar_orientation(1_370_476_800_000);
+        assert!(june.b0_deg.abs() < 0.2, "{june:?}");
+        // in early September the north pole is tilted towards the earth the most
+        let september = solar_orientation(1_378_598_400_000);
+        assert!((september.b0_deg - 7.25).abs() < 0.1, "{september:?}");
+        // about 13.2° of rotation per day as seen from the earth
+        let next_day = solar_orientation(1_378_598_400_000 + 24 * 60 * 60 * 1_000);
+        let rotation = (september.l0_deg - next_day.l0_deg).rem_euclid(360.0);
+        assert!((rotation - 13.2).abs() < 0.1, "{rotation}");
+    }
+}
@@ -0,0 +1,215 @@
+//! The state of the [sun integration](https://www.home-assistant.io/integrations/sun/)
+//! of Home Assistant.
+//!
+//! [`SunState::to_json`] writes the attributes exactly like the `sun.sun` entity, i.e.
+//! with the same keys in the same order, ISO 8601 times in UTC and angles rounded to two
+//! decimals, so that automations and dashboards keep working when the state is published
+//! by this crate instead.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::home_assistant::SunState;
+//!
+//! let state = SunState::new(1_362_441_600_000, 48.0, 9.0);
+//! assert_eq!(state.state(), "below_horizon");
+//! println!("{}", state.to_json());
+//! ```
+
+use crate::calendar::civil_from_days;
+use crate::report::next_event;
+use crate::{local_noon, pos, solar_noon, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+/// Days searched for the next rising or setting, enough to leave the polar night.
+const MAX_DAYS: i64 = 366;
+
+/// The state of the sun at a point in time, as exposed by Home Assistant.
+///
+/// Times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds and
+/// `None` if the event doesn't occur within a year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunState {
+    /// Next civil dawn.
+    pub next_dawn: Option<i64>,
+    /// Next civil dusk.
+    pub next_dusk: Option<i64>,
+    /// Next solar midnight.
+    pub next_midnight: i64,
+    /// Next solar noon.
+    pub next_noon: i64,
+    /// Next sunrise.
+    pub next_rising: Option<i64>,
+    /// Next sunset.
+    pub next_setting: Option<i64>,
+    /// Altitude of the sun in degrees, rounded to two decimals.
+    pub elevation: f64,
+    /// Azimuth of the sun in degrees clockwise from north, rounded to two decimals.
+    pub azimuth: f64,
+    /// Whether the sun is rising, i.e. noon comes before midnight.
+    pub rising: bool,
+}
+
+impl SunState {
+    /// Calculates the state at `unixtime_in_ms`.
+    ///
+    /// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    /// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    #[must_use]
+    pub fn new(unixtime_in_ms: i64, lat: f64, lon: f64) -> Self {
+        let today = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+        let noons = (-1..=2)
+            .map(|day| solar_noon(local_noon(today + day * MILLISECONDS_PER_DAY_INT, lon), lon));
+        let next_noon = noons
+            .clone()
+            .find(|&noon| noon > unixtime_in_ms)
+            .unwrap_or(today + 2 * MILLISECONDS_PER_DAY_INT);
+        let next_midnight = noons
+            .map(|noon| noon + MILLISECONDS_PER_DAY_INT / 2)
+            .find(|&midnight| midnight > unixtime_in_ms)
+            .unwrap_or(next_noon + MILLISECONDS_PER_DAY_INT / 2);
+        let position = pos(unixtime_in_ms, lat, lon);
+        let next = |phase| next_event(unixtime_in_ms, phase, lat, lon, MAX_DAYS);
+        Self {
+            next_dawn: next(SunPhase::Dawn),
+            next_dusk: next(SunPhase::Dusk),
+            next_midnight,
+            next_noon,
+            next_rising: next(SunPhase::Sunrise),
+            next_setting: next(SunPhase::Sunset),
+            elevation: round_2(position.altitude.to_degrees()),
+            azimuth: round_2(position.azimuth.to_degrees()),
+            rising: next_noon < next_midnight,
+        }
+    }
+
+    /// The state of the entity, `"above_horizon"` while the next setting comes before
+    /// the next rising and `"below_horizon"` otherwise.
+    #[must_use]
+    pub fn state(&self) -> &'static str {
+        match (self.next_rising, self.next_setting) {
+            (Some(rising), Some(setting)) if rising > setting => "above_horizon",
+            (None, Some(_)) => "above_horizon",
+            (None, None) if self.elevation > 0.0 => "above_horizon",
+            _ => "below_horizon",
+        }
+    }
+
+    /// Writes the attributes as compact JSON like Home Assistant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sun::home_assistant::SunState;
+    ///
+    /// let json = SunState::new(1_362_441_600_000, 48.0, 9.0).to_json();
+    /// assert!(json.starts_with(r#"{"next_dawn":"2013-03-05T05:"#));
+    /// assert!(json.ends_with(r#""rising":true}"#));
+    /// ```
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let times = [
+            ("next_dawn", self.next_dawn),
+            ("next_dusk", self.next_dusk),
+            ("next_midnight", Some(self.next_midnight)),
+            ("next_noon", Some(self.next_noon)),
+            ("next_rising", self.next_rising),
+            ("next_setting", self.next_setting),
+        ];
+        let mut fields: Vec<String> = times
+            .iter()
+            .map(|(key, time)| match time {
+                Some(time) => format!(r#""{key}":"{}""#, isoformat(*time)),
+                None => format!(r#""{key}":null"#),
+            })
+            .collect();
+        fields.push(format!(r#""elevation":{}"#, python_float(self.elevation)));
+        fields.push(format!(r#""azimuth":{}"#, python_float(self.azimuth)));
+        fields.push(format!(r#""rising":{}"#, self.rising));
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+fn round_2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Formats a float like Python's `repr`, i.e. always with a decimal point.
+fn python_float(value: f64) -> String {
+    let text = value.to_string();
+    if text.contains('.') {
+        text
+    } else {
+        text + ".0"
+    }
+}
+
+/// Formats a time like Python's `datetime.isoformat` in UTC, with microseconds unless
+/// they are zero.
+fn isoformat(unixtime_in_ms: i64) -> String {
+    let (year, month, day) = civil_from_days(unixtime_in_ms.div_euclid(MILLISECONDS_PER_DAY_INT));
+    let ms = unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let (hour, minute, second, millis) = (
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1_000 % 60,
+        ms % 1_000,
+    );
+    let fraction = if millis == 0 {
+        String::new()
+    } else {
+        format!(".{:06}", millis * 1_000)
+    };
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}{fraction}+00:00")
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(
+            isoformat(1_362_463_116_241),
+            "2013-03-05T05:58:36.241000+00:00"
+        );
+        assert_eq!(isoformat(1_362_441_600_000), "2013-03-05T00:00:00+00:00");
+        assert_eq!(python_float(45.0), "45.0");
+        assert_eq!(python_float(-0.0), "-0.0");
+        assert_eq!(python_float(round_2(12.345_6)), "12.35");
+    }
+
+    #[test]
+    fn test_state() {
+        // 2013-03-05 12:00 UTC in Kyiv, in the afternoon
+        let noon = 1_362_484_800_000;
+        let state = SunState::new(noon, 50.5, 30.5);
+        assert_eq!(state.state(), "above_horizon");
+        assert!(!state.rising);
+        assert_eq!(
+            state.next_setting,
+            Some(crate::time_at_phase(
+                noon,
+                SunPhase::Sunset,
+                50.5,
+                30.5,
+                0.0
+            ))
+        );
+        assert!(state.next_rising.unwrap() > state.next_setting.unwrap());
+        assert!(state.next_midnight < state.next_noon);
+        let json = state.to_json();
+        assert!(json.starts_with(r#"{"next_dawn":"2013-03-06T"#));
+        assert!(json.ends_with(&format!(
+            r#""elevation":{},"azimuth":{},"rising":false}}"#,
+            python_float(state.elevation),
+            python_float(state.azimuth)
+        )));
+        // the polar night of Longyearbyen ends in February
+        let polar = SunState::new(1_387_627_200_000, 78.2, 15.6);
+        assert_eq!(polar.state(), "below_horizon");
+        assert!(polar.next_rising.unwrap() - 1_387_627_200_000 > 50 * MILLISECONDS_PER_DAY_INT);
+    }
+}
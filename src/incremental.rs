@@ -0,0 +1,161 @@
+//! Cheap position updates at a high rate, e.g. for heliostats and solar trackers.
+//!
+//! A [`PositionTracker`] computes the full position only every
+//! [`max_interval_ms`](PositionTracker::max_interval_ms) and propagates the azimuth and
+//! the altitude linearly in between, which costs no trigonometry at all. With the default
+//! interval of a minute the propagated position stays within 0.01° of [`pos`] except
+//! within a degree of the zenith, where the azimuth changes too fast for any tracker.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::incremental::PositionTracker;
+//!
+//! let mut tracker = PositionTracker::new(1_362_484_800_000, 48.0, 9.0);
+//! for _ in 0..10 {
+//!     // 10 Hz
+//!     let position = tracker.advance(100);
+//!     println!("{:.3}", position.azimuth.to_degrees());
+//! }
+//! ```
+
+use core::f64::consts::PI;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+use crate::{pos, Position};
+
+/// Default time between two full computations in milliseconds.
+const DEFAULT_MAX_INTERVAL_MS: i64 = 60_000;
+/// Time step of the finite difference for the rates in milliseconds.
+const RATE_STEP_MS: i64 = 1_000;
+
+/// Propagates the sun position from the last full computation.
+#[derive(Debug, Clone, Copy)]
+pub struct PositionTracker {
+    /// [Latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    lat: f64,
+    /// [Longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    lon: f64,
+    /// Maximal time since the last full computation in milliseconds.
+    pub max_interval_ms: i64,
+    /// Time of the last full computation.
+    anchor_in_ms: i64,
+    /// Position at the time of the last full computation.
+    anchor: Position,
+    /// Change of the azimuth in radians per millisecond.
+    azimuth_rate: f64,
+    /// Change of the altitude in radians per millisecond.
+    altitude_rate: f64,
+    /// Time of the last update.
+    unixtime_in_ms: i64,
+}
+
+impl PositionTracker {
+    /// Creates a tracker with a full computation at `unixtime_in_ms`.
+    ///
+    /// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    /// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    #[must_use]
+    pub fn new(unixtime_in_ms: i64, lat: f64, lon: f64) -> Self {
+        let mut tracker = Self {
+            lat,
+            lon,
+            max_interval_ms: DEFAULT_MAX_INTERVAL_MS,
+            anchor_in_ms: unixtime_in_ms,
+            anchor: Position {
+                azimuth: 0.0,
+                altitude: 0.0,
+            },
+            azimuth_rate: 0.0,
+            altitude_rate: 0.0,
+            unixtime_in_ms,
+        };
+        tracker.recompute(unixtime_in_ms);
+        tracker
+    }
+
+    /// Sets the maximal time between two full computations in milliseconds.
+    #[must_use]
+    pub const fn with_max_interval(mut self, max_interval_ms: i64) -> Self {
+        self.max_interval_ms = max_interval_ms;
+        self
+    }
+
+    /// Time of the last update.
+    #[must_use]
+    pub const fn unixtime_in_ms(&self) -> i64 {
+        self.unixtime_in_ms
+    }
+
+    fn recompute(&mut self, unixtime_in_ms: i64) {
+        let anchor = pos(unixtime_in_ms, self.lat, self.lon);
+        let next = pos(unixtime_in_ms + RATE_STEP_MS, self.lat, self.lon);
+        let azimuth_change = (next.azimuth - anchor.azimuth + PI).rem_euclid(2.0 * PI) - PI;
+        #[allow(clippy::cast_precision_loss)]
+        let step = RATE_STEP_MS as f64;
+        self.azimuth_rate = azimuth_change / step;
+        self.altitude_rate = (next.altitude - anchor.altitude) / step;
+        self.anchor = anchor;
+        self.anchor_in_ms = unixtime_in_ms;
+    }
+
+    /// Returns the position at `unixtime_in_ms`.
+    ///
+    /// Times before the last full computation or more than
+    /// [`max_interval_ms`](Self::max_interval_ms) after it trigger a new full computation.
+    pub fn at(&mut self, unixtime_in_ms: i64) -> Position {
+        let elapsed = unixtime_in_ms - self.anchor_in_ms;
+        if !(0..=self.max_interval_ms).contains(&elapsed) {
+            self.recompute(unixtime_in_ms);
+        }
+        self.unixtime_in_ms = unixtime_in_ms;
+        #[allow(clippy::cast_precision_loss)]
+        let elapsed = (unixtime_in_ms - self.anchor_in_ms) as f64;
+        Position {
+            azimuth: (self.anchor.azimuth + self.azimuth_rate * elapsed).rem_euclid(2.0 * PI),
+            altitude: self.anchor.altitude + self.altitude_rate * elapsed,
+        }
+    }
+
+    /// Advances the time of the last update by `delta_in_ms` and returns the position.
+    pub fn advance(&mut self, delta_in_ms: i64) -> Position {
+        self.at(self.unixtime_in_ms + delta_in_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_propagation_stays_close_to_pos() {
+        // 2013-03-05 06:00 UTC, through the whole day at 1 Hz
+        let start = 1_362_463_200_000;
+        let mut tracker = PositionTracker::new(start, 50.5, 30.5);
+        for _ in 0..12 * 60 * 60 {
+            let position = tracker.advance(1_000);
+            let expected = pos(tracker.unixtime_in_ms(), 50.5, 30.5);
+            let azimuth_error =
+                (position.azimuth - expected.azimuth + PI).rem_euclid(2.0 * PI) - PI;
+            assert!(azimuth_error.to_degrees().abs() < 0.01);
+            assert!((position.altitude - expected.altitude).to_degrees().abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_recompute() {
+        let start = 1_362_463_200_000;
+        let mut tracker = PositionTracker::new(start, 50.5, 30.5).with_max_interval(10_000);
+        tracker.advance(5_000);
+        assert_eq!(tracker.anchor_in_ms, start);
+        tracker.advance(6_000);
+        assert_eq!(tracker.anchor_in_ms, start + 11_000);
+        // going back in time
+        let position = tracker.at(start);
+        assert_eq!(tracker.anchor_in_ms, start);
+        assert!((position.altitude - pos(start, 50.5, 30.5).altitude).abs() < f64::EPSILON);
+    }
+}
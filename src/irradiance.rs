@@ -0,0 +1,151 @@
+//! Solar irradiance on a horizontal surface under a clear or a clouded sky, e.g. to
+//! estimate the energy yield from the cloudiness of a weather forecast.
+//!
+//! The clear sky follows the air mass model of Meinel, the clouds the model of
+//! [Kasten and Czeplak](https://doi.org/10.1016/0038-092X(80)90391-6), which reduces the
+//! global irradiance to a quarter under an overcast sky. Clouds also turn the direct
+//! radiation into diffuse radiation, until an overcast sky leaves no direct radiation.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::irradiance::{irradiance, CloudCover};
+//!
+//! let now = 1_362_484_800_000;
+//! let clear = irradiance(now, 48.0, 9.0, CloudCover::CLEAR);
+//! let cloudy = irradiance(now, 48.0, 9.0, CloudCover::from_oktas(6));
+//! println!("{:.0} W/m² instead of {:.0} W/m²", cloudy.ghi, clear.ghi);
+//! ```
+
+use crate::pos;
+
+/// Irradiance of the sun outside the atmosphere in W/m².
+const SOLAR_CONSTANT: f64 = 1361.0;
+/// Diffuse radiation of the clear sky as a fraction of the direct radiation.
+const CLEAR_SKY_DIFFUSE: f64 = 0.1;
+
+/// Components of the solar irradiance in W/m².
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Irradiance {
+    /// Global horizontal irradiance, the sum of the direct and the diffuse radiation on
+    /// a horizontal surface.
+    pub ghi: f64,
+    /// Direct normal irradiance, the direct radiation on a surface facing the sun.
+    pub dni: f64,
+    /// Diffuse horizontal irradiance, the radiation of the sky on a horizontal surface.
+    pub dhi: f64,
+}
+
+/// Fraction of the sky covered by clouds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CloudCover(f64);
+
+impl CloudCover {
+    /// A sky without clouds.
+    pub const CLEAR: Self = Self(0.0);
+    /// A sky completely covered by clouds.
+    pub const OVERCAST: Self = Self(1.0);
+
+    /// Creates a cloud cover from a fraction between 0 and 1, clamped to that range.
+    #[must_use]
+    pub fn from_fraction(fraction: f64) -> Self {
+        Self(fraction.clamp(0.0, 1.0))
+    }
+
+    /// Creates a cloud cover from [oktas](https://en.wikipedia.org/wiki/Okta), eighths of
+    /// the sky, with values above 8 treated as 8.
+    #[must_use]
+    pub fn from_oktas(oktas: u8) -> Self {
+        Self(f64::from(oktas.min(8)) / 8.0)
+    }
+
+    /// Returns the covered fraction of the sky between 0 and 1.
+    #[must_use]
+    pub const fn fraction(self) -> f64 {
+        self.0
+    }
+}
+
+/// Estimates the irradiance under a clear sky for an altitude of the sun in radians.
+#[must_use]
+pub fn clear_sky(altitude: f64) -> Irradiance {
+    if altitude <= 0.0 {
+        return Irradiance::default();
+    }
+    // Kasten and Young
+    let air_mass =
+        1.0 / (altitude.sin() + 0.50572 * (altitude.to_degrees() + 6.07995).powf(-1.6364));
+    let dni = SOLAR_CONSTANT * 0.7_f64.powf(air_mass.powf(0.678));
+    let dhi = CLEAR_SKY_DIFFUSE * dni * altitude.sin();
+    Irradiance {
+        ghi: dni * altitude.sin() + dhi,
+        dni,
+        dhi,
+    }
+}
+
+/// Estimates the irradiance under a partly clouded sky for an altitude of the sun in
+/// radians.
+#[must_use]
+pub fn clouded_sky(altitude: f64, cover: CloudCover) -> Irradiance {
+    let clear = clear_sky(altitude);
+    if clear.ghi <= 0.0 {
+        return clear;
+    }
+    let n = cover.fraction();
+    let ghi = clear.ghi * (1.0 - 0.75 * n.powf(3.4));
+    let clear_diffuse = clear.dhi / clear.ghi;
+    let dhi = ghi * (clear_diffuse + (1.0 - clear_diffuse) * n * n);
+    Irradiance {
+        ghi,
+        dni: (ghi - dhi) / altitude.sin(),
+        dhi,
+    }
+}
+
+/// Estimates the irradiance at a place and time.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `cover`     - cloud cover, [`CloudCover::CLEAR`] for the clear-sky ceiling.
+#[must_use]
+pub fn irradiance(unixtime_in_ms: i64, lat: f64, lon: f64, cover: CloudCover) -> Irradiance {
+    clouded_sky(pos(unixtime_in_ms, lat, lon).altitude, cover)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_cloud_cover() {
+        assert_eq!(CloudCover::from_oktas(0), CloudCover::CLEAR);
+        assert_eq!(CloudCover::from_oktas(8), CloudCover::OVERCAST);
+        assert_eq!(CloudCover::from_oktas(9), CloudCover::OVERCAST);
+        assert!((CloudCover::from_oktas(4).fraction() - 0.5).abs() < 1e-12);
+        assert_eq!(CloudCover::from_fraction(1.5), CloudCover::OVERCAST);
+    }
+
+    #[test]
+    fn test_clouded_sky() {
+        let altitude = 40_f64.to_radians();
+        let clear = clear_sky(altitude);
+        assert!((clear.ghi - clear.dni * altitude.sin() - clear.dhi).abs() < 1e-9);
+        assert!(clear.ghi > 500.0 && clear.ghi < 700.0, "{clear:?}");
+        assert_eq!(clouded_sky(altitude, CloudCover::CLEAR), clear);
+        // an overcast sky leaves a quarter of the radiation, all of it diffuse
+        let overcast = clouded_sky(altitude, CloudCover::OVERCAST);
+        assert!((overcast.ghi - clear.ghi / 4.0).abs() < 1e-9);
+        assert!(overcast.dni.abs() < 1e-9);
+        assert!((overcast.dhi - overcast.ghi).abs() < 1e-9);
+        // a few clouds hardly matter
+        let scattered = clouded_sky(altitude, CloudCover::from_oktas(2));
+        assert!(scattered.ghi > 0.95 * clear.ghi && scattered.dni < clear.dni);
+        assert_eq!(
+            clouded_sky(-0.1, CloudCover::OVERCAST),
+            Irradiance::default()
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! [KML](https://developers.google.com/kml/documentation/kmlreference) export of the path
+//! of the sun, of shadows and of the [terminator](crate::terminator) for viewing in
+//! Google Earth.
+//!
+//! The functions return placemarks, which are combined into a file with [`document`].
+//! Placemarks of a point in time carry a `TimeStamp`, so the time slider of Google Earth
+//! can animate them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::kml::{document, shadow_placemark, sun_path_placemark, terminator_placemark};
+//!
+//! let (lat, lon) = (48.78, 9.18);
+//! let now = 1_371_816_000_000;
+//! // a 20 m high building with a square footprint
+//! let footprint = [(48.7801, 9.1801), (48.7801, 9.1799), (48.7799, 9.1799), (48.7799, 9.1801)];
+//! let kml = document(
+//!     "Sun in Stuttgart",
+//!     &[
+//!         sun_path_placemark(now, lat, lon, 100.0, 10),
+//!         shadow_placemark(now, &footprint, 20.0).unwrap(),
+//!         terminator_placemark(now, 0.0, 360),
+//!     ],
+//! );
+//! std::fs::write(std::env::temp_dir().join("sun.kml"), kml).unwrap();
+//! ```
+
+use std::f64::consts::PI;
+
+use crate::calendar::civil_from_days;
+use crate::report::event;
+use crate::terminator::terminator_lines;
+use crate::{pos, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+const MILLISECONDS_PER_MINUTE: i64 = 60 * 1_000;
+/// Mean radius of the earth in meters.
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+/// Escapes the characters with a meaning in XML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Formats a time as `xsd:dateTime` in UTC.
+fn date_time(unixtime_in_ms: i64) -> String {
+    let (year, month, day) = civil_from_days(unixtime_in_ms.div_euclid(MILLISECONDS_PER_DAY_INT));
+    let ms = unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1_000 % 60
+    )
+}
+
+/// Formats `(lat, lon, altitude)` tuples as the content of a `coordinates` element.
+fn coordinates(points: impl IntoIterator<Item = (f64, f64, f64)>) -> String {
+    let points: Vec<String> = points
+        .into_iter()
+        .map(|(lat, lon, altitude)| format!("{lon:.7},{lat:.7},{altitude:.1}"))
+        .collect();
+    format!("<coordinates>{}</coordinates>", points.join(" "))
+}
+
+/// Moves a point by the given distances in meters, which is accurate for distances much
+/// smaller than the radius of the earth.
+fn offset(lat: f64, lon: f64, north: f64, east: f64) -> (f64, f64) {
+    (
+        lat + (north / EARTH_RADIUS).to_degrees(),
+        lon + (east / (EARTH_RADIUS * lat.to_radians().cos())).to_degrees(),
+    )
+}
+
+/// Wraps placemarks into a KML document.
+#[must_use]
+pub fn document(name: &str, placemarks: &[String]) -> String {
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "\n",
+            r#"<kml xmlns="http://www.opengis.net/kml/2.2"><Document><name>{}</name>{}</Document></kml>"#,
+            "\n"
+        ),
+        escape(name),
+        placemarks.concat()
+    )
+}
+
+/// Returns the path of the sun over the UTC day of `unixtime_in_ms` as an arc on a dome
+/// of `radius_m` meters around the location, from sunrise to sunset.
+///
+/// Each point lies in the direction of the sun as seen from the location, so the arc
+/// shows where the sun rises, how high it climbs and where it sets. On days without
+/// sunrise or sunset the whole day above the horizon is drawn.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `radius_m`  - radius of the dome in meters.
+/// * `step_min`  - minutes between the points of the arc.
+#[must_use]
+pub fn sun_path_placemark(
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    radius_m: f64,
+    step_min: u32,
+) -> String {
+    let day_start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let noon = day_start + MILLISECONDS_PER_DAY_INT / 2;
+    let start = event(noon, SunPhase::Sunrise, lat, lon).unwrap_or(day_start);
+    let end =
+        event(noon, SunPhase::Sunset, lat, lon).unwrap_or(day_start + MILLISECONDS_PER_DAY_INT);
+    let step = i64::from(step_min.max(1)) * MILLISECONDS_PER_MINUTE;
+    let times = (0..)
+        .map(|i| start + i * step)
+        .take_while(|&time| time < end)
+        .chain([end]);
+    let horizon = SunPhase::Sunrise.angle_deg().to_radians();
+    let points = times.filter_map(|time| {
+        let position = pos(time, lat, lon);
+        (position.altitude >= horizon).then(|| {
+            let bearing = position.azimuth;
+            let ground = radius_m * position.altitude.cos();
+            let (point_lat, point_lon) =
+                offset(lat, lon, ground * bearing.cos(), ground * bearing.sin());
+            (
+                point_lat,
+                point_lon,
+                radius_m * position.altitude.max(0.0).sin(),
+            )
+        })
+    });
+    format!(
+        "<Placemark><name>Sun path {}</name><LineString><altitudeMode>relativeToGround</altitudeMode>{}</LineString></Placemark>",
+        &date_time(day_start)[..10],
+        coordinates(points)
+    )
+}
+
+/// Returns the shadow of a vertical prism, e.g. a building, as a polygon on the ground,
+/// or `None` if the sun is below the horizon.
+///
+/// The polygon is the convex hull of the footprint and of its projection along the
+/// rays of the sun, which is the exact outline for convex footprints.
+///
+/// * `unixtime`   - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `footprint`  - corners of the footprint as `(lat, lon)` in degrees.
+/// * `height_m`   - height of the prism in meters.
+#[must_use]
+pub fn shadow_placemark(
+    unixtime_in_ms: i64,
+    footprint: &[(f64, f64)],
+    height_m: f64,
+) -> Option<String> {
+    #[allow(clippy::cast_precision_loss)]
+    let n = footprint.len().max(1) as f64;
+    let (lat, lon) = footprint
+        .iter()
+        .fold((0.0, 0.0), |(lat, lon), &(a, b)| (lat + a / n, lon + b / n));
+    let position = pos(unixtime_in_ms, lat, lon);
+    if footprint.is_empty() || position.altitude <= 0.0 {
+        return None;
+    }
+    let length = height_m / position.altitude.tan();
+    // the shadow points away from the sun
+    let bearing = position.azimuth + PI;
+    let (north, east) = (length * bearing.cos(), length * bearing.sin());
+    let mut corners: Vec<(f64, f64)> = footprint.to_vec();
+    corners.extend(
+        footprint
+            .iter()
+            .map(|&(lat, lon)| offset(lat, lon, north, east)),
+    );
+    let mut hull = convex_hull(corners);
+    if let Some(&first) = hull.first() {
+        hull.push(first);
+    }
+    Some(format!(
+        "<Placemark><name>Shadow</name><TimeStamp><when>{}</when></TimeStamp><Polygon><outerBoundaryIs><LinearRing>{}</LinearRing></outerBoundaryIs></Polygon></Placemark>",
+        date_time(unixtime_in_ms),
+        coordinates(hull.into_iter().map(|(lat, lon)| (lat, lon, 0.0)))
+    ))
+}
+
+/// Convex hull of `(lat, lon)` points in counterclockwise order, as KML expects for outer
+/// boundaries.
+fn convex_hull(mut points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    points.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.total_cmp(&b.0)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    // counterclockwise with the longitude as x and the latitude as y
+    let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| {
+        (a.1 - o.1) * (b.0 - o.0) - (a.0 - o.0) * (b.1 - o.1)
+    };
+    let mut hull: Vec<(f64, f64)> = Vec::with_capacity(points.len() * 2);
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // the last point of a pass is the first point of the next one
+        hull.pop();
+    }
+    hull
+}
+
+/// Returns the lines along which the sun stands `depression_deg` below the horizon, see
+/// [`terminator_lines`].
+///
+/// * `unixtime`       - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `depression_deg` - depression of the sun below the horizon in degrees.
+/// * `n_points`       - number of points on the whole line.
+#[must_use]
+pub fn terminator_placemark(unixtime_in_ms: i64, depression_deg: f64, n_points: usize) -> String {
+    let lines: Vec<String> = terminator_lines(unixtime_in_ms, depression_deg, n_points)
+        .iter()
+        .map(|line| {
+            let points = line.iter().map(|point| (point.lat, point.lon, 0.0));
+            format!(
+                "<LineString><tessellate>1</tessellate>{}</LineString>",
+                coordinates(points)
+            )
+        })
+        .collect();
+    format!(
+        "<Placemark><name>Terminator {depression_deg}°</name><TimeStamp><when>{}</when></TimeStamp><MultiGeometry>{}</MultiGeometry></Placemark>",
+        date_time(unixtime_in_ms),
+        lines.concat()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_sun_path_placemark() {
+        // 2013-06-21 in Stuttgart
+        let kml = sun_path_placemark(1_371_816_000_000, 48.78, 9.18, 100.0, 60);
+        assert!(kml.starts_with("<Placemark><name>Sun path 2013-06-21</name>"));
+        let coordinates = kml
+            .split("<coordinates>")
+            .nth(1)
+            .unwrap()
+            .split("</coordinates>")
+            .next()
+            .unwrap();
+        let points: Vec<Vec<f64>> = coordinates
+            .split(' ')
+            .map(|point| point.split(',').map(|v| v.parse().unwrap()).collect())
+            .collect();
+        // about 16 hours of daylight
+        assert!(points.len() >= 16 && points.len() <= 18);
+        // rises in the north-east, stands high in the south at noon
+        assert!(points[0][2] < 1.0 && points[0][1] > 48.78 && points[0][0] > 9.18);
+        let highest = points.iter().max_by(|a, b| a[2].total_cmp(&b[2])).unwrap();
+        assert!(highest[2] > 90.0 && highest[1] < 48.78);
+    }
+
+    #[test]
+    fn test_shadow_and_document() {
+        let footprint = [
+            (48.7801, 9.1801),
+            (48.7801, 9.1799),
+            (48.7799, 9.1799),
+            (48.7799, 9.1801),
+        ];
+        // the shadow points north at noon
+        let shadow = shadow_placemark(1_371_814_800_000, &footprint, 20.0).unwrap();
+        assert!(shadow.contains("<when>2013-06-21T11:40:00Z</when>"));
+        assert_eq!(shadow.matches(',').count(), 2 * 7);
+        assert!(shadow_placemark(1_371_772_800_000, &footprint, 20.0).is_none());
+        assert_eq!(
+            convex_hull(vec![
+                (0.0, 0.0),
+                (1.0, 1.0),
+                (0.0, 1.0),
+                (0.5, 0.5),
+                (1.0, 0.0)
+            ]),
+            vec![(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)]
+        );
+        let kml = document(
+            "A & B",
+            &[shadow, terminator_placemark(1_371_814_800_000, 0.0, 36)],
+        );
+        assert!(kml.contains("<name>A &amp; B</name>"));
+        assert_eq!(kml.matches("<Placemark>").count(), 2);
+        assert!(kml.ends_with("</Document></kml>\n"));
+    }
+}
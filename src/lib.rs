@@ -18,18 +18,166 @@
 //! let time_ms = sun::time_at_phase(unixtime, sun::SunPhase::Sunrise, lat, lon, 0.0);
 //! assert_eq!(time_ms, 1_362_463_116_241);
 //! ```
+//!
+//! # `no_std`
+//!
+//! Without the default `std` feature the crate is `#![no_std]` and the float math comes
+//! from [`libm`](https://docs.rs/libm), which has to be enabled with the `libm` feature.
+//! The positions, the phase times and the modules [`alarm`], [`body`], [`coords`],
+//! [`delta_t`], [`elements`], [`fast_math`], [`heliographic`], [`incremental`] and
+//! [`refraction`] are available then, with the `alloc` feature also the lookups of
+//! [`almanac`]. Everything else needs the standard library.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("without the `std` feature the `libm` feature is required for the float math");
 
-use std::f64::consts::PI;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+extern crate alloc;
+
+use core::f64::consts::{FRAC_PI_2, PI};
+
+#[cfg(not(any(feature = "std", test)))]
+use math::Float as _;
+
+#[cfg(feature = "std")]
+pub mod accuracy;
+pub mod alarm;
+#[cfg(feature = "std")]
+pub mod alignment;
+#[cfg(feature = "alloc")]
+pub mod almanac;
+#[cfg(feature = "std")]
+pub mod animation;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod body;
+#[cfg(feature = "std")]
+pub mod buildings;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod calendar;
+#[cfg(feature = "std")]
+pub mod chronolocation;
+#[cfg(feature = "std")]
+pub mod compliance;
+#[cfg(feature = "std")]
+pub mod convention;
+pub mod coords;
+#[cfg(feature = "moon")]
+pub mod crescent;
+#[cfg(feature = "std")]
+pub mod cursor;
+#[cfg(feature = "std")]
+pub mod datetime;
+pub mod delta_t;
+#[cfg(feature = "std")]
+pub mod dms;
+#[cfg(feature = "chrono-tz")]
+pub mod dst;
+pub mod elements;
+pub mod fast_math;
+#[cfg(feature = "std")]
+pub mod format;
+#[cfg(feature = "export")]
+pub mod geojson;
+#[cfg(feature = "std")]
+pub mod glint;
+#[cfg(feature = "irradiance")]
+pub mod greenhouse;
+pub mod heliographic;
+#[cfg(feature = "std")]
+pub mod home_assistant;
+pub mod incremental;
+#[cfg(feature = "irradiance")]
+pub mod irradiance;
+#[cfg(feature = "export")]
+pub mod kml;
+#[cfg(feature = "std")]
+pub mod light_quality;
+#[cfg(feature = "wmm")]
+pub mod magnetic;
+#[cfg(feature = "image")]
+pub mod map;
+#[cfg(not(any(feature = "std", test)))]
+mod math;
+#[cfg(feature = "moon")]
+pub mod moon;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "std")]
+pub mod navigation;
+#[cfg(feature = "std")]
+pub mod observer;
+#[cfg(feature = "std")]
+pub mod observing;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod phenomena;
+#[cfg(feature = "std")]
+pub mod planets;
+#[cfg(feature = "std")]
+pub mod pv;
+#[cfg(feature = "std")]
+pub mod qibla;
+#[cfg(feature = "std")]
+pub mod raster;
+#[cfg(feature = "reference")]
+pub mod reference;
+pub mod refraction;
+#[cfg(feature = "std")]
+pub mod report;
+#[cfg(feature = "std")]
+mod search;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "simd")]
+pub mod simd;
+#[cfg(feature = "moon")]
+pub mod sky;
+#[cfg(feature = "std")]
+pub mod slope;
+#[cfg(feature = "std")]
+pub mod solar_time;
+#[cfg(feature = "std")]
+pub mod sundial;
+#[cfg(feature = "std")]
+pub mod terminator;
+#[cfg(feature = "std")]
+pub mod terrain;
+#[cfg(feature = "std")]
+pub mod theme;
+#[cfg(feature = "std")]
+pub mod times;
+#[cfg(feature = "std")]
+pub mod transits;
+#[cfg(feature = "ratatui")]
+pub mod tui;
+#[cfg(feature = "chrono-tz")]
+pub mod wall_clock;
 
 // date/time constants and conversions
 
+const MILLISECONDS_PER_HOUR: f64 = 1_000.0 * 60.0 * 60.0;
 const MILLISECONDS_PER_DAY: f64 = 1_000.0 * 60.0 * 60.0 * 24.0;
+pub(crate) const MILLISECONDS_PER_DAY_INT: i64 = 24 * 60 * 60 * 1_000;
 const JULIAN_0: f64 = 0.000_9;
 const JULIAN_1970: f64 = 2_440_588.0;
 const JULIAN_2000: f64 = 2_451_545.0;
 const TO_RAD: f64 = PI / 180.0;
 const OBLIQUITY_OF_EARTH: f64 = 23.439_7 * TO_RAD;
 const PERIHELION_OF_EARTH: f64 = 102.937_2 * TO_RAD;
+const ASTRONOMICAL_UNIT_IN_KM: f64 = 149_597_870.7;
+
+// accuracy estimates
+
+/// Typical altitude error of the simplified solar model in degrees.
+const MODEL_ALTITUDE_ERROR_DEG: f64 = 0.01;
+/// Typical variability of the atmospheric refraction close to the horizon in degrees.
+const REFRACTION_ALTITUDE_ERROR_DEG: f64 = 0.1;
 
 /// Holds the [azimuth](https://en.wikipedia.org/wiki/Azimuth)
 /// and [altitude](https://en.wikipedia.org/wiki/Horizontal_coordinate_system)
@@ -40,6 +188,165 @@ pub struct Position {
     pub altitude: f64,
 }
 
+/// Selects between the geometric and the observed altitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AltitudeKind {
+    /// Geometric altitude without atmospheric refraction, as returned by [`pos`].
+    #[default]
+    True,
+    /// Altitude as seen by an observer, lifted by atmospheric refraction.
+    Apparent,
+}
+
+/// Sun position with both the true and the apparent altitude.
+#[derive(Debug, Clone, Copy)]
+pub struct ObservedPosition {
+    pub azimuth: f64,
+    /// Geometric altitude in radians.
+    pub true_altitude: f64,
+    /// Altitude in radians including atmospheric refraction at standard conditions.
+    pub apparent_altitude: f64,
+}
+
+impl ObservedPosition {
+    /// Returns the requested altitude in radians.
+    #[must_use]
+    pub const fn altitude(&self, kind: AltitudeKind) -> f64 {
+        match kind {
+            AltitudeKind::True => self.true_altitude,
+            AltitudeKind::Apparent => self.apparent_altitude,
+        }
+    }
+
+    /// Returns a [`Position`] with the requested altitude.
+    #[must_use]
+    pub const fn position(&self, kind: AltitudeKind) -> Position {
+        Position {
+            azimuth: self.azimuth,
+            altitude: self.altitude(kind),
+        }
+    }
+}
+
+/// Calculates the sun position like [`pos`] with both the true (geometric) and the apparent
+/// (refracted) altitude. The refraction assumes 10 °C and 1010 hPa.
+///
+/// # Examples
+///
+/// ```rust
+/// let pos = sun::observed_pos(1_362_441_600_000, 48.0, 9.0);
+/// assert!(pos.apparent_altitude > pos.true_altitude);
+/// ```
+#[must_use]
+pub fn observed_pos(unixtime_in_ms: i64, lat: f64, lon: f64) -> ObservedPosition {
+    let Position { azimuth, altitude } = pos(unixtime_in_ms, lat, lon);
+    let refraction = refraction::refraction_from_true(
+        altitude,
+        refraction::STANDARD_TEMPERATURE,
+        refraction::STANDARD_PRESSURE,
+    );
+    ObservedPosition {
+        azimuth,
+        true_altitude: altitude,
+        apparent_altitude: altitude + refraction,
+    }
+}
+
+/// Calculates the signed angle in degrees from a heading to the sun,
+/// normalized to `(-180, 180]`.
+///
+/// Positive values mean the sun is to the right (clockwise) of the heading,
+/// negative values to the left.
+///
+/// * `pos`         - position of the sun, e.g. from [`pos`].
+/// * `heading_deg` - direction of travel or facing direction in degrees clockwise from north.
+///
+/// # Examples
+///
+/// ```rust
+/// let pos = sun::Position { azimuth: 90_f64.to_radians(), altitude: 0.3 };
+/// // facing north, the sun in the east is 90° to the right
+/// assert!((sun::relative_bearing(pos, 0.0) - 90.0).abs() < 1e-9);
+/// // facing 120°, it is 30° to the left
+/// assert!((sun::relative_bearing(pos, 120.0) + 30.0).abs() < 1e-9);
+/// ```
+#[must_use]
+pub fn relative_bearing(pos: Position, heading_deg: f64) -> f64 {
+    let bearing = (pos.azimuth.to_degrees() - heading_deg).rem_euclid(360.0);
+    if bearing > 180.0 {
+        bearing - 360.0
+    } else {
+        bearing
+    }
+}
+
+/// Shifts `azimuth` by multiples of a full turn so that it is as close as possible
+/// to `previous`, i.e. it removes the jump at the 0/2π boundary between two
+/// consecutive azimuths in radians.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::f64::consts::PI;
+///
+/// let unwrapped = sun::unwrap_azimuth(2.0 * PI - 0.1, 0.1);
+/// assert!((unwrapped - (2.0 * PI + 0.1)).abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn unwrap_azimuth(previous: f64, azimuth: f64) -> f64 {
+    let turn = 2.0 * PI;
+    azimuth + ((previous - azimuth) / turn).round() * turn
+}
+
+/// Turns a sequence of azimuths into a continuous signal without the 0/2π discontinuity,
+/// e.g. for plotting or as input of a control loop.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut tracker = sun::AzimuthTracker::default();
+/// let start = 1_362_441_600_000;
+/// for minute in 0..24 * 60 {
+///     let pos = sun::pos(start + minute * 60_000, 48.0, 9.0);
+///     let azimuth = tracker.update(pos.azimuth);
+///     // the azimuth keeps growing past 2π instead of jumping back to 0
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AzimuthTracker {
+    last: Option<f64>,
+}
+
+impl AzimuthTracker {
+    /// Returns `azimuth` unwrapped relative to the previous update.
+    pub fn update(&mut self, azimuth: f64) -> f64 {
+        let unwrapped = match self.last {
+            Some(previous) => unwrap_azimuth(previous, azimuth),
+            None => azimuth,
+        };
+        self.last = Some(unwrapped);
+        unwrapped
+    }
+
+    /// Returns the last unwrapped azimuth.
+    #[must_use]
+    pub const fn last(&self) -> Option<f64> {
+        self.last
+    }
+
+    /// Forgets the previous azimuth.
+    pub const fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Calculates the azimuth like [`pos`] but unwrapped relative to `previous_azimuth`,
+/// so that successive calls yield a continuous function of time.
+#[must_use]
+pub fn continuous_azimuth(unixtime_in_ms: i64, lat: f64, lon: f64, previous_azimuth: f64) -> f64 {
+    unwrap_azimuth(previous_azimuth, pos(unixtime_in_ms, lat, lon).azimuth)
+}
+
 const fn to_julian(unixtime_in_ms: f64) -> f64 {
     unixtime_in_ms / MILLISECONDS_PER_DAY - 0.5 + JULIAN_1970
 }
@@ -55,6 +362,28 @@ const fn to_days(unixtime_in_ms: f64) -> f64 {
 
 // general calculations for position
 
+/// The trigonometric functions of the position calculation, replaced by the
+/// approximations of [`fast_math`] with the `fast-math` feature.
+#[cfg(not(feature = "fast-math"))]
+mod trig {
+    #[cfg(not(any(feature = "std", test)))]
+    use crate::math::Float as _;
+
+    pub(crate) fn sin(x: f64) -> f64 {
+        x.sin()
+    }
+
+    pub(crate) fn cos(x: f64) -> f64 {
+        x.cos()
+    }
+
+    pub(crate) fn asin(x: f64) -> f64 {
+        x.asin()
+    }
+}
+#[cfg(feature = "fast-math")]
+use fast_math as trig;
+
 fn right_ascension(ecliptic_longitude: f64, ecliptic_latitude: f64) -> f64 {
     (ecliptic_longitude.sin() * OBLIQUITY_OF_EARTH.cos()
         - ecliptic_latitude.tan() * OBLIQUITY_OF_EARTH.sin())
@@ -68,16 +397,17 @@ fn declination(ecliptic_longitude: f64, ecliptic_latitude: f64) -> f64 {
 }
 
 fn azimuth(sidereal_time: f64, latitude_rad: f64, declination: f64) -> f64 {
-    sidereal_time
-        .sin()
-        .atan2(sidereal_time.cos() * latitude_rad.sin() - declination.tan() * latitude_rad.cos())
-        + PI
+    trig::sin(sidereal_time).atan2(
+        trig::cos(sidereal_time) * trig::sin(latitude_rad)
+            - declination.tan() * trig::cos(latitude_rad),
+    ) + PI
 }
 
 fn altitude(sidereal_time: f64, latitude_rad: f64, declination: f64) -> f64 {
-    (latitude_rad.sin() * declination.sin()
-        + latitude_rad.cos() * declination.cos() * sidereal_time.cos())
-    .asin()
+    trig::asin(
+        trig::sin(latitude_rad) * trig::sin(declination)
+            + trig::cos(latitude_rad) * trig::cos(declination) * trig::cos(sidereal_time),
+    )
 }
 
 fn sidereal_time(days: f64, longitude_rad: f64) -> f64 {
@@ -101,6 +431,39 @@ fn ecliptic_longitude(solar_mean_anomaly: f64) -> f64 {
     solar_mean_anomaly + equation_of_center(solar_mean_anomaly) + PERIHELION_OF_EARTH + PI
 }
 
+#[cfg(feature = "std")]
+fn sun_distance(solar_mean_anomaly: f64) -> f64 {
+    (1.000_14 - 0.016_71 * solar_mean_anomaly.cos() - 0.000_14 * (2.0 * solar_mean_anomaly).cos())
+        * ASTRONOMICAL_UNIT_IN_KM
+}
+
+/// Holds the horizontal and equatorial coordinates of the sun together
+/// with its distance, see [`full_pos`].
+#[derive(Debug, Clone, Copy)]
+pub struct FullPosition {
+    /// [azimuth](https://en.wikipedia.org/wiki/Azimuth) in radians.
+    pub azimuth: f64,
+    /// [altitude](https://en.wikipedia.org/wiki/Horizontal_coordinate_system) in radians.
+    pub altitude: f64,
+    /// [right ascension](https://en.wikipedia.org/wiki/Right_ascension) in radians, in `[0, 2π)`.
+    pub right_ascension: f64,
+    /// [declination](https://en.wikipedia.org/wiki/Declination) in radians.
+    pub declination: f64,
+    /// local [hour angle](https://en.wikipedia.org/wiki/Hour_angle) in radians, in `[-π, π)`.
+    pub hour_angle: f64,
+    /// distance between the centers of earth and sun in kilometers.
+    pub distance: f64,
+}
+
+impl From<FullPosition> for Position {
+    fn from(full: FullPosition) -> Self {
+        Position {
+            azimuth: full.azimuth,
+            altitude: full.altitude,
+        }
+    }
+}
+
 /// Calculates the sun position for a given date and latitude/longitude.
 /// The angles are calculated as [radians](https://en.wikipedia.org/wiki/Radian).
 ///
@@ -111,18 +474,258 @@ fn ecliptic_longitude(solar_mean_anomaly: f64) -> f64 {
 /// calculates the sun position for a given date and latitude/longitude
 #[must_use]
 pub fn pos(unixtime_in_ms: i64, lat: f64, lon: f64) -> Position {
-    let longitude_rad = -lon.to_radians();
+    pos_with_dut1(unixtime_in_ms, lat, lon, 0.0)
+}
+
+/// Calculates the sun positions for many sites at the same instant.
+///
+/// The orbit of the earth and the sidereal time are computed once for all sites, which
+/// makes this considerably faster than calling [`pos`] for each site, with identical results.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `sites`     - [latitude](https://en.wikipedia.org/wiki/Latitude) and
+///   [longitude](https://en.wikipedia.org/wiki/Longitude) of the sites in degrees.
+///
+/// # Examples
+///
+/// ```rust
+/// let sites = [(48.0, 9.0), (52.5, 13.4), (-33.9, 18.4)];
+/// let positions = sun::pos_many(1_362_441_600_000, &sites);
+/// assert_eq!(positions.len(), 3);
+/// assert_eq!(positions[1].altitude, sun::pos(1_362_441_600_000, 52.5, 13.4).altitude);
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn pos_many(unixtime_in_ms: i64, sites: &[(f64, f64)]) -> Vec<Position> {
+    let mut positions = Vec::with_capacity(sites.len());
+    for_each_pos(unixtime_in_ms, sites, |position| positions.push(position));
+    positions
+}
+
+/// Passes the positions of [`pos_many`] to `f` in the order of the sites.
+#[cfg(feature = "std")]
+pub(crate) fn for_each_pos(unixtime_in_ms: i64, sites: &[(f64, f64)], mut f: impl FnMut(Position)) {
+    let earth = body::Body::EARTH;
+    let days = earth.days(unixtime_in_ms);
+    let (right_ascension, declination) = earth.elements.equatorial(days);
+    for &(lat, lon) in sites {
+        let latitude_rad = lat.to_radians();
+        let hour_angle = earth.local_rotation(days, lon) - right_ascension;
+        f(Position {
+            azimuth: azimuth(hour_angle, latitude_rad, declination),
+            altitude: altitude(hour_angle, latitude_rad, declination),
+        });
+    }
+}
+
+/// Calculates the sun positions at many times for one site.
+///
+/// The sine and cosine of the latitude are computed once for all times, with identical
+/// results to calling [`pos`] for each time. The positions are written to `positions` in
+/// the order of the times, so a buffer can be reused for consecutive batches.
+///
+/// * `times`     - [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Panics
+///
+/// Panics if `times` and `positions` differ in length.
+///
+/// # Examples
+///
+/// ```rust
+/// // every second of 2013-03-05 UTC
+/// let times: Vec<i64> = (0..86_400).map(|s| 1_362_441_600_000 + s * 1_000).collect();
+/// let mut positions = vec![sun::Position { azimuth: 0.0, altitude: 0.0 }; times.len()];
+/// sun::pos_for_times(&times, 48.0, 9.0, &mut positions);
+/// assert_eq!(positions[43_200].altitude, sun::pos(times[43_200], 48.0, 9.0).altitude);
+/// ```
+pub fn pos_for_times(times: &[i64], lat: f64, lon: f64, positions: &mut [Position]) {
+    assert_eq!(
+        times.len(),
+        positions.len(),
+        "one position is needed per time"
+    );
+    let earth = body::Body::EARTH;
     let latitude_rad = lat.to_radians();
+    let (sin_lat, cos_lat) = (trig::sin(latitude_rad), trig::cos(latitude_rad));
+    for (&unixtime_in_ms, position) in times.iter().zip(positions) {
+        let days = earth.days(unixtime_in_ms);
+        let (right_ascension, declination) = earth.elements.equatorial(days);
+        let hour_angle = earth.local_rotation(days, lon) - right_ascension;
+        let (sin_hour_angle, cos_hour_angle) = (trig::sin(hour_angle), trig::cos(hour_angle));
+        // the formulas of `azimuth` and `altitude`
+        *position = Position {
+            azimuth: sin_hour_angle.atan2(cos_hour_angle * sin_lat - declination.tan() * cos_lat)
+                + PI,
+            altitude: trig::asin(
+                sin_lat * trig::sin(declination)
+                    + cos_lat * trig::cos(declination) * cos_hour_angle,
+            ),
+        };
+    }
+}
+
+/// Calculates the sun position like [`pos`] together with the equatorial coordinates,
+/// the hour angle and the distance of the sun.
+///
+/// # Examples
+///
+/// ```rust
+/// let full = sun::full_pos(1_362_441_600_000, 48.0, 9.0);
+/// // the sun is below the horizon at midnight, about one AU away
+/// assert!(full.altitude < 0.0);
+/// assert!((full.distance / 149_597_870.7 - 1.0).abs() < 0.02);
+/// ```
+#[must_use]
+pub fn full_pos(unixtime_in_ms: i64, lat: f64, lon: f64) -> FullPosition {
+    full_pos_with_dut1(unixtime_in_ms, lat, lon, 0.0)
+}
+
+/// Calculates the equatorial coordinates of the sun, e.g. for pointing a telescope.
+///
+/// The coordinates are geocentric and refer to the equinox of J2000 like the rest of the
+/// solar model, the right ascension is in `[0, 2π)`.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+///
+/// # Examples
+///
+/// ```rust
+/// // the sun is close to the celestial equator at the March equinox 2013
+/// let equatorial = sun::equatorial_pos(1_363_781_160_000);
+/// assert!(equatorial.declination.to_degrees().abs() < 0.1);
+/// ```
+#[must_use]
+pub fn equatorial_pos(unixtime_in_ms: i64) -> coords::Equatorial {
+    let earth = body::Body::EARTH;
+    let (right_ascension, declination) = earth.elements.equatorial(earth.days(unixtime_in_ms));
+    coords::Equatorial {
+        right_ascension: right_ascension.rem_euclid(2.0 * PI),
+        declination,
+    }
+}
+
+/// Calculates the [declination](https://en.wikipedia.org/wiki/Declination) of the sun in
+/// radians, the latitude at which the sun stands in the zenith at noon, see
+/// [`equatorial_pos`].
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+///
+/// # Examples
+///
+/// ```rust
+/// // the June solstice 2013, the sun stands above the tropic of cancer
+/// let declination = sun::declination_at(1_371_834_240_000);
+/// assert!((declination.to_degrees() - 23.44).abs() < 0.01);
+/// ```
+#[must_use]
+pub fn declination_at(unixtime_in_ms: i64) -> f64 {
+    equatorial_pos(unixtime_in_ms).declination
+}
+
+/// Calculates the sun position like [`pos`], applying a DUT1 correction to the
+/// rotation of the earth.
+///
+/// * `dut1` - difference UT1 − UTC in seconds as published by the
+///   [IERS](https://www.iers.org/), always within ±0.9 s.
+///
+/// Applications that need sub-second consistency with IERS data, e.g. for comparing
+/// against other ephemerides, can pass the current value; `0.0` gives the result of [`pos`].
+#[must_use]
+pub fn pos_with_dut1(unixtime_in_ms: i64, lat: f64, lon: f64, dut1: f64) -> Position {
+    body::pos_at(&body::Body::EARTH, unixtime_in_ms, lat, lon, dut1)
+}
+
+/// Calculates the sun position like [`full_pos`] with a DUT1 correction,
+/// see [`pos_with_dut1`].
+#[must_use]
+pub fn full_pos_with_dut1(unixtime_in_ms: i64, lat: f64, lon: f64, dut1: f64) -> FullPosition {
+    body::full_pos_at(&body::Body::EARTH, unixtime_in_ms, lat, lon, dut1)
+}
+
+/// Intermediate values of the solar model at an instant, e.g. to validate
+/// against other models or for teaching.
+#[derive(Debug, Clone, Copy)]
+pub struct SolarGeometry {
+    /// [julian day](https://en.wikipedia.org/wiki/Julian_day).
+    pub julian_day: f64,
+    /// [mean anomaly](https://en.wikipedia.org/wiki/Mean_anomaly) of the earth in radians.
+    pub mean_anomaly: f64,
+    /// [ecliptic longitude](https://en.wikipedia.org/wiki/Ecliptic_coordinate_system) of the sun in radians.
+    pub ecliptic_longitude: f64,
+    /// [right ascension](https://en.wikipedia.org/wiki/Right_ascension) in radians, in `[0, 2π)`.
+    pub right_ascension: f64,
+    /// [declination](https://en.wikipedia.org/wiki/Declination) in radians.
+    pub declination: f64,
+    /// [equation of time](https://en.wikipedia.org/wiki/Equation_of_time) in minutes,
+    /// positive if the sundial is ahead of the clock.
+    pub equation_of_time: f64,
+    /// local [hour angle](https://en.wikipedia.org/wiki/Hour_angle) in radians, in `[-π, π)`.
+    pub hour_angle: f64,
+}
+
+fn equation_of_time_rad(mean_anomaly: f64, right_ascension: f64) -> f64 {
+    let mean_longitude = mean_anomaly + PERIHELION_OF_EARTH + PI;
+    (mean_longitude - right_ascension + PI).rem_euclid(2.0 * PI) - PI
+}
+
+/// Calculates the intermediate values of the solar model for an instant.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees, only used for the hour angle.
+///
+/// # Examples
+///
+/// ```rust
+/// // 2013-02-11 UTC, close to the minimum of the equation of time
+/// let geometry = sun::solar_geometry(1_360_584_000_000, 0.0);
+/// assert!((geometry.equation_of_time + 14.2).abs() < 0.2);
+/// ```
+#[must_use]
+pub fn solar_geometry(unixtime_in_ms: i64, lon: f64) -> SolarGeometry {
     #[allow(clippy::cast_precision_loss)]
-    let days = to_days(unixtime_in_ms as f64);
-    let mean = solar_mean_anomaly(days);
-    let ecliptic_longitude = ecliptic_longitude(mean);
-    let declination = declination(ecliptic_longitude, 0.0);
+    let julian_day = to_julian(unixtime_in_ms as f64);
+    let days = julian_day - JULIAN_2000;
+    let mean_anomaly = solar_mean_anomaly(days);
+    let ecliptic_longitude = ecliptic_longitude(mean_anomaly);
     let right_ascension = right_ascension(ecliptic_longitude, 0.0);
-    let sidereal_time = sidereal_time(days, longitude_rad) - right_ascension;
-    let azimuth = azimuth(sidereal_time, latitude_rad, declination);
-    let altitude = altitude(sidereal_time, latitude_rad, declination);
-    Position { azimuth, altitude }
+    let declination = declination(ecliptic_longitude, 0.0);
+    let hour_angle = sidereal_time(days, -lon.to_radians()) - right_ascension;
+    SolarGeometry {
+        julian_day,
+        mean_anomaly: mean_anomaly.rem_euclid(2.0 * PI),
+        ecliptic_longitude: ecliptic_longitude.rem_euclid(2.0 * PI),
+        right_ascension: right_ascension.rem_euclid(2.0 * PI),
+        declination,
+        equation_of_time: equation_of_time_rad(mean_anomaly, right_ascension).to_degrees() * 4.0,
+        hour_angle: (hour_angle + PI).rem_euclid(2.0 * PI) - PI,
+    }
+}
+
+/// Calculates the [equation of time](https://en.wikipedia.org/wiki/Equation_of_time) in
+/// minutes, the difference between the time of a sundial and the local mean time.
+///
+/// It is positive if the sundial is ahead of the clock and stays within about ±16.5
+/// minutes over the year, see also [`SolarGeometry::equation_of_time`].
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+///
+/// # Examples
+///
+/// ```rust
+/// // 2013-11-03 UTC, close to the maximum of the equation of time
+/// let minutes = sun::equation_of_time(1_383_480_000_000);
+/// assert!((minutes - 16.4).abs() < 0.2);
+/// ```
+#[must_use]
+pub fn equation_of_time(unixtime_in_ms: i64) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let mean_anomaly = solar_mean_anomaly(days);
+    let right_ascension = right_ascension(ecliptic_longitude(mean_anomaly), 0.0);
+    equation_of_time_rad(mean_anomaly, right_ascension).to_degrees() * 4.0
 }
 
 fn julian_cycle(days: f64, longitude_rad: f64) -> f64 {
@@ -170,6 +773,9 @@ fn sunset_julian(
 /// Calculates the time for the given [`SunPhase`] at a given date, height and Latitude/Longitude.
 /// The returned time is the [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
 ///
+/// If the sun doesn't reach the altitude of the phase on that day, e.g. during polar day
+/// or polar night, the result is meaningless, see [`try_time_at_phase`].
+///
 /// # Arguments
 ///
 /// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
@@ -189,7 +795,6 @@ fn sunset_julian(
 /// let time_ms = sun::time_at_phase(unixtime, sun::SunPhase::Sunrise, lat, lon, height);
 /// assert_eq!(time_ms, 1_362_463_116_241);
 /// ```
-
 #[must_use]
 pub fn time_at_phase(
     unixtime_in_ms: i64,
@@ -198,39 +803,487 @@ pub fn time_at_phase(
     lon: f64,
     height: f64,
 ) -> i64 {
-    let longitude_rad = -lon.to_radians();
-    let latitude_rad = lat.to_radians();
-    let observer_angle = observer_angle(height);
-    #[allow(clippy::cast_precision_loss)]
-    let days = to_days(unixtime_in_ms as f64);
-    let julian_cycle = julian_cycle(days, longitude_rad);
-    let approx_transit = approx_transit(0.0, longitude_rad, julian_cycle);
-    let solar_mean_anomaly = solar_mean_anomaly(approx_transit);
-    let ecliptic_longitude = ecliptic_longitude(solar_mean_anomaly);
-    let declination = declination(ecliptic_longitude, 0.0);
-    let julian_noon = solar_transit_julian(approx_transit, solar_mean_anomaly, ecliptic_longitude);
+    from_julian(julian_at_phase(unixtime_in_ms, sun_phase, lat, lon, height))
+}
 
-    let altitude_angle = (sun_phase.angle_deg() + observer_angle).to_radians();
-    let julian_set = sunset_julian(
-        altitude_angle,
-        longitude_rad,
-        latitude_rad,
-        declination,
-        julian_cycle,
-        solar_mean_anomaly,
-        ecliptic_longitude,
-    );
+/// Calculates the time for the given [`SunPhase`] like [`time_at_phase`], applying a
+/// DUT1 correction to the rotation of the earth, see [`pos_with_dut1`].
+///
+/// # Examples
+///
+/// ```rust
+/// let time_ms = sun::time_at_phase_with_dut1(1_362_441_600_000, sun::SunPhase::Sunrise, 48.0, 9.0, 0.0, 0.3);
+/// assert_eq!(time_ms, 1_362_463_116_241 - 300);
+/// ```
+#[must_use]
+pub fn time_at_phase_with_dut1(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+    dut1: f64,
+) -> i64 {
+    // the earth reaches the rotation angle of the event earlier in UTC if UT1 is ahead
+    #[allow(clippy::cast_possible_truncation)]
+    let dut1_ms = (dut1 * 1_000.0).round() as i64;
+    time_at_phase(unixtime_in_ms, sun_phase, lat, lon, height) - dut1_ms
+}
 
-    if sun_phase.is_rise() {
-        let julian_rise = julian_noon - (julian_set - julian_noon);
-        from_julian(julian_rise)
-    } else {
-        from_julian(julian_set)
+/// Days searched for the previous or next occurrence of a phase, e.g. of the sunrise
+/// during polar night.
+const MAX_SEARCH_DAYS: i64 = 366;
+
+/// Local solar noon of the UTC date starting at `day_start_in_ms`, the reference time
+/// for the events of that date.
+pub(crate) fn local_noon(day_start_in_ms: i64, lon: f64) -> i64 {
+    #[allow(clippy::cast_possible_truncation)]
+    let offset = ((12.0 - lon / 15.0) * MILLISECONDS_PER_HOUR) as i64;
+    day_start_in_ms + offset
+}
+
+/// Time of a phase on the UTC day with the given number since 1970-01-01.
+fn time_at_phase_on_day(
+    day: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<i64> {
+    let day_start = day * MILLISECONDS_PER_DAY_INT;
+    Transit::new(local_noon(day_start, lon), lon)
+        .phase_outcome(sun_phase, lat, height)
+        .time()
+}
+
+/// Finds the first occurrence of a phase after `unixtime_in_ms` within the next `max_days` days.
+pub(crate) fn next_phase_within(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+    max_days: i64,
+) -> Option<i64> {
+    let today = unixtime_in_ms.div_euclid(MILLISECONDS_PER_DAY_INT);
+    // the events of a UTC day may lie on the neighbouring days
+    (today - 1..=today + max_days)
+        .filter_map(|day| time_at_phase_on_day(day, sun_phase, lat, lon, height))
+        .find(|&time| time > unixtime_in_ms)
+}
+
+/// Finds the next time of the given [`SunPhase`] after `unixtime_in_ms`, rolling over to
+/// the following days if the phase already passed today.
+///
+/// Returns `None` if the sun doesn't reach the altitude of the phase within a year.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `sun_phase` - [`SunPhase`] to calcuate time for
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`    - Observer height in meters above the horizon
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::SunPhase;
+///
+/// // 2013-03-05 18:00 UTC, after sunset in Stuttgart
+/// let sunset = sun::next_time_at_phase(1_362_506_400_000, SunPhase::Sunset, 48.0, 9.0, 0.0);
+/// assert!(sunset.unwrap() > 1_362_528_000_000);
+/// ```
+#[must_use]
+pub fn next_time_at_phase(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<i64> {
+    next_phase_within(unixtime_in_ms, sun_phase, lat, lon, height, MAX_SEARCH_DAYS)
+}
+
+/// Finds the last time of the given [`SunPhase`] before `unixtime_in_ms`, rolling over to
+/// the preceding days if the phase is still ahead today.
+///
+/// Returns `None` if the sun didn't reach the altitude of the phase within a year.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `sun_phase` - [`SunPhase`] to calcuate time for
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`    - Observer height in meters above the horizon
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::SunPhase;
+///
+/// // 2013-03-05 00:00 UTC, before sunrise in Stuttgart
+/// let sunrise = sun::prev_time_at_phase(1_362_441_600_000, SunPhase::Sunrise, 48.0, 9.0, 0.0);
+/// assert!(sunrise.unwrap() < 1_362_441_600_000);
+/// ```
+#[must_use]
+pub fn prev_time_at_phase(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<i64> {
+    let today = unixtime_in_ms.div_euclid(MILLISECONDS_PER_DAY_INT);
+    (today - MAX_SEARCH_DAYS..=today + 1)
+        .rev()
+        .filter_map(|day| time_at_phase_on_day(day, sun_phase, lat, lon, height))
+        .find(|&time| time < unixtime_in_ms)
+}
+
+/// Result of [`try_time_at_phase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseOutcome {
+    /// The sun reaches the altitude of the phase at this
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    Time(i64),
+    /// The sun stays above the altitude of the phase all day, e.g. the midnight sun for
+    /// [`SunPhase::Sunset`].
+    AlwaysAbove,
+    /// The sun stays below the altitude of the phase all day, e.g. the polar night for
+    /// [`SunPhase::Sunrise`].
+    AlwaysBelow,
+}
+
+impl PhaseOutcome {
+    /// Returns the time, `None` if the sun doesn't reach the altitude of the phase.
+    #[must_use]
+    pub const fn time(self) -> Option<i64> {
+        match self {
+            Self::Time(time) => Some(time),
+            Self::AlwaysAbove | Self::AlwaysBelow => None,
+        }
+    }
+}
+
+/// Calculates the time for the given [`SunPhase`] like [`time_at_phase`], but tells
+/// whether the sun stays above or below the altitude of the phase all day instead of
+/// returning a meaningless time.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `sun_phase` - [`SunPhase`] to calcuate time for
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`    - Observer height in meters above the horizon
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::{PhaseOutcome, SunPhase};
+///
+/// // midnight sun in Longyearbyen on 2013-06-21
+/// let sunset = sun::try_time_at_phase(1_371_772_800_000, SunPhase::Sunset, 78.2, 15.6, 0.0);
+/// assert_eq!(sunset, PhaseOutcome::AlwaysAbove);
+/// ```
+#[must_use]
+pub fn try_time_at_phase(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> PhaseOutcome {
+    Transit::new(unixtime_in_ms, lon).phase_outcome(sun_phase, lat, height)
+}
+
+/// Intermediate values of the rise and set calculation shared by all phases of a day.
+pub(crate) struct Transit {
+    longitude_rad: f64,
+    julian_cycle: f64,
+    solar_mean_anomaly: f64,
+    ecliptic_longitude: f64,
+    declination: f64,
+    pub(crate) julian_noon: f64,
+}
+
+impl Transit {
+    pub(crate) fn new(unixtime_in_ms: i64, lon: f64) -> Self {
+        Self::of_cycle(Self::cycle(unixtime_in_ms, lon), lon)
+    }
+
+    /// The julian cycle of the transit nearest to `unixtime_in_ms`, which determines the
+    /// transit together with the longitude.
+    pub(crate) fn cycle(unixtime_in_ms: i64, lon: f64) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let days = to_days(unixtime_in_ms as f64);
+        julian_cycle(days, -lon.to_radians())
     }
+
+    pub(crate) fn of_cycle(julian_cycle: f64, lon: f64) -> Self {
+        let longitude_rad = -lon.to_radians();
+        let approx_transit = approx_transit(0.0, longitude_rad, julian_cycle);
+        let solar_mean_anomaly = solar_mean_anomaly(approx_transit);
+        let ecliptic_longitude = ecliptic_longitude(solar_mean_anomaly);
+        Self {
+            longitude_rad,
+            julian_cycle,
+            solar_mean_anomaly,
+            ecliptic_longitude,
+            declination: declination(ecliptic_longitude, 0.0),
+            julian_noon: solar_transit_julian(
+                approx_transit,
+                solar_mean_anomaly,
+                ecliptic_longitude,
+            ),
+        }
+    }
+
+    /// Calculates the julian date of a sun phase, which is `NaN` if the sun
+    /// does not reach the altitude of the phase on that day.
+    pub(crate) fn julian_at_phase(&self, sun_phase: SunPhase, lat: f64, height: f64) -> f64 {
+        let altitude_angle = (sun_phase.angle_deg() + observer_angle(height)).to_radians();
+        let julian_set = sunset_julian(
+            altitude_angle,
+            self.longitude_rad,
+            lat.to_radians(),
+            self.declination,
+            self.julian_cycle,
+            self.solar_mean_anomaly,
+            self.ecliptic_longitude,
+        );
+
+        if sun_phase.is_rise() {
+            self.julian_noon - (julian_set - self.julian_noon)
+        } else {
+            julian_set
+        }
+    }
+
+    /// Calculates the time of a sun phase, or whether the sun stays above or below its
+    /// altitude on that day.
+    pub(crate) fn phase_outcome(&self, sun_phase: SunPhase, lat: f64, height: f64) -> PhaseOutcome {
+        let julian = self.julian_at_phase(sun_phase, lat, height);
+        if !julian.is_nan() {
+            return PhaseOutcome::Time(from_julian(julian));
+        }
+        let altitude_angle = (sun_phase.angle_deg() + observer_angle(height)).to_radians();
+        let highest_altitude = FRAC_PI_2 - (lat.to_radians() - self.declination).abs();
+        if highest_altitude < altitude_angle {
+            PhaseOutcome::AlwaysBelow
+        } else {
+            PhaseOutcome::AlwaysAbove
+        }
+    }
+}
+
+/// Calculates the julian date of a sun phase, which is `NaN` if the sun
+/// does not reach the altitude of the phase on that day.
+fn julian_at_phase(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> f64 {
+    Transit::new(unixtime_in_ms, lon).julian_at_phase(sun_phase, lat, height)
+}
+
+/// Times of several sun phases on one day, see [`times_for_range`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseTimes {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the day in milliseconds,
+    /// as passed to [`time_at_phase`].
+    pub unixtime_in_ms: i64,
+    /// Times of the requested phases in the requested order, `None` if the sun
+    /// does not reach the altitude of the phase on that day.
+    pub times: Vec<Option<i64>>,
+}
+
+/// Calculates the times of several sun phases for consecutive days.
+///
+/// The result for each day and phase equals [`time_at_phase`] at sea level, but the
+/// orbit of the earth is computed only once per day instead of once per phase.
+///
+/// * `start_in_ms` - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the first day in milliseconds.
+/// * `n_days`      - Number of days.
+/// * `phases`      - [`SunPhase`]s to calculate the times for.
+/// * `lat`         - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`         - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::SunPhase;
+///
+/// let year = sun::times_for_range(1_356_998_400_000, 365, &[SunPhase::Sunrise, SunPhase::Sunset], 48.0, 9.0);
+/// assert_eq!(year.len(), 365);
+/// assert_eq!(
+///     year[63].times[0],
+///     Some(sun::time_at_phase(1_362_441_600_000, SunPhase::Sunrise, 48.0, 9.0, 0.0))
+/// );
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn times_for_range(
+    start_in_ms: i64,
+    n_days: u32,
+    phases: &[SunPhase],
+    lat: f64,
+    lon: f64,
+) -> Vec<PhaseTimes> {
+    (0..i64::from(n_days))
+        .map(|day| {
+            let unixtime_in_ms = start_in_ms + day * MILLISECONDS_PER_DAY_INT;
+            let transit = Transit::new(unixtime_in_ms, lon);
+            let times = phases
+                .iter()
+                .map(|&phase| transit.phase_outcome(phase, lat, 0.0).time())
+                .collect();
+            PhaseTimes {
+                unixtime_in_ms,
+                times,
+            }
+        })
+        .collect()
+}
+
+/// Calculates the times of all sun phases of the UTC day of `unixtime_in_ms` at once.
+///
+/// The times are those of [`times::SunTimes::new`] for an observer `height` meters above
+/// the horizon, the julian cycle, the declination and the transit are computed only once
+/// for all phases. Phases the sun doesn't reach on that day are `None`.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`    - Observer height in meters above the horizon
+///
+/// # Examples
+///
+/// ```rust
+/// let times = sun::phases_for_day(1_362_441_600_000, 48.0, 9.0, 0.0);
+/// assert_eq!(times.sunrise, Some(1_362_463_116_241));
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn phases_for_day(unixtime_in_ms: i64, lat: f64, lon: f64, height: f64) -> times::SunTimes {
+    times::SunTimes::at_height(unixtime_in_ms, lat, lon, height)
+}
+
+/// Calculates the time of the solar noon, i.e. the transit of the sun
+/// through the meridian, for a given date and longitude.
+/// The returned time is the [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+///
+/// # Examples
+///
+/// ```rust
+/// let noon = sun::solar_noon(1_362_441_600_000, 9.0);
+/// let rise = sun::time_at_phase(1_362_441_600_000, sun::SunPhase::Sunrise, 48.0, 9.0, 0.0);
+/// let set = sun::time_at_phase(1_362_441_600_000, sun::SunPhase::Sunset, 48.0, 9.0, 0.0);
+/// assert!((noon - (rise + set) / 2).abs() <= 1);
+/// ```
+#[must_use]
+pub fn solar_noon(unixtime_in_ms: i64, lon: f64) -> i64 {
+    from_julian(Transit::new(unixtime_in_ms, lon).julian_noon)
+}
+
+/// Time and sun azimuth of a [`SunPhase`] event.
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseEvent {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the event in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// [azimuth](https://en.wikipedia.org/wiki/Azimuth) of the sun at the event in radians.
+    pub azimuth: f64,
+}
+
+/// Calculates the time for the given [`SunPhase`] like [`time_at_phase`]
+/// together with the azimuth of the sun at that time,
+/// i.e. where on the horizon the sun rises or sets.
+///
+/// Returns `None` if the sun doesn't reach the altitude of the phase on that day, see
+/// [`try_time_at_phase`].
+///
+/// # Examples
+///
+/// ```rust
+/// let event =
+///     sun::event_at_phase(1_362_441_600_000, sun::SunPhase::Sunrise, 48.0, 9.0, 0.0).unwrap();
+/// assert_eq!(event.unixtime_in_ms, 1_362_463_116_241);
+/// // the sun rises slightly south of east in early March
+/// let az = event.azimuth.to_degrees();
+/// assert!(az > 90.0 && az < 100.0);
+/// ```
+#[must_use]
+pub fn event_at_phase(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<PhaseEvent> {
+    let unixtime_in_ms = try_time_at_phase(unixtime_in_ms, sun_phase, lat, lon, height).time()?;
+    let azimuth = pos(unixtime_in_ms, lat, lon).azimuth;
+    Some(PhaseEvent {
+        unixtime_in_ms,
+        azimuth,
+    })
+}
+
+/// Time of a [`SunPhase`] event with its estimated uncertainty, see
+/// [`time_at_phase_with_uncertainty`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UncertainTime {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the event in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Estimated uncertainty of the time in seconds.
+    pub uncertainty_s: f64,
+}
+
+/// Calculates the time for the given [`SunPhase`] like [`time_at_phase`]
+/// together with an estimated uncertainty of that time in seconds.
+///
+/// The uncertainty is derived from the accuracy of the model (and of the atmospheric
+/// refraction for phases close to the horizon) divided by the rate at which the sun's
+/// altitude changes at the event. At high latitudes, where the sun crosses the
+/// horizon at a shallow angle, the uncertainty grows accordingly.
+///
+/// Returns `None` if the sun doesn't reach the altitude of the phase on that day, see
+/// [`try_time_at_phase`], or only touches it without an altitude change to time.
+///
+/// # Examples
+///
+/// ```rust
+/// let sunrise =
+///     sun::time_at_phase_with_uncertainty(1_362_441_600_000, sun::SunPhase::Sunrise, 48.0, 9.0, 0.0)
+///         .unwrap();
+/// assert_eq!(sunrise.unixtime_in_ms, 1_362_463_116_241);
+/// assert!(sunrise.uncertainty_s > 10.0 && sunrise.uncertainty_s < 120.0);
+/// ```
+#[must_use]
+pub fn time_at_phase_with_uncertainty(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Option<UncertainTime> {
+    const HALF_STEP_MS: i64 = 60_000;
+    let time = try_time_at_phase(unixtime_in_ms, sun_phase, lat, lon, height).time()?;
+    let before = pos(time - HALF_STEP_MS, lat, lon).altitude;
+    let after = pos(time + HALF_STEP_MS, lat, lon).altitude;
+    #[allow(clippy::cast_precision_loss)]
+    let rate_deg_per_s = (after - before).to_degrees().abs() / (2 * HALF_STEP_MS / 1_000) as f64;
+    let altitude_error_deg = if sun_phase.angle_deg().abs() < 2.0 {
+        MODEL_ALTITUDE_ERROR_DEG.hypot(REFRACTION_ALTITUDE_ERROR_DEG)
+    } else {
+        MODEL_ALTITUDE_ERROR_DEG
+    };
+    (rate_deg_per_s > 0.0).then(|| UncertainTime {
+        unixtime_in_ms: time,
+        uncertainty_s: altitude_error_deg / rate_deg_per_s,
+    })
 }
 
 /// Sun phases for use with [`time_at_phase`].
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SunPhase {
     Sunrise,
     Sunset,
@@ -244,17 +1297,30 @@ pub enum SunPhase {
     Night,
     GoldenHourEnd,
     GoldenHour,
+    /// End of the morning blue hour, when the sun rises through the given angle in
+    /// degrees, usually [`SunPhase::BLUE_HOUR_DEG`].
+    BlueHourEnd(f64),
+    /// Start of the evening blue hour, when the sun sets through the given angle in
+    /// degrees, usually [`SunPhase::BLUE_HOUR_DEG`].
+    BlueHourStart(f64),
     Custom(f64, bool),
 }
 
 impl SunPhase {
+    /// Altitude in degrees that separates the blue hour from the golden hour.
+    ///
+    /// The sky turns deep blue once the sun is about 4° below the horizon. Conventions
+    /// for the blue hour range from −4° to −8°, so photographers who prefer another one
+    /// pass their angle to [`SunPhase::BlueHourEnd`] and [`SunPhase::BlueHourStart`].
+    pub const BLUE_HOUR_DEG: f64 = -4.0;
+
     /// Create a custom sun phase
     ///
     /// # Arguments
     /// * `angle_deg` - Angle in degrees of the sun above the horizon. Use negative
-    ///                 numbers for angles below the horizon.
+    ///   numbers for angles below the horizon.
     /// * `rise`      - `true` when this sun phase applies to the sun rising, `false`
-    ///                 if it's setting.
+    ///   if it's setting.
     #[must_use]
     pub const fn custom(angle_deg: f64, rise: bool) -> Self {
         SunPhase::Custom(angle_deg, rise)
@@ -268,7 +1334,9 @@ impl SunPhase {
             SunPhase::NauticalDawn | SunPhase::NauticalDusk => -12.0,
             SunPhase::NightEnd | SunPhase::Night => -18.0,
             SunPhase::GoldenHourEnd | SunPhase::GoldenHour => 6.0,
-            SunPhase::Custom(angle, _) => *angle,
+            SunPhase::BlueHourEnd(angle)
+            | SunPhase::BlueHourStart(angle)
+            | SunPhase::Custom(angle, _) => *angle,
         }
     }
 
@@ -279,19 +1347,51 @@ impl SunPhase {
             | SunPhase::Dawn
             | SunPhase::NauticalDawn
             | SunPhase::NightEnd
-            | SunPhase::GoldenHourEnd => true,
+            | SunPhase::GoldenHourEnd
+            | SunPhase::BlueHourEnd(_) => true,
             SunPhase::Sunset
             | SunPhase::SunsetStart
             | SunPhase::Dusk
             | SunPhase::NauticalDusk
             | SunPhase::Night
-            | SunPhase::GoldenHour => false,
+            | SunPhase::GoldenHour
+            | SunPhase::BlueHourStart(_) => false,
             SunPhase::Custom(_, rise) => *rise,
         }
     }
+
+    /// Name of the phase in snake case, e.g. `nautical_dawn`, and `custom` for custom
+    /// phases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// assert_eq!(sun::SunPhase::GoldenHourEnd.name(), "golden_hour_end");
+    /// ```
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            SunPhase::Sunrise => "sunrise",
+            SunPhase::Sunset => "sunset",
+            SunPhase::SunriseEnd => "sunrise_end",
+            SunPhase::SunsetStart => "sunset_start",
+            SunPhase::Dawn => "dawn",
+            SunPhase::Dusk => "dusk",
+            SunPhase::NauticalDawn => "nautical_dawn",
+            SunPhase::NauticalDusk => "nautical_dusk",
+            SunPhase::NightEnd => "night_end",
+            SunPhase::Night => "night",
+            SunPhase::GoldenHourEnd => "golden_hour_end",
+            SunPhase::GoldenHour => "golden_hour",
+            SunPhase::BlueHourEnd(_) => "blue_hour_end",
+            SunPhase::BlueHourStart(_) => "blue_hour_start",
+            SunPhase::Custom(..) => "custom",
+        }
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod tests {
 
     use super::*;
@@ -299,53 +1399,372 @@ mod tests {
     #[test]
     fn test_pos() {
         // 2013-03-05 UTC
-        let date = 1362441600000;
+        let date = 1_362_441_600_000;
         let pos = pos(date, 50.5, 30.5);
-        assert_eq!(0.6412750628729547, pos.azimuth);
-        assert_eq!(-0.7000406838781611, pos.altitude);
+        if cfg!(feature = "fast-math") {
+            assert!((0.641_275_062_872_954_7 - pos.azimuth).abs() < fast_math::SIN_MAX_ERROR);
+            assert!((-0.700_040_683_878_161_1 - pos.altitude).abs() < fast_math::ASIN_MAX_ERROR);
+        } else {
+            assert_eq!(0.641_275_062_872_954_7, pos.azimuth);
+            assert_eq!(-0.700_040_683_878_161_1, pos.altitude);
+        }
     }
 
     #[test]
     fn test_time_at_angle() {
         // 2013-03-05 UTC
-        let date = 1362441600000;
+        let date = 1_362_441_600_000;
 
         assert_eq!(
             time_at_phase(date, SunPhase::Sunrise, 50.5, 30.5, 0.0),
-            1362458096440
+            1_362_458_096_440
         );
         assert_eq!(
             time_at_phase(date, SunPhase::Sunset, 50.5, 30.5, 0.0),
-            1362498417875
+            1_362_498_417_875
         );
 
         // equal to Dusk
         assert_eq!(
             time_at_phase(date, SunPhase::custom(-6.0, false), 50.5, 30.5, 0.0),
-            1362500376781
+            1_362_500_376_781
         );
         // equal to Dawn
         assert_eq!(
             time_at_phase(date, SunPhase::custom(-6.0, true), 50.5, 30.5, 0.0),
-            1362456137534
+            1_362_456_137_534
+        );
+    }
+
+    #[test]
+    fn test_try_time_at_phase() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        assert_eq!(
+            try_time_at_phase(date, SunPhase::Sunrise, 50.5, 30.5, 0.0),
+            PhaseOutcome::Time(time_at_phase(date, SunPhase::Sunrise, 50.5, 30.5, 0.0))
+        );
+        // polar night in Longyearbyen, with nautical twilight at noon
+        let december = 1_387_584_000_000;
+        let polar_night = |phase| try_time_at_phase(december, phase, 78.2, 15.6, 0.0);
+        assert_eq!(polar_night(SunPhase::Sunrise), PhaseOutcome::AlwaysBelow);
+        assert_eq!(polar_night(SunPhase::Sunset), PhaseOutcome::AlwaysBelow);
+        assert!(polar_night(SunPhase::NauticalDawn).time().is_some());
+        // midnight sun
+        let june = 1_371_772_800_000;
+        let midnight_sun = |phase| try_time_at_phase(june, phase, 78.2, 15.6, 0.0);
+        assert_eq!(midnight_sun(SunPhase::Sunset), PhaseOutcome::AlwaysAbove);
+        assert_eq!(midnight_sun(SunPhase::Night), PhaseOutcome::AlwaysAbove);
+        assert_eq!(midnight_sun(SunPhase::Sunrise).time(), None);
+    }
+
+    #[test]
+    fn test_next_and_prev_time_at_phase() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let day = 24 * 60 * 60 * 1_000;
+        let sunrise = time_at_phase(date, SunPhase::Sunrise, 48.0, 9.0, 0.0);
+        let sunset = time_at_phase(date, SunPhase::Sunset, 48.0, 9.0, 0.0);
+        let next = |time, phase| next_time_at_phase(time, phase, 48.0, 9.0, 0.0).unwrap();
+        let prev = |time, phase| prev_time_at_phase(time, phase, 48.0, 9.0, 0.0).unwrap();
+        assert_eq!(next(date, SunPhase::Sunrise), sunrise);
+        assert_eq!(next(sunrise, SunPhase::Sunset), sunset);
+        assert_eq!(prev(sunset, SunPhase::Sunrise), sunrise);
+        assert_eq!(prev(sunset + 1, SunPhase::Sunset), sunset);
+        // the events roll over to the neighbouring days
+        assert!((next(sunrise, SunPhase::Sunrise) - sunrise - day).abs() < 5 * 60 * 1_000);
+        assert!((sunset - prev(sunset, SunPhase::Sunset) - day).abs() < 5 * 60 * 1_000);
+        // the polar night in Longyearbyen ends in February
+        let december = 1_387_584_000_000;
+        let polar_sunrise = next_time_at_phase(december, SunPhase::Sunrise, 78.2, 15.6, 0.0);
+        assert!(polar_sunrise.unwrap() - december > 50 * day);
+        assert!(
+            prev_time_at_phase(december, SunPhase::Sunrise, 78.2, 15.6, 0.0).unwrap()
+                < december - 40 * day
+        );
+    }
+
+    #[test]
+    fn test_blue_hour() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let time = |phase| time_at_phase(date, phase, 50.5, 30.5, 0.0);
+        let end = time(SunPhase::BlueHourEnd(SunPhase::BLUE_HOUR_DEG));
+        let start = time(SunPhase::BlueHourStart(SunPhase::BLUE_HOUR_DEG));
+        assert_eq!(end, time(SunPhase::custom(-4.0, true)));
+        assert_eq!(start, time(SunPhase::custom(-4.0, false)));
+        assert!(time(SunPhase::Dawn) < end && end < time(SunPhase::Sunrise));
+        assert!(time(SunPhase::Sunset) < start && start < time(SunPhase::Dusk));
+        // a deeper convention moves the blue hour towards the night
+        assert!(time(SunPhase::BlueHourStart(-8.0)) > time(SunPhase::Dusk));
+        assert_eq!(SunPhase::BlueHourStart(-8.0).name(), "blue_hour_start");
+    }
+
+    #[test]
+    fn test_event_at_phase() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let rise = event_at_phase(date, SunPhase::Sunrise, 50.5, 30.5, 0.0).unwrap();
+        let set = event_at_phase(date, SunPhase::Sunset, 50.5, 30.5, 0.0).unwrap();
+        assert_eq!(rise.unixtime_in_ms, 1_362_458_096_440);
+        assert_eq!(set.unixtime_in_ms, 1_362_498_417_875);
+        // rise and set are roughly symmetric to the meridian
+        assert!((rise.azimuth + set.azimuth - 2.0 * PI).abs() < 0.01);
+        // polar night in Longyearbyen on 2013-12-21
+        assert!(event_at_phase(1_387_584_000_000, SunPhase::Sunrise, 78.2, 15.6, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_relative_bearing() {
+        let pos = Position {
+            azimuth: 350_f64.to_radians(),
+            altitude: 0.0,
+        };
+        assert!((relative_bearing(pos, 10.0) + 20.0).abs() < 1e-9);
+        assert!((relative_bearing(pos, -190.0) - 180.0).abs() < 1e-9);
+        assert!((relative_bearing(pos, 170.0) - 180.0).abs() < 1e-9);
+        assert!((relative_bearing(pos, 720.0) + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_time_at_phase_with_uncertainty() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let uncertainty = |phase, lat| time_at_phase_with_uncertainty(date, phase, lat, 0.0, 0.0);
+        let equator = uncertainty(SunPhase::Sunrise, 0.0).unwrap();
+        assert_eq!(
+            equator.unixtime_in_ms,
+            time_at_phase(date, SunPhase::Sunrise, 0.0, 0.0, 0.0)
+        );
+        let arctic = uncertainty(SunPhase::Sunrise, 65.0).unwrap();
+        assert!(equator.uncertainty_s < arctic.uncertainty_s);
+        let dusk = uncertainty(SunPhase::Dusk, 0.0).unwrap();
+        assert!(dusk.uncertainty_s < equator.uncertainty_s);
+        // polar night in Longyearbyen on 2013-12-21
+        assert_eq!(
+            time_at_phase_with_uncertainty(1_387_584_000_000, SunPhase::Sunrise, 78.2, 15.6, 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_azimuth_tracker() {
+        // 2013-03-05 UTC, the sun passes north at local midnight
+        let date = 1_362_441_600_000;
+        let mut tracker = AzimuthTracker::default();
+        let mut previous = None;
+        for minute in 0..2 * 24 * 60 {
+            let azimuth = tracker.update(pos(date + minute * 60_000, 50.5, 30.5).azimuth);
+            if let Some(previous) = previous {
+                assert!(azimuth > previous);
+                assert!(azimuth - previous < 0.1);
+            }
+            previous = Some(azimuth);
+        }
+        assert!(tracker.last().unwrap() > 4.0 * PI);
+        tracker.reset();
+        assert!(tracker.last().is_none());
+    }
+
+    #[test]
+    fn test_observed_pos() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let observed = observed_pos(date, 50.5, 30.5);
+        let pos = pos(date, 50.5, 30.5);
+        assert_eq!(observed.altitude(AltitudeKind::True), pos.altitude);
+        assert_eq!(observed.azimuth, pos.azimuth);
+        let refraction = observed.altitude(AltitudeKind::Apparent) - pos.altitude;
+        assert!(refraction > 0.0 && refraction.to_degrees() < 0.7);
+        assert_eq!(
+            observed.position(AltitudeKind::Apparent).altitude,
+            observed.apparent_altitude
+        );
+    }
+
+    #[test]
+    fn test_full_pos() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let full = full_pos(date, 50.5, 30.5);
+        let pos = pos(date, 50.5, 30.5);
+        assert_eq!(full.azimuth, pos.azimuth);
+        assert_eq!(full.altitude, pos.altitude);
+        // early March: the sun is south of the equator and close to 23h RA
+        assert!(full.declination.to_degrees() < -5.0 && full.declination.to_degrees() > -7.0);
+        assert!((full.right_ascension.to_degrees() / 15.0 - 23.0).abs() < 0.2);
+        // local midnight plus 2 hours
+        assert!((full.hour_angle.to_degrees() / 15.0 + 10.0).abs() < 0.3);
+        assert!(full.distance > 147e6 && full.distance < 149e6);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_pos_many() {
+        let date = 1_362_441_600_000;
+        let sites = [(50.5, 30.5), (-33.9, 18.4), (78.2, 15.6), (0.0, -179.9)];
+        let positions = pos_many(date, &sites);
+        for (position, &(lat, lon)) in positions.iter().zip(&sites) {
+            let expected = pos(date, lat, lon);
+            assert_eq!(position.azimuth, expected.azimuth);
+            assert_eq!(position.altitude, expected.altitude);
+        }
+        assert!(pos_many(date, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_equatorial_pos() {
+        let date = 1_362_441_600_000;
+        let equatorial = equatorial_pos(date);
+        let full = full_pos(date, 48.0, 9.0);
+        assert_eq!(equatorial.right_ascension, full.right_ascension);
+        assert_eq!(equatorial.declination, full.declination);
+        // the same for any site
+        let position = coords::equatorial_to_horizontal(equatorial, date, -33.9, 18.4);
+        assert!(position.separation(pos(date, -33.9, 18.4)) < 1e-4);
+    }
+
+    #[test]
+    fn test_declination_at() {
+        let date = 1_362_441_600_000;
+        assert_eq!(declination_at(date), full_pos(date, 48.0, 9.0).declination);
+        // the equinoxes and the December solstice of 2013
+        assert!(declination_at(1_363_781_160_000).to_degrees().abs() < 0.1);
+        assert!(declination_at(1_379_882_640_000).to_degrees().abs() < 0.1);
+        assert!((declination_at(1_387_608_420_000).to_degrees() + 23.44).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_equation_of_time() {
+        // 2013-02-11 and 2013-11-03 UTC, the extremes of the year
+        assert!((equation_of_time(1_360_584_000_000) + 14.2).abs() < 0.2);
+        assert!((equation_of_time(1_383_480_000_000) - 16.4).abs() < 0.2);
+        let date = 1_362_441_600_000;
+        let geometry = solar_geometry(date, 9.0);
+        assert!((equation_of_time(date) - geometry.equation_of_time).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pos_for_times() {
+        let date = 1_362_441_600_000;
+        let times: Vec<i64> = (-24..48)
+            .map(|hour| date + hour * 60 * 60 * 1_000)
+            .collect();
+        let mut positions = vec![
+            Position {
+                azimuth: 0.0,
+                altitude: 0.0
+            };
+            times.len()
+        ];
+        pos_for_times(&times, -33.9, 18.4, &mut positions);
+        for (position, &time) in positions.iter().zip(&times) {
+            let expected = pos(time, -33.9, 18.4);
+            assert_eq!(position.azimuth, expected.azimuth);
+            assert_eq!(position.altitude, expected.altitude);
+        }
+        pos_for_times(&[], 0.0, 0.0, &mut []);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_times_for_range() {
+        let start = 1_362_441_600_000;
+        let phases = [
+            SunPhase::Dawn,
+            SunPhase::Sunrise,
+            SunPhase::Sunset,
+            SunPhase::Night,
+        ];
+        let days = times_for_range(start, 30, &phases, 50.5, 30.5);
+        assert_eq!(days.len(), 30);
+        for (day, times) in (0..).zip(&days) {
+            assert_eq!(times.unixtime_in_ms, start + day * 86_400_000);
+            for (&phase, &time) in phases.iter().zip(&times.times) {
+                assert_eq!(
+                    time,
+                    Some(time_at_phase(times.unixtime_in_ms, phase, 50.5, 30.5, 0.0))
+                );
+            }
+        }
+        // polar night
+        let polar = times_for_range(1_387_584_000_000, 1, &phases, 78.2, 15.6);
+        assert_eq!(polar[0].times[1], None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_phases_for_day() {
+        let date = 1_362_477_600_000;
+        let times = phases_for_day(date, 50.5, 30.5, 100.0);
+        assert_eq!(times.unixtime_in_ms, 1_362_441_600_000);
+        for (&phase, &time) in times::PHASES.iter().zip(&times.phase_times()) {
+            assert_eq!(time, Some(time_at_phase(date, phase, 50.5, 30.5, 100.0)));
+        }
+        assert_eq!(times.solar_noon, solar_noon(date, 30.5));
+        // polar night
+        let polar = phases_for_day(1_387_584_000_000, 78.2, 15.6, 0.0);
+        assert_eq!(polar.sunrise, None);
+        assert!(polar.night_end.is_some());
+        // the events of the UTC day far from the prime meridian, at 00:00 and 23:00 UTC
+        for (time, lat, lon) in [
+            (1_362_441_600_000, 40.7, -74.0),
+            (1_362_524_400_000, -41.3, 170.0),
+        ] {
+            assert_eq!(
+                phases_for_day(time, lat, lon, 0.0),
+                times::SunTimes::new(time, lat, lon)
+            );
+        }
+    }
+
+    #[test]
+    fn test_dut1() {
+        // 2013-03-05 10:00 UTC
+        let date = 1_362_477_600_000;
+        let corrected = full_pos_with_dut1(date, 50.5, 30.5, 0.5);
+        let full = full_pos(date, 50.5, 30.5);
+        // only the rotation of the earth advances, by about 7.5 arc seconds
+        assert_eq!(corrected.declination, full.declination);
+        let rotation = (corrected.hour_angle - full.hour_angle).to_degrees() * 3_600.0;
+        assert!((rotation - 7.52).abs() < 0.01);
+        assert_eq!(pos_with_dut1(date, 50.5, 30.5, 0.0).altitude, full.altitude);
+        assert_eq!(
+            time_at_phase_with_dut1(date, SunPhase::Sunset, 50.5, 30.5, 0.0, -0.25),
+            time_at_phase(date, SunPhase::Sunset, 50.5, 30.5, 0.0) + 250
         );
     }
 
+    #[test]
+    fn test_solar_geometry() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let geometry = solar_geometry(date, 30.5);
+        let full = full_pos(date, 50.5, 30.5);
+        assert_eq!(geometry.julian_day, to_julian(1_362_441_600_000.0));
+        assert!((geometry.declination - full.declination).abs() < 1e-12);
+        assert!((geometry.right_ascension - full.right_ascension).abs() < 1e-12);
+        assert!((geometry.hour_angle - full.hour_angle).abs() < 1e-12);
+        // 2013-11-03 UTC, close to the maximum of the equation of time
+        let geometry = solar_geometry(1_383_480_000_000, 0.0);
+        assert!((geometry.equation_of_time - 16.4).abs() < 0.2);
+    }
+
     #[test]
     fn test_to_julian() {
         // 1. Jan. 2015
-        assert_eq!(2457054.5, to_julian(1422748800000.0));
+        assert_eq!(2_457_054.5, to_julian(1_422_748_800_000.0));
     }
 
     #[test]
     fn test_from_julian() {
         // 1. Jan. 2015
-        assert_eq!(from_julian(2457054.5), 1422748800000);
+        assert_eq!(from_julian(2_457_054.5), 1_422_748_800_000);
     }
 
     #[test]
     fn test_to_days() {
         // 1. Jan. 2015
-        assert_eq!(5509.5, to_days(1422748800000.0));
+        assert_eq!(5509.5, to_days(1_422_748_800_000.0));
     }
 }
@@ -0,0 +1,210 @@
+//! Ratings of the natural light for photography, e.g. to rank the best windows of a day
+//! in a photo-planning app.
+//!
+//! The score combines three factors between 0 and 1:
+//!
+//! * the altitude band, best with the sun a few degrees above the horizon,
+//! * the twilight stage, favoring the golden and the blue hour over daylight,
+//! * the rate of change, favoring a slowly moving sun that gives more time to shoot.
+//!
+//! The ratings assume a clear sky.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::light_quality::best_windows;
+//!
+//! for window in best_windows(1_362_441_600_000, 48.0, 9.0, 0.5) {
+//!     println!(
+//!         "{:?} from {} to {}, score {:.2}",
+//!         window.stage, window.start_in_ms, window.end_in_ms, window.peak_score
+//!     );
+//! }
+//! ```
+
+use crate::{observed_pos, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+const STEP_MS: i64 = 60 * 1_000;
+/// Altitude in degrees with the best light.
+const BEST_ALTITUDE_DEG: f64 = 2.0;
+/// Width of the altitude band in degrees.
+const ALTITUDE_BAND_DEG: f64 = 6.0;
+/// Fastest change of the altitude in degrees per minute, at the equator.
+const MAX_RATE_DEG_PER_MINUTE: f64 = 0.25;
+
+/// Stage of the daylight by the apparent altitude of the sun.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightStage {
+    /// The sun is more than 18° below the horizon.
+    Night,
+    /// Nautical and astronomical twilight, from 18° below the horizon to the blue hour.
+    DeepTwilight,
+    /// The blue hour, see [`SunPhase::BlueHourEnd`].
+    BlueHour,
+    /// The golden hour, see [`SunPhase::GoldenHour`].
+    GoldenHour,
+    /// The sun is more than 6° above the horizon.
+    Day,
+}
+
+impl LightStage {
+    /// Determines the stage for an apparent altitude of the sun in degrees.
+    #[must_use]
+    pub fn from_altitude_deg(altitude_deg: f64) -> Self {
+        if altitude_deg > 6.0 {
+            LightStage::Day
+        } else if altitude_deg > SunPhase::BLUE_HOUR_DEG {
+            LightStage::GoldenHour
+        } else if altitude_deg > -6.0 {
+            LightStage::BlueHour
+        } else if altitude_deg > -18.0 {
+            LightStage::DeepTwilight
+        } else {
+            LightStage::Night
+        }
+    }
+
+    /// Weight of the stage in the score between 0 and 1.
+    #[must_use]
+    pub const fn weight(self) -> f64 {
+        match self {
+            LightStage::Night => 0.0,
+            LightStage::DeepTwilight => 0.3,
+            LightStage::BlueHour => 0.8,
+            LightStage::GoldenHour => 1.0,
+            LightStage::Day => 0.4,
+        }
+    }
+}
+
+/// Rating of the light at a point in time, see [`light_score`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightScore {
+    /// Overall score between 0 and 1.
+    pub score: f64,
+    /// Stage of the daylight.
+    pub stage: LightStage,
+    /// Apparent altitude of the sun in degrees.
+    pub altitude_deg: f64,
+    /// Change of the altitude in degrees per minute, positive while the sun rises.
+    pub rate_deg_per_minute: f64,
+}
+
+/// A period of good light, see [`best_windows`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightWindow {
+    /// Start of the window.
+    pub start_in_ms: i64,
+    /// End of the window.
+    pub end_in_ms: i64,
+    /// Time of the best light within the window.
+    pub peak_in_ms: i64,
+    /// Score at the peak.
+    pub peak_score: f64,
+    /// Stage of the daylight at the peak.
+    pub stage: LightStage,
+}
+
+/// Rates the light at a point in time.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub fn light_score(unixtime_in_ms: i64, lat: f64, lon: f64) -> LightScore {
+    let altitude_deg = |time: i64| observed_pos(time, lat, lon).apparent_altitude.to_degrees();
+    let altitude = altitude_deg(unixtime_in_ms);
+    let rate =
+        (altitude_deg(unixtime_in_ms + STEP_MS) - altitude_deg(unixtime_in_ms - STEP_MS)) / 2.0;
+    let stage = LightStage::from_altitude_deg(altitude);
+    let band = (-((altitude - BEST_ALTITUDE_DEG) / ALTITUDE_BAND_DEG).powi(2)).exp();
+    let slowness = 1.0 - (rate.abs() / MAX_RATE_DEG_PER_MINUTE).min(1.0);
+    LightScore {
+        score: stage.weight() * band * (0.5 + 0.5 * slowness),
+        stage,
+        altitude_deg: altitude,
+        rate_deg_per_minute: rate,
+    }
+}
+
+/// Finds the windows within the UTC day of `unixtime_in_ms` in which the score reaches at
+/// least `min_score`, with a resolution of one minute.
+///
+/// The windows are ranked, i.e. sorted by their peak score with the best first.
+#[must_use]
+pub fn best_windows(unixtime_in_ms: i64, lat: f64, lon: f64, min_score: f64) -> Vec<LightWindow> {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let mut windows: Vec<LightWindow> = Vec::new();
+    let mut open: Option<LightWindow> = None;
+    for step in 0..=MILLISECONDS_PER_DAY_INT / STEP_MS {
+        let time = start + step * STEP_MS;
+        let rating = light_score(time, lat, lon);
+        if rating.score >= min_score {
+            let window = open.get_or_insert(LightWindow {
+                start_in_ms: time,
+                end_in_ms: time,
+                peak_in_ms: time,
+                peak_score: rating.score,
+                stage: rating.stage,
+            });
+            window.end_in_ms = time;
+            if rating.score > window.peak_score {
+                window.peak_in_ms = time;
+                window.peak_score = rating.score;
+                window.stage = rating.stage;
+            }
+        } else if let Some(window) = open.take() {
+            windows.push(window);
+        }
+    }
+    windows.extend(open);
+    windows.sort_by(|a, b| b.peak_score.total_cmp(&a.peak_score));
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_light_score() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let noon = crate::solar_noon(date + MILLISECONDS_PER_DAY_INT / 2, 9.0);
+        let sunset = crate::time_at_phase(date, SunPhase::Sunset, 48.0, 9.0, 0.0);
+        let at_noon = light_score(noon, 48.0, 9.0);
+        assert_eq!(at_noon.stage, LightStage::Day);
+        assert!(at_noon.rate_deg_per_minute.abs() < 0.01);
+        let before_sunset = light_score(sunset - 15 * 60 * 1_000, 48.0, 9.0);
+        assert_eq!(before_sunset.stage, LightStage::GoldenHour);
+        assert!(before_sunset.rate_deg_per_minute < 0.0);
+        assert!(before_sunset.score > 0.5 && before_sunset.score > 10.0 * at_noon.score);
+        let midnight = light_score(noon + MILLISECONDS_PER_DAY_INT / 2, 48.0, 9.0);
+        assert_eq!(midnight.stage, LightStage::Night);
+        assert!(midnight.score.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_best_windows() {
+        let date = 1_362_441_600_000;
+        let windows = best_windows(date, 48.0, 9.0, 0.5);
+        assert_eq!(windows.len(), 2, "{windows:?}");
+        assert!(windows[0].peak_score >= windows[1].peak_score);
+        let sunrise = crate::time_at_phase(date, SunPhase::Sunrise, 48.0, 9.0, 0.0);
+        let sunset = crate::time_at_phase(date, SunPhase::Sunset, 48.0, 9.0, 0.0);
+        for window in &windows {
+            assert_eq!(window.stage, LightStage::GoldenHour);
+            let near = |event: i64| (window.peak_in_ms - event).abs() < 60 * 60 * 1_000;
+            assert!(near(sunrise) || near(sunset));
+            assert!(
+                window.start_in_ms <= window.peak_in_ms && window.peak_in_ms <= window.end_in_ms
+            );
+        }
+        // the sun rises slower in the north, which gives better windows
+        let north = best_windows(date, 65.0, 9.0, 0.5);
+        assert!(north[0].peak_score > windows[0].peak_score);
+    }
+}
@@ -0,0 +1,367 @@
+//! Magnetic azimuths based on the [World Magnetic Model](https://www.ncei.noaa.gov/products/world-magnetic-model).
+//!
+//! The coefficients of the WMM2025, valid from 2025 to 2030, are embedded as
+//! [`MagneticModel::wmm2025`]. NOAA publishes the coefficients as a `WMM.COF` file every
+//! five years, later models can be loaded with [`MagneticModel::from_cof`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::magnetic::MagneticModel;
+//!
+//! let model = MagneticModel::wmm2025();
+//! // 2025-06-01 in Sydney
+//! let pos = sun::magnetic::pos_magnetic(&model, 1_748_736_000_000, -33.87, 151.21, 0.0);
+//! println!("Magnetic azimuth: {}", pos.azimuth.to_degrees());
+//! ```
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::{pos, Position};
+
+/// Geomagnetic reference radius in meters.
+const REFERENCE_RADIUS: f64 = 6_371_200.0;
+/// WGS84 semi-major axis in meters.
+const WGS84_A: f64 = 6_378_137.0;
+/// WGS84 flattening.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const MILLISECONDS_PER_YEAR: f64 = 1_000.0 * 60.0 * 60.0 * 24.0 * 365.242_5;
+/// Coefficients of the WMM2025 in the `WMM.COF` format.
+const WMM2025_COF: &str = include_str!("../data/WMM2025.COF");
+
+/// Gauss coefficients of a spherical harmonic main field model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MagneticModel {
+    /// Epoch of the model as decimal year.
+    pub epoch: f64,
+    /// Maximal degree of the model.
+    pub degree: usize,
+    // indexed by `n * (n + 1) / 2 + m`
+    g: Vec<f64>,
+    h: Vec<f64>,
+    g_dot: Vec<f64>,
+    h_dot: Vec<f64>,
+}
+
+/// Error returned by [`MagneticModel::from_cof`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseModelError {
+    /// The header line with the epoch is missing or invalid.
+    InvalidHeader,
+    /// A coefficient line could not be parsed.
+    InvalidLine(usize),
+}
+
+impl fmt::Display for ParseModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseModelError::InvalidHeader => write!(f, "invalid model header"),
+            ParseModelError::InvalidLine(line) => write!(f, "invalid coefficients in line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseModelError {}
+
+const fn index(n: usize, m: usize) -> usize {
+    n * (n + 1) / 2 + m
+}
+
+impl MagneticModel {
+    /// Creates a model of the given degree with all coefficients set to zero.
+    #[must_use]
+    pub fn new(epoch: f64, degree: usize) -> Self {
+        let len = index(degree, degree) + 1;
+        Self {
+            epoch,
+            degree,
+            g: vec![0.0; len],
+            h: vec![0.0; len],
+            g_dot: vec![0.0; len],
+            h_dot: vec![0.0; len],
+        }
+    }
+
+    /// Sets the coefficients (in nT and nT/year) for degree `n` and order `m`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m > n` or `n` exceeds the degree of the model.
+    pub fn set(&mut self, n: usize, m: usize, g: f64, h: f64, g_dot: f64, h_dot: f64) {
+        assert!(m <= n && n <= self.degree, "invalid degree/order {n}/{m}");
+        let idx = index(n, m);
+        self.g[idx] = g;
+        self.h[idx] = h;
+        self.g_dot[idx] = g_dot;
+        self.h_dot[idx] = h_dot;
+    }
+
+    /// The World Magnetic Model 2025 of NOAA, valid from 2025.0 to 2030.0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the embedded coefficients are malformed, which the tests rule out.
+    #[must_use]
+    pub fn wmm2025() -> Self {
+        Self::from_cof(WMM2025_COF).expect("the embedded WMM2025 coefficients are valid")
+    }
+
+    /// Parses a model in the `WMM.COF` format published by NOAA.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseModelError`] if the input is malformed.
+    pub fn from_cof(input: &str) -> Result<Self, ParseModelError> {
+        let mut lines = input.lines().enumerate();
+        let epoch = lines
+            .next()
+            .and_then(|(_, line)| line.split_whitespace().next())
+            .and_then(|epoch| epoch.parse::<f64>().ok())
+            .ok_or(ParseModelError::InvalidHeader)?;
+        let mut rows = Vec::new();
+        for (number, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if line.trim_start().starts_with("9999") {
+                break;
+            }
+            let values: Vec<&str> = line.split_whitespace().collect();
+            let invalid = || ParseModelError::InvalidLine(number + 1);
+            if values.len() < 6 {
+                return Err(invalid());
+            }
+            let n = values[0].parse::<usize>().map_err(|_| invalid())?;
+            let m = values[1].parse::<usize>().map_err(|_| invalid())?;
+            if m > n {
+                return Err(invalid());
+            }
+            let mut coefficients = [0.0; 4];
+            for (c, v) in coefficients.iter_mut().zip(&values[2..6]) {
+                *c = v.parse().map_err(|_| invalid())?;
+            }
+            rows.push((n, m, coefficients));
+        }
+        let degree = rows.iter().map(|(n, _, _)| *n).max().unwrap_or(0);
+        let mut model = Self::new(epoch, degree);
+        for (n, m, [g, h, g_dot, h_dot]) in rows {
+            model.set(n, m, g, h, g_dot, h_dot);
+        }
+        Ok(model)
+    }
+
+    /// Calculates the [magnetic declination](https://en.wikipedia.org/wiki/Magnetic_declination)
+    /// in radians, positive if magnetic north is east of true north.
+    ///
+    /// * `lat`          - geodetic [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`          - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    /// * `height`       - height above the WGS84 ellipsoid in meters.
+    /// * `decimal_year` - date as decimal year, e.g. `2025.5`.
+    #[must_use]
+    pub fn declination(&self, lat: f64, lon: f64, height: f64, decimal_year: f64) -> f64 {
+        let (north, east, _) = self.field(lat, lon, height, decimal_year);
+        east.atan2(north)
+    }
+
+    /// Calculates the north, east and down components of the magnetic field in nT.
+    #[must_use]
+    #[allow(clippy::many_single_char_names)]
+    pub fn field(&self, lat: f64, lon: f64, height: f64, decimal_year: f64) -> (f64, f64, f64) {
+        // geodetic to geocentric spherical coordinates
+        let lat_rad = lat.to_radians();
+        let lon_rad = lon.to_radians();
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let rc = WGS84_A / (1.0 - e2 * lat_rad.sin().powi(2)).sqrt();
+        let p = (rc + height) * lat_rad.cos();
+        let z = (rc * (1.0 - e2) + height) * lat_rad.sin();
+        let r = p.hypot(z);
+        let lat_geocentric = (z / r).asin();
+        let colatitude = PI / 2.0 - lat_geocentric;
+
+        let (legendre, legendre_derivative) = schmidt_legendre(self.degree, colatitude);
+        let dt = decimal_year - self.epoch;
+        let sin_theta = colatitude.sin().max(1e-12);
+
+        let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+        let mut ratio = (REFERENCE_RADIUS / r).powi(2);
+        for n in 1..=self.degree {
+            ratio *= REFERENCE_RADIUS / r;
+            #[allow(clippy::cast_precision_loss)]
+            let n_f = n as f64;
+            for m in 0..=n {
+                let i = index(n, m);
+                let g = self.g[i] + dt * self.g_dot[i];
+                let h = self.h[i] + dt * self.h_dot[i];
+                #[allow(clippy::cast_precision_loss)]
+                let m_lon = m as f64 * lon_rad;
+                let (sin_m, cos_m) = m_lon.sin_cos();
+                let gh = g * cos_m + h * sin_m;
+                x += ratio * gh * legendre_derivative[i];
+                #[allow(clippy::cast_precision_loss)]
+                let m_f = m as f64;
+                y += ratio * m_f * (g * sin_m - h * cos_m) * legendre[i] / sin_theta;
+                z -= ratio * (n_f + 1.0) * gh * legendre[i];
+            }
+        }
+
+        // rotate back into the ellipsoidal frame
+        let psi = lat_geocentric - lat_rad;
+        let north = x * psi.cos() - z * psi.sin();
+        let down = x * psi.sin() + z * psi.cos();
+        (north, y, down)
+    }
+}
+
+/// Schmidt semi-normalized associated Legendre functions of `cos(theta)`
+/// and their derivatives with respect to `theta`.
+#[allow(clippy::many_single_char_names)]
+fn schmidt_legendre(degree: usize, theta: f64) -> (Vec<f64>, Vec<f64>) {
+    let len = index(degree, degree) + 1;
+    let mut p = vec![0.0; len];
+    let mut dp = vec![0.0; len];
+    let (sin_t, cos_t) = theta.sin_cos();
+    p[0] = 1.0;
+    for n in 1..=degree {
+        #[allow(clippy::cast_precision_loss)]
+        let n_f = n as f64;
+        for m in 0..=n {
+            #[allow(clippy::cast_precision_loss)]
+            let m_f = m as f64;
+            let i = index(n, m);
+            if n == m {
+                let j = index(n - 1, n - 1);
+                let k = if n == 1 {
+                    1.0
+                } else {
+                    ((2.0 * n_f - 1.0) / (2.0 * n_f)).sqrt()
+                };
+                p[i] = k * sin_t * p[j];
+                dp[i] = k * (cos_t * p[j] + sin_t * dp[j]);
+            } else {
+                let j = index(n - 1, m);
+                let (p2, dp2) = if n >= m + 2 {
+                    let k = index(n - 2, m);
+                    (p[k], dp[k])
+                } else {
+                    (0.0, 0.0)
+                };
+                let a = ((n_f - 1.0).powi(2) - m_f * m_f).max(0.0).sqrt();
+                let b = (n_f * n_f - m_f * m_f).sqrt();
+                p[i] = ((2.0 * n_f - 1.0) * cos_t * p[j] - a * p2) / b;
+                dp[i] = ((2.0 * n_f - 1.0) * (cos_t * dp[j] - sin_t * p[j]) - a * dp2) / b;
+            }
+        }
+    }
+    (p, dp)
+}
+
+/// Converts a true azimuth in radians into a magnetic azimuth
+/// using the given magnetic declination in radians.
+#[must_use]
+pub fn to_magnetic_azimuth(azimuth: f64, declination: f64) -> f64 {
+    (azimuth - declination).rem_euclid(2.0 * PI)
+}
+
+/// Calculates the sun position like [`pos`](crate::pos) but with the azimuth
+/// relative to magnetic north.
+///
+/// * `height` - height above the WGS84 ellipsoid in meters.
+#[must_use]
+pub fn pos_magnetic(
+    model: &MagneticModel,
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Position {
+    let position = pos(unixtime_in_ms, lat, lon);
+    #[allow(clippy::cast_precision_loss)]
+    let decimal_year = 1970.0 + unixtime_in_ms as f64 / MILLISECONDS_PER_YEAR;
+    let declination = model.declination(lat, lon, height, decimal_year);
+    Position {
+        azimuth: to_magnetic_azimuth(position.azimuth, declination),
+        altitude: position.altitude,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const DIPOLE: &str = "    2025.0            TEST-DIPOLE     01/01/2025
+  1  0  -30000.0       0.0       10.0        0.0
+  1  1   -1500.0    5000.0        0.0        0.0
+999999999999999999999999999999999999999999999999
+";
+
+    #[test]
+    fn test_from_cof() {
+        let model = MagneticModel::from_cof(DIPOLE).unwrap();
+        assert_eq!(model.degree, 1);
+        assert!((model.epoch - 2025.0).abs() < 1e-12);
+        assert_eq!(
+            MagneticModel::from_cof(""),
+            Err(ParseModelError::InvalidHeader)
+        );
+        assert_eq!(
+            MagneticModel::from_cof("2025.0\n 1 x 1 2 3 4"),
+            Err(ParseModelError::InvalidLine(2))
+        );
+    }
+
+    #[test]
+    fn test_dipole_declination() {
+        let model = MagneticModel::from_cof(DIPOLE).unwrap();
+        // at the equator on the prime meridian the declination of a
+        // tilted dipole is atan2(-h11, -g10)
+        let declination = model.declination(0.0, 0.0, 0.0, 2025.0);
+        let expected = (-5000.0_f64).atan2(30_000.0);
+        assert!((declination - expected).abs() < 1e-9);
+        let (north, _, down) = model.field(90.0, 0.0, 0.0, 2025.0);
+        assert!(down > 0.0);
+        assert!(north.abs() < down.abs());
+    }
+
+    #[test]
+    fn test_legendre() {
+        let theta = 0.7_f64;
+        let (p, dp) = schmidt_legendre(2, theta);
+        let (s, c) = theta.sin_cos();
+        assert!((p[index(2, 0)] - (3.0 * c * c - 1.0) / 2.0).abs() < 1e-12);
+        assert!((p[index(2, 1)] - 3f64.sqrt() * s * c).abs() < 1e-12);
+        assert!((p[index(2, 2)] - 3f64.sqrt() / 2.0 * s * s).abs() < 1e-12);
+        assert!((dp[index(2, 0)] + 3.0 * c * s).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_wmm2025() {
+        let model = MagneticModel::wmm2025();
+        assert_eq!(model.degree, 12);
+        assert!((model.epoch - 2025.0).abs() < 1e-12);
+        // approximate declinations in 2025, east positive
+        for (lat, lon, declination) in [
+            (40.0, -105.3, 7.8),
+            (51.5, -0.1, 0.9),
+            (-33.9, 151.2, 12.8),
+            (35.7, 139.7, -7.9),
+            (40.7, -74.0, -12.5),
+        ] {
+            let computed = model.declination(lat, lon, 0.0, 2025.5).to_degrees();
+            assert!(
+                (computed - declination).abs() < 0.5,
+                "{lat} {lon} {computed}"
+            );
+        }
+        // the field points down in the north and up in the south
+        assert!(model.field(80.0, 0.0, 0.0, 2025.0).2 > 50_000.0);
+        assert!(model.field(-80.0, 0.0, 0.0, 2025.0).2 < -40_000.0);
+    }
+
+    #[test]
+    fn test_magnetic_azimuth() {
+        let azimuth = to_magnetic_azimuth(0.1, 0.2);
+        assert!((azimuth - (2.0 * PI - 0.1)).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,143 @@
+//! World maps shaded by day, twilight and night in the
+//! [equirectangular projection](https://en.wikipedia.org/wiki/Equirectangular_projection),
+//! e.g. for world clocks.
+//!
+//! The map is darkened in steps at the terminator and at the ends of civil, nautical and
+//! astronomical twilight. A base map in the same projection can be shaded instead of a
+//! plain background.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::map::{render, render_png};
+//!
+//! let now = 1_362_484_800_000;
+//! let map = render(now, 720, 360, None);
+//! assert_eq!(map.dimensions(), (720, 360));
+//! let png = render_png(now, 360, 180, Some(&map)).unwrap();
+//! std::fs::write(std::env::temp_dir().join("day-night.png"), png).unwrap();
+//! ```
+
+use std::io::Cursor;
+
+use image::imageops::{resize, FilterType};
+use image::{ImageError, ImageFormat, Rgba, RgbaImage};
+
+use crate::terminator::subsolar;
+
+/// Color of the map without base map.
+const BACKGROUND: Rgba<u8> = Rgba([70, 130, 180, 255]);
+/// Opacities of the night shading beyond the terminator and beyond the end of civil,
+/// nautical and astronomical twilight.
+const BANDS: [(f64, u8); 4] = [(0.0, 64), (-6.0, 112), (-12.0, 160), (-18.0, 200)];
+
+/// Opacity of the night shading at the altitude of the sun in degrees.
+fn opacity(altitude_deg: f64) -> u8 {
+    BANDS
+        .iter()
+        .rev()
+        .find(|(limit, _)| altitude_deg < *limit)
+        .map_or(0, |&(_, opacity)| opacity)
+}
+
+/// Returns the night shading as a black image with the opacity increasing towards the
+/// night, to be drawn over a map of the whole earth.
+///
+/// Refraction and the size of the sun are ignored.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `width`     - width of the image in pixels, spanning 360° of longitude.
+/// * `height`    - height of the image in pixels, spanning 180° of latitude.
+#[must_use]
+pub fn shading(unixtime_in_ms: i64, width: u32, height: u32) -> RgbaImage {
+    let (declination, subsolar_lon) = subsolar(unixtime_in_ms);
+    let (sin_dec, cos_dec) = declination.sin_cos();
+    RgbaImage::from_fn(width, height, |x, y| {
+        // the centers of the pixels
+        let lon = (-180.0 + (f64::from(x) + 0.5) * 360.0 / f64::from(width)).to_radians();
+        let lat = (90.0 - (f64::from(y) + 0.5) * 180.0 / f64::from(height)).to_radians();
+        let altitude = (lat.sin() * sin_dec + lat.cos() * cos_dec * (lon - subsolar_lon).cos())
+            .clamp(-1.0, 1.0)
+            .asin();
+        Rgba([0, 0, 0, opacity(altitude.to_degrees())])
+    })
+}
+
+/// Renders the day and night map, shading the base map scaled to the size of the map or
+/// a plain background.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `width`     - width of the map in pixels, spanning 360° of longitude.
+/// * `height`    - height of the map in pixels, spanning 180° of latitude.
+/// * `base`      - map of the whole earth in the equirectangular projection.
+#[must_use]
+pub fn render(unixtime_in_ms: i64, width: u32, height: u32, base: Option<&RgbaImage>) -> RgbaImage {
+    let mut map = match base {
+        Some(base) if base.dimensions() == (width, height) => base.clone(),
+        Some(base) => resize(base, width, height, FilterType::Triangle),
+        None => RgbaImage::from_pixel(width, height, BACKGROUND),
+    };
+    for (pixel, shade) in map
+        .pixels_mut()
+        .zip(shading(unixtime_in_ms, width, height).pixels())
+    {
+        let alpha = u16::from(shade[3]);
+        for channel in &mut pixel.0[..3] {
+            // the shading is black, so blending only darkens the channel
+            let value = u16::from(*channel) * (255 - alpha) / 255;
+            *channel = u8::try_from(value).unwrap_or(u8::MAX);
+        }
+    }
+    map
+}
+
+/// Renders the map like [`render`] and encodes it as PNG.
+///
+/// # Errors
+///
+/// Returns the error of the encoder, which doesn't happen for maps that fit into memory.
+pub fn render_png(
+    unixtime_in_ms: i64,
+    width: u32,
+    height: u32,
+    base: Option<&RgbaImage>,
+) -> Result<Vec<u8>, ImageError> {
+    let mut png = Cursor::new(Vec::new());
+    render(unixtime_in_ms, width, height, base).write_to(&mut png, ImageFormat::Png)?;
+    Ok(png.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_shading() {
+        // 2013-03-05 12:00 UTC, the sun stands in the zenith close to 0°, 6°S
+        let shade = shading(1_362_484_800_000, 360, 180);
+        assert_eq!(shade.get_pixel(180, 96)[3], 0);
+        // midnight at the antimeridian
+        assert_eq!(shade.get_pixel(0, 84)[3], 200);
+        assert_eq!(shade.get_pixel(359, 84)[3], 200);
+        // all bands appear along the equator
+        let mut opacities: Vec<u8> = (0..360).map(|x| shade.get_pixel(x, 90)[3]).collect();
+        opacities.sort_unstable();
+        opacities.dedup();
+        assert_eq!(opacities, [0, 64, 112, 160, 200]);
+        assert_eq!(opacity(-0.1), 64);
+        assert_eq!(opacity(-18.1), 200);
+    }
+
+    #[test]
+    fn test_render() {
+        let base = RgbaImage::from_pixel(36, 18, Rgba([255, 255, 255, 255]));
+        let map = render(1_362_484_800_000, 72, 36, Some(&base));
+        assert_eq!(map.dimensions(), (72, 36));
+        assert_eq!(*map.get_pixel(36, 19), Rgba([255, 255, 255, 255]));
+        assert_eq!(*map.get_pixel(0, 17), Rgba([55, 55, 55, 255]));
+        let png = render_png(1_362_484_800_000, 72, 36, None).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(decoded, render(1_362_484_800_000, 72, 36, None));
+    }
+}
@@ -0,0 +1,679 @@
+//! Calculations for the moon, ported from
+//! [suncalc](https://github.com/mourner/suncalc) as well.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::search::{crossings_within_day, Crossings};
+use crate::{
+    altitude, azimuth, declination, ecliptic_longitude, refraction, right_ascension, sidereal_time,
+    solar_mean_anomaly, to_days, MILLISECONDS_PER_DAY_INT,
+};
+
+#[allow(clippy::cast_precision_loss)]
+const MILLISECONDS_PER_DAY: f64 = MILLISECONDS_PER_DAY_INT as f64;
+/// Mean length of a lunation in days.
+const SYNODIC_MONTH: f64 = 29.530_589;
+/// Daily motion of the moon's mean anomaly in degrees.
+const MEAN_ANOMALY_RATE: f64 = 13.064_993;
+const MEAN_ANOMALY_AT_J2000: f64 = 134.963;
+const SUN_DISTANCE_IN_KM: f64 = 149_598_000.0;
+/// Altitude of the moon's center at rise and set (parallax minus semi-diameter and refraction).
+const MOON_RISE_ALTITUDE: f64 = 0.133 * PI / 180.0;
+
+/// Geocentric equatorial coordinates of the moon.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MoonCoords {
+    pub(crate) right_ascension: f64,
+    pub(crate) declination: f64,
+    /// distance in kilometers
+    pub(crate) distance: f64,
+}
+
+fn moon_longitude(days: f64) -> f64 {
+    let ecliptic_longitude = (218.316 + 13.176_396 * days).to_radians();
+    let mean_anomaly = (MEAN_ANOMALY_AT_J2000 + MEAN_ANOMALY_RATE * days).to_radians();
+    ecliptic_longitude + 6.289_f64.to_radians() * mean_anomaly.sin()
+}
+
+/// Elongation of the moon from the sun in ecliptic longitude as fraction of a full turn,
+/// `0.0` at new moon and `0.5` at full moon.
+pub(crate) fn lunation_fraction(unixtime_in_ms: f64) -> f64 {
+    let days = to_days(unixtime_in_ms);
+    let sun = ecliptic_longitude(solar_mean_anomaly(days));
+    ((moon_longitude(days) - sun) / (2.0 * PI)).rem_euclid(1.0)
+}
+
+pub(crate) fn moon_coords(days: f64) -> MoonCoords {
+    let mean_anomaly = (MEAN_ANOMALY_AT_J2000 + MEAN_ANOMALY_RATE * days).to_radians();
+    let mean_distance = (93.272 + 13.229_350 * days).to_radians();
+
+    let longitude = moon_longitude(days);
+    let latitude = 5.128_f64.to_radians() * mean_distance.sin();
+    let distance = 385_001.0 - 20_905.0 * mean_anomaly.cos();
+
+    MoonCoords {
+        right_ascension: right_ascension(longitude, latitude),
+        declination: declination(longitude, latitude),
+        distance,
+    }
+}
+
+/// Geocentric azimuth and altitude of the moon in radians without refraction.
+pub(crate) fn moon_horizontal(unixtime_in_ms: i64, lat: f64, lon: f64) -> (f64, f64, MoonCoords) {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let coords = moon_coords(days);
+    let hour_angle = sidereal_time(days, -lon.to_radians()) - coords.right_ascension;
+    let latitude_rad = lat.to_radians();
+    (
+        azimuth(hour_angle, latitude_rad, coords.declination),
+        altitude(hour_angle, latitude_rad, coords.declination),
+        coords,
+    )
+}
+
+/// Apparent altitude of the moon in radians.
+pub(crate) fn moon_altitude(unixtime_in_ms: i64, lat: f64, lon: f64) -> f64 {
+    let (_, altitude, _) = moon_horizontal(unixtime_in_ms, lat, lon);
+    altitude
+        + refraction::refraction_from_true(
+            altitude.max(0.0),
+            refraction::STANDARD_TEMPERATURE,
+            refraction::STANDARD_PRESSURE,
+        )
+}
+
+/// Position of the moon as seen by an observer, see [`moon_pos`].
+#[derive(Debug, Clone, Copy)]
+pub struct MoonPosition {
+    /// [Azimuth](https://en.wikipedia.org/wiki/Azimuth) in radians, clockwise from north
+    /// like the azimuth of the sun.
+    pub azimuth: f64,
+    /// Altitude above the horizon in radians, including the refraction.
+    pub altitude: f64,
+    /// Distance between the centers of the earth and the moon in kilometers.
+    pub distance: f64,
+    /// [Parallactic angle](https://en.wikipedia.org/wiki/Parallactic_angle) in radians.
+    pub parallactic_angle: f64,
+}
+
+/// Calculates the position of the moon like `getMoonPosition` of suncalc.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Examples
+///
+/// ```rust
+/// let moon = sun::moon::moon_pos(1_362_441_600_000, 50.5, 30.5);
+/// println!("the moon is {:.0} km away", moon.distance);
+/// ```
+#[must_use]
+pub fn moon_pos(unixtime_in_ms: i64, lat: f64, lon: f64) -> MoonPosition {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let coords = moon_coords(days);
+    let hour_angle = sidereal_time(days, -lon.to_radians()) - coords.right_ascension;
+    let latitude_rad = lat.to_radians();
+    MoonPosition {
+        azimuth: azimuth(hour_angle, latitude_rad, coords.declination),
+        altitude: moon_altitude(unixtime_in_ms, lat, lon),
+        distance: coords.distance,
+        parallactic_angle: parallactic_angle(hour_angle, latitude_rad, coords.declination),
+    }
+}
+
+/// Illuminated fraction and phase of the moon.
+#[derive(Debug, Clone, Copy)]
+pub struct MoonIllumination {
+    /// Illuminated fraction of the moon, from `0.0` (new moon) to `1.0` (full moon).
+    pub fraction: f64,
+    /// Phase of the moon from `0.0` to `1.0`: `0.0` new moon, `0.25` first quarter,
+    /// `0.5` full moon, `0.75` last quarter.
+    pub phase: f64,
+    /// Midpoint angle in radians of the illuminated limb of the moon reckoned
+    /// eastward from the north point of the disk.
+    pub angle: f64,
+}
+
+/// Calculates the illumination of the moon at a given time.
+///
+/// # Examples
+///
+/// ```rust
+/// // 2013-03-27, full moon
+/// let illumination = sun::moon::moon_illumination(1_364_378_400_000);
+/// assert!(illumination.fraction > 0.99);
+/// ```
+#[must_use]
+pub fn moon_illumination(unixtime_in_ms: i64) -> MoonIllumination {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let sun_longitude = ecliptic_longitude(solar_mean_anomaly(days));
+    let sun_declination = declination(sun_longitude, 0.0);
+    let sun_right_ascension = right_ascension(sun_longitude, 0.0);
+    let moon = moon_coords(days);
+
+    let delta_ra = sun_right_ascension - moon.right_ascension;
+    let elongation = (sun_declination.sin() * moon.declination.sin()
+        + sun_declination.cos() * moon.declination.cos() * delta_ra.cos())
+    .acos();
+    let inclination = (SUN_DISTANCE_IN_KM * elongation.sin())
+        .atan2(moon.distance - SUN_DISTANCE_IN_KM * elongation.cos());
+    let angle = (sun_declination.cos() * delta_ra.sin()).atan2(
+        sun_declination.sin() * moon.declination.cos()
+            - sun_declination.cos() * moon.declination.sin() * delta_ra.cos(),
+    );
+    let sign = if angle < 0.0 { -1.0 } else { 1.0 };
+    MoonIllumination {
+        fraction: (1.0 + inclination.cos()) / 2.0,
+        phase: 0.5 + 0.5 * inclination * sign / PI,
+        angle,
+    }
+}
+
+/// Orientation of the moon's disk as seen by an observer.
+#[derive(Debug, Clone, Copy)]
+pub struct MoonOrientation {
+    /// [Parallactic angle](https://en.wikipedia.org/wiki/Parallactic_angle) in radians,
+    /// the angle between the direction to the north celestial pole and the zenith.
+    pub parallactic_angle: f64,
+    /// Position angle of the midpoint of the bright limb in radians,
+    /// reckoned eastward from the north point of the disk.
+    pub bright_limb_angle: f64,
+    /// Angle of the bright limb in radians relative to the zenith,
+    /// i.e. how the crescent appears tilted in the sky.
+    pub zenith_angle: f64,
+}
+
+fn parallactic_angle(hour_angle: f64, latitude_rad: f64, declination: f64) -> f64 {
+    // formula 14.1 of "Astronomical Algorithms" 2nd edition by Jean Meeus
+    hour_angle
+        .sin()
+        .atan2(latitude_rad.tan() * declination.cos() - declination.sin() * hour_angle.cos())
+}
+
+/// Calculates the parallactic angle of the moon and the orientation of its bright limb.
+///
+/// # Examples
+///
+/// ```rust
+/// let orientation = sun::moon::moon_orientation(1_362_441_600_000, 50.5, 30.5);
+/// let zenith = orientation.bright_limb_angle - orientation.parallactic_angle;
+/// assert!((orientation.zenith_angle - zenith).abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn moon_orientation(unixtime_in_ms: i64, lat: f64, lon: f64) -> MoonOrientation {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let coords = moon_coords(days);
+    let hour_angle = sidereal_time(days, -lon.to_radians()) - coords.right_ascension;
+    let parallactic_angle = parallactic_angle(hour_angle, lat.to_radians(), coords.declination);
+    let bright_limb_angle = moon_illumination(unixtime_in_ms).angle;
+    MoonOrientation {
+        parallactic_angle,
+        bright_limb_angle,
+        zenith_angle: bright_limb_angle - parallactic_angle,
+    }
+}
+
+/// Finds the first time after `unixtime_in_ms` at which the moon has the given
+/// elongation from the sun in ecliptic longitude (`0.0` new moon, `0.5` full moon).
+pub(crate) fn next_phase_time(unixtime_in_ms: i64, target: f64) -> i64 {
+    let month = SYNODIC_MONTH * MILLISECONDS_PER_DAY;
+    let phase_at = lunation_fraction;
+    let refine = |mut time: f64| {
+        for _ in 0..5 {
+            time += ((target - phase_at(time) + 0.5).rem_euclid(1.0) - 0.5) * month;
+        }
+        time
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let start = unixtime_in_ms as f64;
+    let mut time = refine(start + (target - phase_at(start)).rem_euclid(1.0) * month);
+    if time <= start {
+        time = refine(time + month);
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let result = time.round() as i64;
+    result
+}
+
+/// Closest or farthest point of the moon's orbit.
+#[derive(Debug, Clone, Copy)]
+pub struct Apsis {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// distance between the centers of earth and moon in kilometers.
+    pub distance: f64,
+}
+
+/// Main periodic terms of the distance of the moon, table 47.A of "Astronomical Algorithms"
+/// by Jean Meeus: multiples of the mean elongation of the moon, the mean anomaly of the sun,
+/// the mean anomaly of the moon and its argument of latitude, and the amplitude in meters.
+const DISTANCE_TERMS: [(f64, f64, f64, f64, f64); 16] = [
+    (0.0, 0.0, 1.0, 0.0, -20_905_355.0),
+    (2.0, 0.0, -1.0, 0.0, -3_699_111.0),
+    (2.0, 0.0, 0.0, 0.0, -2_955_968.0),
+    (0.0, 0.0, 2.0, 0.0, -569_925.0),
+    (0.0, 1.0, 0.0, 0.0, 48_888.0),
+    (0.0, 0.0, 0.0, 2.0, -3_149.0),
+    (2.0, 0.0, -2.0, 0.0, 246_158.0),
+    (2.0, -1.0, -1.0, 0.0, -152_138.0),
+    (2.0, 0.0, 1.0, 0.0, -170_733.0),
+    (2.0, -1.0, 0.0, 0.0, -204_586.0),
+    (0.0, 1.0, -1.0, 0.0, -129_620.0),
+    (1.0, 0.0, 0.0, 0.0, 108_743.0),
+    (0.0, 1.0, 1.0, 0.0, 104_755.0),
+    (2.0, 0.0, 0.0, -2.0, 10_321.0),
+    (0.0, 0.0, 1.0, -2.0, 79_661.0),
+    (4.0, 0.0, -1.0, 0.0, -34_782.0),
+];
+
+/// Distance between the centers of earth and moon in kilometers including the main
+/// perturbations by the sun, which the distance of [`moon_coords`] ignores.
+fn moon_distance(days: f64) -> f64 {
+    let centuries = days / 36_525.0;
+    let elongation = (297.850_192 + 445_267.111_403 * centuries).to_radians();
+    let sun_anomaly = (357.529_109 + 35_999.050_291 * centuries).to_radians();
+    let moon_anomaly = (134.963_396 + 477_198.867_506 * centuries).to_radians();
+    let latitude_argument = (93.272_095 + 483_202.017_523 * centuries).to_radians();
+    let periodic: f64 = DISTANCE_TERMS
+        .iter()
+        .map(|&(d, m, m_moon, f, meters)| {
+            let argument =
+                d * elongation + m * sun_anomaly + m_moon * moon_anomaly + f * latitude_argument;
+            meters * argument.cos()
+        })
+        .sum();
+    385_000.56 + periodic / 1_000.0
+}
+
+/// Finds the extremum of [`moon_distance`] within five days of the mean apsis at `mean_time`,
+/// the minimum for `sign` 1 and the maximum for -1, as the perturbations by the sun shift
+/// the apsides by up to about two days.
+fn refine_apsis(mean_time: f64, sign: f64) -> f64 {
+    let key = |time: f64| sign * moon_distance(to_days(time));
+    let hour = MILLISECONDS_PER_DAY / 24.0;
+    let mut time = (-120..=120)
+        .map(|hours| mean_time + f64::from(hours) * hour)
+        .min_by(|&a, &b| key(a).total_cmp(&key(b)))
+        .unwrap_or(mean_time);
+    let (mut low, mut high) = (time - hour, time + hour);
+    // ternary search to a second
+    while high - low > 1_000.0 {
+        let third = (high - low) / 3.0;
+        if key(low + third) < key(high - third) {
+            high -= third;
+        } else {
+            low += third;
+        }
+        time = (low + high) / 2.0;
+    }
+    time
+}
+
+fn next_apsis(unixtime_in_ms: i64, target_anomaly_deg: f64, sign: f64) -> Apsis {
+    let period = 360.0 / MEAN_ANOMALY_RATE * MILLISECONDS_PER_DAY;
+    #[allow(clippy::cast_precision_loss)]
+    let start = unixtime_in_ms as f64;
+    let anomaly = MEAN_ANOMALY_AT_J2000 + MEAN_ANOMALY_RATE * to_days(start);
+    // begin a period early, as an apsis may come days after its mean time
+    let mut mean_time =
+        start + (target_anomaly_deg - anomaly).rem_euclid(360.0) / 360.0 * period - period;
+    loop {
+        #[allow(clippy::cast_possible_truncation)]
+        let time = refine_apsis(mean_time, sign).round() as i64;
+        if time > unixtime_in_ms {
+            #[allow(clippy::cast_precision_loss)]
+            let distance = moon_distance(to_days(time as f64));
+            return Apsis {
+                unixtime_in_ms: time,
+                distance,
+            };
+        }
+        mean_time += period;
+    }
+}
+
+/// Finds the next [perigee](https://en.wikipedia.org/wiki/Apsis) of the moon after `unixtime_in_ms`,
+/// accurate to about half an hour and a few hundred kilometers.
+#[must_use]
+pub fn next_perigee(unixtime_in_ms: i64) -> Apsis {
+    next_apsis(unixtime_in_ms, 0.0, 1.0)
+}
+
+/// Finds the next [apogee](https://en.wikipedia.org/wiki/Apsis) of the moon after `unixtime_in_ms`,
+/// accurate to about half an hour and a few hundred kilometers.
+#[must_use]
+pub fn next_apogee(unixtime_in_ms: i64) -> Apsis {
+    next_apsis(unixtime_in_ms, 180.0, -1.0)
+}
+
+/// Named phase of the moon, see [`moon_phase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoonPhase {
+    NewMoon,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    FullMoon,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl MoonPhase {
+    const ALL: [MoonPhase; 8] = [
+        MoonPhase::NewMoon,
+        MoonPhase::WaxingCrescent,
+        MoonPhase::FirstQuarter,
+        MoonPhase::WaxingGibbous,
+        MoonPhase::FullMoon,
+        MoonPhase::WaningGibbous,
+        MoonPhase::LastQuarter,
+        MoonPhase::WaningCrescent,
+    ];
+
+    /// Names a [`MoonIllumination::phase`] from `0.0` to `1.0`.
+    ///
+    /// Every name covers an eighth of the lunation centered on its phase, e.g. the full
+    /// moon the phases from `0.4375` to `0.5625`, i.e. about 1.8 days before and after
+    /// the exact full moon.
+    #[must_use]
+    pub fn from_phase(phase: f64) -> Self {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let index = (phase * 8.0).round().rem_euclid(8.0) as usize;
+        Self::ALL[index]
+    }
+
+    /// Returns the English name, e.g. "waxing crescent".
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            MoonPhase::NewMoon => "new moon",
+            MoonPhase::WaxingCrescent => "waxing crescent",
+            MoonPhase::FirstQuarter => "first quarter",
+            MoonPhase::WaxingGibbous => "waxing gibbous",
+            MoonPhase::FullMoon => "full moon",
+            MoonPhase::WaningGibbous => "waning gibbous",
+            MoonPhase::LastQuarter => "last quarter",
+            MoonPhase::WaningCrescent => "waning crescent",
+        }
+    }
+}
+
+impl fmt::Display for MoonPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Names the phase of the moon at a given time.
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::moon::{moon_phase, MoonPhase};
+///
+/// // 2013-03-27, full moon
+/// assert_eq!(moon_phase(1_364_378_400_000), MoonPhase::FullMoon);
+/// ```
+#[must_use]
+pub fn moon_phase(unixtime_in_ms: i64) -> MoonPhase {
+    MoonPhase::from_phase(moon_illumination(unixtime_in_ms).phase)
+}
+
+/// Finds the next new moon after `unixtime_in_ms`.
+#[must_use]
+pub fn next_new_moon(unixtime_in_ms: i64) -> i64 {
+    next_phase_time(unixtime_in_ms, 0.0)
+}
+
+/// Finds the next full moon after `unixtime_in_ms`.
+#[must_use]
+pub fn next_full_moon(unixtime_in_ms: i64) -> i64 {
+    next_phase_time(unixtime_in_ms, 0.5)
+}
+
+/// Classification of a full moon by its distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FullMoonKind {
+    /// The full moon is close to perigee.
+    Supermoon,
+    /// The full moon is close to apogee.
+    Micromoon,
+    Regular,
+}
+
+/// A full moon with its distance classification.
+#[derive(Debug, Clone, Copy)]
+pub struct FullMoon {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// distance between the centers of earth and moon in kilometers.
+    pub distance: f64,
+    pub kind: FullMoonKind,
+}
+
+/// Classifies the full moon at `full_moon_in_ms`: it is a supermoon (micromoon)
+/// if a perigee (apogee) is less than `threshold_ms` milliseconds away.
+#[must_use]
+pub fn classify_full_moon(full_moon_in_ms: i64, threshold_ms: i64) -> FullMoonKind {
+    let start = full_moon_in_ms - threshold_ms;
+    let within = |apsis: Apsis| (apsis.unixtime_in_ms - full_moon_in_ms).abs() <= threshold_ms;
+    if within(next_perigee(start)) {
+        FullMoonKind::Supermoon
+    } else if within(next_apogee(start)) {
+        FullMoonKind::Micromoon
+    } else {
+        FullMoonKind::Regular
+    }
+}
+
+/// Lists all full moons between `start_in_ms` and `end_in_ms` with their classification,
+/// see [`classify_full_moon`].
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::moon::{full_moons, FullMoonKind};
+///
+/// // 2013, the full moon on June 23 was less than an hour from perigee, those of May and
+/// // July about 21 hours
+/// let hours = 12 * 60 * 60 * 1_000;
+/// let moons = full_moons(1_356_998_400_000, 1_388_534_400_000, hours);
+/// let supermoons: Vec<_> = moons.iter().filter(|m| m.kind == FullMoonKind::Supermoon).collect();
+/// assert_eq!(supermoons.len(), 1);
+/// ```
+#[must_use]
+pub fn full_moons(start_in_ms: i64, end_in_ms: i64, threshold_ms: i64) -> Vec<FullMoon> {
+    let mut moons = Vec::new();
+    let mut time = next_phase_time(start_in_ms, 0.5);
+    while time < end_in_ms {
+        #[allow(clippy::cast_precision_loss)]
+        let distance = moon_coords(to_days(time as f64)).distance;
+        moons.push(FullMoon {
+            unixtime_in_ms: time,
+            distance,
+            kind: classify_full_moon(time, threshold_ms),
+        });
+        time = next_phase_time(time + MILLISECONDS_PER_DAY_INT, 0.5);
+    }
+    moons
+}
+
+/// Rise and set of the moon within a day, see [`moon_times`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoonTimes {
+    /// Moonrise, `None` if the moon doesn't rise within the day.
+    pub rise: Option<i64>,
+    /// Moonset, `None` if the moon doesn't set within the day.
+    pub set: Option<i64>,
+    /// The moon neither rises nor sets and stays above the horizon.
+    pub always_up: bool,
+    /// The moon neither rises nor sets and stays below the horizon.
+    pub always_down: bool,
+}
+
+impl From<Crossings> for MoonTimes {
+    fn from(crossings: Crossings) -> Self {
+        let neither = crossings.rise.is_none() && crossings.set.is_none();
+        Self {
+            rise: crossings.rise,
+            set: crossings.set,
+            always_up: neither && crossings.always_up,
+            always_down: neither && !crossings.always_up,
+        }
+    }
+}
+
+/// Calculates the rise and set of the moon within the UTC day of `unixtime_in_ms` like
+/// `getMoonTimes` of suncalc with `inUTC` set.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Examples
+///
+/// ```rust
+/// let times = sun::moon::moon_times(1_362_441_600_000, 50.5, 30.5);
+/// if let Some(rise) = times.rise {
+///     println!("the moon rises at {rise}");
+/// }
+/// ```
+#[must_use]
+pub fn moon_times(unixtime_in_ms: i64, lat: f64, lon: f64) -> MoonTimes {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    moon_times_after(start, lat, lon)
+}
+
+/// Calculates the rise and set of the moon within the 24 hours after `start_in_ms`, e.g.
+/// the local midnight for the local day like `getMoonTimes` of suncalc.
+#[must_use]
+pub fn moon_times_after(start_in_ms: i64, lat: f64, lon: f64) -> MoonTimes {
+    moon_crossings(start_in_ms, lat, lon).into()
+}
+
+/// Rise and set of the moon within the 24 hours after `start`.
+pub(crate) fn moon_crossings(start: i64, lat: f64, lon: f64) -> Crossings {
+    crossings_within_day(start, |time| {
+        moon_altitude(time, lat, lon) - MOON_RISE_ALTITUDE
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const MILLISECONDS_PER_HOUR: i64 = 60 * 60 * 1_000;
+
+    #[test]
+    fn test_moon_pos() {
+        // 2013-03-05 UTC, suncalc returns azimuth -0.9784 from south, altitude 0.0146
+        // and distance 364121.37
+        let moon = moon_pos(1_362_441_600_000, 50.5, 30.5);
+        assert!((moon.azimuth - (PI - 0.978_4)).abs() < 0.001, "{moon:?}");
+        assert!((moon.altitude - 0.014_6).abs() < 0.001, "{moon:?}");
+        assert!((moon.distance - 364_121.37).abs() < 0.01, "{moon:?}");
+        let orientation = moon_orientation(1_362_441_600_000, 50.5, 30.5);
+        assert!((moon.parallactic_angle - orientation.parallactic_angle).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_moon_illumination() {
+        // 2013-03-05 UTC, suncalc returns fraction 0.4848 and phase 0.7548
+        let illumination = moon_illumination(1_362_441_600_000);
+        assert!((illumination.fraction - 0.484_8).abs() < 0.001);
+        assert!((illumination.phase - 0.754_8).abs() < 0.001);
+        assert!((illumination.angle - 1.673_3).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_moon_orientation() {
+        // 2013-03-05 UTC, the moon is rising in the south-east
+        let orientation = moon_orientation(1_362_441_600_000, 50.5, 30.5);
+        assert!((orientation.parallactic_angle + 0.598_3).abs() < 0.001);
+        assert!((orientation.bright_limb_angle - 1.673_3).abs() < 0.001);
+        // zero on the meridian, positive west of it
+        assert!(parallactic_angle(0.0, 0.8, 0.1).abs() < 1e-12);
+        assert!(parallactic_angle(0.5, 0.8, 0.1) > 0.0);
+    }
+
+    #[test]
+    fn test_next_phase_time() {
+        // 2013-03-05 UTC, next full moon on 2013-03-27 09:27 UTC
+        let full = next_phase_time(1_362_441_600_000, 0.5);
+        assert!((full - 1_364_376_420_000).abs() < 6 * MILLISECONDS_PER_HOUR);
+        #[allow(clippy::cast_precision_loss)]
+        let fraction = lunation_fraction(full as f64);
+        assert!((fraction - 0.5).abs() < 1e-6);
+        let next = next_phase_time(full + 1_000, 0.5);
+        assert!(next - full > 29 * 24 * MILLISECONDS_PER_HOUR);
+    }
+
+    #[test]
+    fn test_moon_phase() {
+        assert_eq!(MoonPhase::from_phase(0.0), MoonPhase::NewMoon);
+        assert_eq!(MoonPhase::from_phase(0.98), MoonPhase::NewMoon);
+        assert_eq!(MoonPhase::from_phase(0.1), MoonPhase::WaxingCrescent);
+        assert_eq!(MoonPhase::from_phase(0.5), MoonPhase::FullMoon);
+        assert_eq!(MoonPhase::from_phase(0.77), MoonPhase::LastQuarter);
+        assert_eq!(MoonPhase::WaningGibbous.to_string(), "waning gibbous");
+        // 2013-03-05 UTC, three quarters of the lunation
+        assert_eq!(moon_phase(1_362_441_600_000), MoonPhase::LastQuarter);
+        // new moon on 2013-03-11 19:51 UTC, full moon on 2013-03-27 09:27 UTC
+        let new = next_new_moon(1_362_441_600_000);
+        assert!((new - 1_363_031_460_000).abs() < 6 * MILLISECONDS_PER_HOUR);
+        assert_eq!(moon_phase(new), MoonPhase::NewMoon);
+        let full = next_full_moon(new);
+        assert!((full - 1_364_376_420_000).abs() < 6 * MILLISECONDS_PER_HOUR);
+        assert!(next_new_moon(new + 1_000) > full);
+    }
+
+    #[test]
+    fn test_apsides() {
+        let date = 1_362_441_600_000;
+        let perigee = next_perigee(date);
+        let apogee = next_apogee(date);
+        assert!(perigee.unixtime_in_ms > date);
+        assert!(apogee.unixtime_in_ms > date);
+        assert!(perigee.distance < 370_500.0 && apogee.distance > 404_000.0);
+        let after = next_perigee(perigee.unixtime_in_ms);
+        assert!(after.unixtime_in_ms > perigee.unixtime_in_ms);
+        // perigee on 2013-06-23 11:32 UTC at 356,991 km, on 2016-11-14 11:23 UTC at 356,509 km
+        for (known, known_distance) in [
+            (1_371_987_120_000, 356_991.0),
+            (1_479_122_580_000, 356_509.0),
+        ] {
+            let perigee = next_perigee(known - 10 * 24 * MILLISECONDS_PER_HOUR);
+            assert!((perigee.unixtime_in_ms - known).abs() < MILLISECONDS_PER_HOUR);
+            assert!((perigee.distance - known_distance).abs() < 200.0);
+        }
+        // distance of example 47.a in Meeus at 1992-04-12 00:00 TD
+        assert!((moon_distance(to_days(703_036_800_000.0)) - 368_409.7).abs() < 10.0);
+    }
+
+    #[test]
+    fn test_moon_times() {
+        // 2013-03-04 UTC, suncalc returns rise 23:54:29 and set 07:47:58
+        let times = moon_times(1_362_355_200_000 + 12 * MILLISECONDS_PER_HOUR, 50.5, 30.5);
+        assert!((times.rise.unwrap() - 1_362_441_269_000).abs() < 5_000);
+        assert!((times.set.unwrap() - 1_362_383_278_000).abs() < 5_000);
+        assert!(!times.always_up && !times.always_down);
+        // the same day starting at midnight in Kyiv, UTC+2
+        let local = moon_times_after(1_362_355_200_000 - 2 * MILLISECONDS_PER_HOUR, 50.5, 30.5);
+        assert_eq!(local.set, times.set);
+        // the moon rose already before midnight UTC
+        assert!(local.rise.unwrap() < 1_362_355_200_000);
+        // the moon circles above the pole
+        let polar = moon_times(1_362_441_600_000, 89.0, 0.0);
+        assert!(polar.always_up || polar.always_down, "{polar:?}");
+    }
+}
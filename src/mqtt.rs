@@ -0,0 +1,231 @@
+//! Periodic publishing of the sun position and upcoming events to an
+//! [MQTT](https://mqtt.org/) broker, e.g. for home automation.
+//!
+//! [`messages`] builds the topics and payloads of one update without any network
+//! access; [`Publisher`] sends them to a broker in a loop. The `sun-mqtt` binary wraps
+//! the publisher for the command line.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::mqtt::{messages, TopicSchema};
+//!
+//! let schema = TopicSchema::new("home/sun/{key}");
+//! for message in messages(1_362_441_600_000, 48.0, 9.0, &schema) {
+//!     println!("{} = {}", message.topic, message.payload);
+//! }
+//! ```
+
+use std::thread;
+use std::time::Duration;
+
+use rumqttc::{Client, ClientError, MqttOptions, QoS};
+
+use crate::observer::{Clock, SystemClock};
+use crate::report::next_event;
+use crate::{pos, SunPhase};
+
+const KEY_PLACEHOLDER: &str = "{key}";
+
+/// Events published with the key `next_<name>`.
+const EVENTS: [(&str, SunPhase); 10] = [
+    ("night_end", SunPhase::NightEnd),
+    ("dawn", SunPhase::Dawn),
+    (
+        "blue_hour_end",
+        SunPhase::BlueHourEnd(SunPhase::BLUE_HOUR_DEG),
+    ),
+    ("sunrise", SunPhase::Sunrise),
+    ("golden_hour_end", SunPhase::GoldenHourEnd),
+    ("golden_hour", SunPhase::GoldenHour),
+    ("sunset", SunPhase::Sunset),
+    (
+        "blue_hour_start",
+        SunPhase::BlueHourStart(SunPhase::BLUE_HOUR_DEG),
+    ),
+    ("dusk", SunPhase::Dusk),
+    ("night", SunPhase::Night),
+];
+
+/// Template of the topics, in which `{key}` is replaced by the name of the value,
+/// e.g. `azimuth` or `next_sunrise`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicSchema {
+    template: String,
+}
+
+impl TopicSchema {
+    /// Creates a schema from a template like `"sun/{key}"`.
+    ///
+    /// A template without `{key}` is used as prefix, i.e. `"sun"` equals `"sun/{key}"`.
+    #[must_use]
+    pub fn new(template: &str) -> Self {
+        let template = if template.contains(KEY_PLACEHOLDER) {
+            template.to_string()
+        } else {
+            format!("{}/{KEY_PLACEHOLDER}", template.trim_end_matches('/'))
+        };
+        Self { template }
+    }
+
+    /// Topic of the value with the given key.
+    #[must_use]
+    pub fn topic(&self, key: &str) -> String {
+        self.template.replace(KEY_PLACEHOLDER, key)
+    }
+}
+
+impl Default for TopicSchema {
+    /// Publishes to `sun/{key}`.
+    fn default() -> Self {
+        Self::new("sun/{key}")
+    }
+}
+
+/// A message to publish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// Topic of the message.
+    pub topic: String,
+    /// Payload as UTF-8 text.
+    pub payload: String,
+}
+
+/// Builds the messages of one update at `unixtime_in_ms`.
+///
+/// `azimuth` and `altitude` are published in degrees, the azimuth measured clockwise
+/// from north; the events `next_sunrise`, `next_sunset`, `next_dawn` and so on are
+/// [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds and are
+/// left out while they don't occur within the next two days, e.g. in polar night.
+#[must_use]
+pub fn messages(unixtime_in_ms: i64, lat: f64, lon: f64, schema: &TopicSchema) -> Vec<Message> {
+    let position = pos(unixtime_in_ms, lat, lon);
+    let mut messages = vec![
+        Message {
+            topic: schema.topic("azimuth"),
+            payload: format!("{:.2}", position.azimuth.to_degrees()),
+        },
+        Message {
+            topic: schema.topic("altitude"),
+            payload: format!("{:.2}", position.altitude.to_degrees()),
+        },
+    ];
+    messages.extend(EVENTS.iter().filter_map(|&(name, phase)| {
+        next_event(unixtime_in_ms, phase, lat, lon, 2).map(|time| Message {
+            topic: schema.topic(&format!("next_{name}")),
+            payload: time.to_string(),
+        })
+    }));
+    messages
+}
+
+/// Publishes the messages of [`messages`] to a broker at a fixed interval.
+#[derive(Debug, Clone)]
+pub struct Publisher {
+    /// Host name of the broker.
+    pub host: String,
+    /// Port of the broker.
+    pub port: u16,
+    /// Client identifier to connect with.
+    pub client_id: String,
+    /// [Latitude](https://en.wikipedia.org/wiki/Latitude) of the observer in degrees.
+    pub lat: f64,
+    /// [Longitude](https://en.wikipedia.org/wiki/Longitude) of the observer in degrees.
+    pub lon: f64,
+    /// Topics to publish to.
+    pub schema: TopicSchema,
+    /// Time between two updates.
+    pub interval: Duration,
+    /// Whether the broker retains the last values for new subscribers.
+    pub retain: bool,
+}
+
+impl Publisher {
+    /// Creates a publisher for `localhost:1883` updating every minute with retained
+    /// messages to the [default](TopicSchema::default) topics.
+    #[must_use]
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: "sun".to_string(),
+            lat,
+            lon,
+            schema: TopicSchema::default(),
+            interval: Duration::from_secs(60),
+            retain: true,
+        }
+    }
+
+    /// Connects to the broker and publishes until an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the client if a message could not be queued, e.g. after the
+    /// connection to the broker was closed.
+    pub fn run(&self) -> Result<(), ClientError> {
+        let options = MqttOptions::new(&self.client_id, &self.host, self.port);
+        let (client, mut connection) = Client::new(options, 2 * (EVENTS.len() + 2));
+        // the event loop sends the queued messages and reconnects after errors
+        thread::spawn(move || for _ in connection.iter() {});
+        loop {
+            let now = SystemClock.now_in_ms();
+            for message in messages(now, self.lat, self.lon, &self.schema) {
+                client.publish(
+                    message.topic,
+                    QoS::AtLeastOnce,
+                    self.retain,
+                    message.payload,
+                )?;
+            }
+            thread::sleep(self.interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_topic_schema() {
+        assert_eq!(TopicSchema::default().topic("azimuth"), "sun/azimuth");
+        assert_eq!(
+            TopicSchema::new("home/{key}/state").topic("altitude"),
+            "home/altitude/state"
+        );
+        assert_eq!(
+            TopicSchema::new("home/sun/").topic("altitude"),
+            "home/sun/altitude"
+        );
+    }
+
+    #[test]
+    fn test_messages() {
+        // 2013-03-05 00:00 UTC
+        let date = 1_362_441_600_000;
+        let update = messages(date, 50.5, 30.5, &TopicSchema::default());
+        assert_eq!(update.len(), 2 + EVENTS.len());
+        assert_eq!(update[0].topic, "sun/azimuth");
+        let altitude: f64 = update[1].payload.parse().unwrap();
+        assert!((altitude - pos(date, 50.5, 30.5).altitude.to_degrees()).abs() < 0.01);
+        let sunrise = update
+            .iter()
+            .find(|message| message.topic == "sun/next_sunrise")
+            .unwrap();
+        let expected = crate::time_at_phase(
+            date + 12 * 60 * 60 * 1_000,
+            SunPhase::Sunrise,
+            50.5,
+            30.5,
+            0.0,
+        );
+        assert_eq!(sunrise.payload, expected.to_string());
+        // no sunrise in the polar night of Longyearbyen
+        let polar = messages(1_387_627_200_000, 78.2, 15.6, &TopicSchema::default());
+        assert!(polar
+            .iter()
+            .all(|message| message.topic != "sun/next_sunrise"));
+    }
+}
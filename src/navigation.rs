@@ -0,0 +1,222 @@
+//! Celestial navigation with the sun: estimates the position of an observer
+//! from several timestamped measurements of the sun.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::navigation::{fix_position, SunObservation};
+//!
+//! // altitudes measured with a sextant in the morning, at noon and in the afternoon
+//! let observations: Vec<SunObservation> = [1_371_794_400_000, 1_371_808_800_000, 1_371_823_200_000]
+//!     .iter()
+//!     .map(|&time| SunObservation {
+//!         unixtime_in_ms: time,
+//!         altitude_deg: sun::pos(time, 48.0, 9.0).altitude.to_degrees(),
+//!         azimuth_deg: None,
+//!     })
+//!     .collect();
+//! let fix = fix_position(&observations).unwrap();
+//! assert!((fix.lat - 48.0).abs() < 0.01 && (fix.lon - 9.0).abs() < 0.01);
+//! ```
+
+use std::fmt;
+
+use crate::pos;
+
+const GRID_STEP: f64 = 5.0;
+const MAX_ITERATIONS: usize = 50;
+const DERIVATIVE_STEP: f64 = 1e-5;
+
+/// A measurement of the sun.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunObservation {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the measurement in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Measured geometric altitude of the sun's center in degrees.
+    pub altitude_deg: f64,
+    /// Measured azimuth of the sun in degrees clockwise from north, if available.
+    pub azimuth_deg: Option<f64>,
+}
+
+/// Estimated position of the observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fix {
+    /// [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    pub lat: f64,
+    /// [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    pub lon: f64,
+    /// Root mean square of the remaining residuals in degrees.
+    pub rms_deg: f64,
+}
+
+/// Errors of [`fix_position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixError {
+    /// At least two measured quantities are needed for two unknowns.
+    TooFewObservations,
+    /// The observations do not determine a unique position, e.g. they were all taken at the same time.
+    Degenerate,
+}
+
+impl fmt::Display for FixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixError::TooFewObservations => write!(f, "at least two measurements are needed"),
+            FixError::Degenerate => write!(f, "the observations do not determine a position"),
+        }
+    }
+}
+
+impl std::error::Error for FixError {}
+
+fn residuals(observations: &[SunObservation], lat: f64, lon: f64) -> Vec<f64> {
+    let mut residuals = Vec::with_capacity(observations.len() * 2);
+    for observation in observations {
+        let sun = pos(observation.unixtime_in_ms, lat, lon);
+        residuals.push(sun.altitude.to_degrees() - observation.altitude_deg);
+        if let Some(azimuth) = observation.azimuth_deg {
+            let delta = (sun.azimuth.to_degrees() - azimuth + 180.0).rem_euclid(360.0) - 180.0;
+            // weight the azimuth by the length of the arc on the sky
+            residuals.push(delta * sun.altitude.cos());
+        }
+    }
+    residuals
+}
+
+fn sum_of_squares(residuals: &[f64]) -> f64 {
+    residuals.iter().map(|r| r * r).sum()
+}
+
+fn wrap_lon(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Estimates the position of an observer from sun measurements by least squares.
+///
+/// A coarse global grid search provides the starting point for a Levenberg-Marquardt
+/// refinement, so no initial guess is needed. With altitudes only, two measurements
+/// several hours apart are the minimum; more measurements average out measurement errors.
+///
+/// # Errors
+///
+/// Returns a [`FixError`] if the observations are insufficient.
+pub fn fix_position(observations: &[SunObservation]) -> Result<Fix, FixError> {
+    let quantities: usize = observations
+        .iter()
+        .map(|o| 1 + usize::from(o.azimuth_deg.is_some()))
+        .sum();
+    if quantities < 2 {
+        return Err(FixError::TooFewObservations);
+    }
+
+    let cost = |lat: f64, lon: f64| sum_of_squares(&residuals(observations, lat, lon));
+
+    // coarse grid search for the starting point
+    let mut best = (0.0, 0.0, f64::INFINITY);
+    let mut lat = -90.0;
+    while lat <= 90.0 {
+        let mut lon = -180.0;
+        while lon < 180.0 {
+            let value = cost(lat, lon);
+            if value < best.2 {
+                best = (lat, lon, value);
+            }
+            lon += GRID_STEP;
+        }
+        lat += GRID_STEP;
+    }
+
+    let (mut lat, mut lon, mut current) = best;
+    let mut damping = 1e-3;
+    for _ in 0..MAX_ITERATIONS {
+        let r = residuals(observations, lat, lon);
+        let d_lat = residuals(observations, lat + DERIVATIVE_STEP, lon);
+        let d_lon = residuals(observations, lat, lon + DERIVATIVE_STEP);
+        let (mut a11, mut a12, mut a22, mut g1, mut g2) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for i in 0..r.len() {
+            let j1 = (d_lat[i] - r[i]) / DERIVATIVE_STEP;
+            let j2 = (d_lon[i] - r[i]) / DERIVATIVE_STEP;
+            a11 += j1 * j1;
+            a12 += j1 * j2;
+            a22 += j2 * j2;
+            g1 += j1 * r[i];
+            g2 += j2 * r[i];
+        }
+        let mut improved = false;
+        while damping < 1e10 {
+            let (b11, b22) = (a11 * (1.0 + damping), a22 * (1.0 + damping));
+            let determinant = b11 * b22 - a12 * a12;
+            if determinant.abs() < f64::EPSILON {
+                damping *= 10.0;
+                continue;
+            }
+            let step_lat = -(b22 * g1 - a12 * g2) / determinant;
+            let step_lon = -(b11 * g2 - a12 * g1) / determinant;
+            let candidate_lat = (lat + step_lat).clamp(-90.0, 90.0);
+            let candidate_lon = wrap_lon(lon + step_lon);
+            let candidate = cost(candidate_lat, candidate_lon);
+            if candidate < current {
+                let converged = step_lat.abs() < 1e-9 && step_lon.abs() < 1e-9;
+                (lat, lon, current) = (candidate_lat, candidate_lon, candidate);
+                damping /= 10.0;
+                improved = !converged;
+                break;
+            }
+            damping *= 10.0;
+        }
+        if !improved {
+            break;
+        }
+        if a11 * a22 - a12 * a12 < 1e-12 {
+            return Err(FixError::Degenerate);
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let rms_deg = (current / quantities as f64).sqrt();
+    Ok(Fix { lat, lon, rms_deg })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn observe(time: i64, lat: f64, lon: f64, with_azimuth: bool) -> SunObservation {
+        let sun = pos(time, lat, lon);
+        SunObservation {
+            unixtime_in_ms: time,
+            altitude_deg: sun.altitude.to_degrees(),
+            azimuth_deg: with_azimuth.then(|| sun.azimuth.to_degrees()),
+        }
+    }
+
+    #[test]
+    fn test_fix_from_altitudes() {
+        // 2013-03-05 in Sydney, three hours apart
+        let observations: Vec<_> = [1_362_448_800_000, 1_362_459_600_000, 1_362_470_400_000]
+            .iter()
+            .map(|&time| observe(time, -33.87, 151.2, false))
+            .collect();
+        let fix = fix_position(&observations).unwrap();
+        assert!((fix.lat + 33.87).abs() < 1e-3);
+        assert!((fix.lon - 151.2).abs() < 1e-3);
+        assert!(fix.rms_deg < 1e-3);
+    }
+
+    #[test]
+    fn test_fix_from_single_altitude_and_azimuth() {
+        let fix = fix_position(&[observe(1_362_484_800_000, 50.5, 30.5, true)]).unwrap();
+        assert!((fix.lat - 50.5).abs() < 1e-3);
+        assert!((fix.lon - 30.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_too_few_observations() {
+        let observations = [observe(1_362_484_800_000, 50.5, 30.5, false)];
+        assert_eq!(
+            fix_position(&observations),
+            Err(FixError::TooFewObservations)
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! An observer at a fixed location asking about the sun right now, e.g. for a status bar
+//! or a home automation rule.
+//!
+//! The current time comes from a [`Clock`], so logic built on an [`Observer`] can be
+//! tested with a [`FixedClock`] instead of the system time.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::observer::{FixedClock, Observer};
+//!
+//! let berlin = Observer::new(52.5, 13.4);
+//! println!("the sun is at {:.1}°", berlin.current_pos().altitude.to_degrees());
+//!
+//! // 2013-03-05 00:00 UTC
+//! let mocked = Observer::with_clock(48.0, 9.0, FixedClock(1_362_441_600_000));
+//! assert_eq!(mocked.next_sunrise(), Some(1_362_463_116_241));
+//! ```
+//!
+//! A [`SolarObserver`] keeps the location, the height and the refraction setting for
+//! calculations at arbitrary times:
+//!
+//! ```rust
+//! use sun::observer::SolarObserver;
+//! use sun::SunPhase;
+//!
+//! let summit = SolarObserver::new(47.42, 10.99).height(2_962.0).refraction(true);
+//! let sunrise = summit.time_at(1_362_441_600_000, SunPhase::Sunrise).time().unwrap();
+//! assert!(summit.position(sunrise).altitude < 0.0);
+//! ```
+
+use std::ops::RangeInclusive;
+use std::time::SystemTime;
+
+use crate::cursor::{events, Events};
+use crate::datetime::unixtime_from_system_time;
+use crate::report::next_event;
+use crate::{
+    next_time_at_phase, observed_pos, pos, try_time_at_phase, AltitudeKind, PhaseOutcome, Position,
+    SunPhase,
+};
+
+/// Days to search ahead for the next event before giving up, e.g. in polar night.
+const MAX_DAYS: i64 = 2;
+
+/// A source of the current time.
+pub trait Clock {
+    /// Returns the current [unix time](https://en.wikipedia.org/wiki/Unix_time) in
+    /// milliseconds.
+    fn now_in_ms(&self) -> i64;
+}
+
+/// The time of the operating system.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_in_ms(&self) -> i64 {
+        unixtime_from_system_time(SystemTime::now())
+    }
+}
+
+/// A clock standing still at a [unix time](https://en.wikipedia.org/wiki/Unix_time) in
+/// milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FixedClock(pub i64);
+
+impl Clock for FixedClock {
+    fn now_in_ms(&self) -> i64 {
+        self.0
+    }
+}
+
+impl<F: Fn() -> i64> Clock for F {
+    fn now_in_ms(&self) -> i64 {
+        self()
+    }
+}
+
+/// A location on the earth together with a [`Clock`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer<C = SystemClock> {
+    /// [Latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    pub lat: f64,
+    /// [Longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    pub lon: f64,
+    clock: C,
+}
+
+impl Observer {
+    /// Creates an observer using the [`SystemClock`].
+    #[must_use]
+    pub const fn new(lat: f64, lon: f64) -> Self {
+        Self::with_clock(lat, lon, SystemClock)
+    }
+}
+
+impl<C: Clock> Observer<C> {
+    /// Creates an observer using the given clock.
+    #[must_use]
+    pub const fn with_clock(lat: f64, lon: f64, clock: C) -> Self {
+        Self { lat, lon, clock }
+    }
+
+    /// Returns the clock.
+    #[must_use]
+    pub const fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the current time of the clock in milliseconds.
+    #[must_use]
+    pub fn now_in_ms(&self) -> i64 {
+        self.clock.now_in_ms()
+    }
+
+    /// Calculates the current position of the sun, see [`pos`].
+    #[must_use]
+    pub fn current_pos(&self) -> Position {
+        pos(self.now_in_ms(), self.lat, self.lon)
+    }
+
+    /// Finds the next occurrence of `phase` after now, `None` if it doesn't occur within
+    /// the next two days.
+    #[must_use]
+    pub fn next_event(&self, phase: SunPhase) -> Option<i64> {
+        next_event(self.now_in_ms(), phase, self.lat, self.lon, MAX_DAYS)
+    }
+
+    /// Finds the next sunrise, see [`Observer::next_event`].
+    #[must_use]
+    pub fn next_sunrise(&self) -> Option<i64> {
+        self.next_event(SunPhase::Sunrise)
+    }
+
+    /// Finds the next sunset, see [`Observer::next_event`].
+    #[must_use]
+    pub fn next_sunset(&self) -> Option<i64> {
+        self.next_event(SunPhase::Sunset)
+    }
+}
+
+/// A location on the earth with the height of the observer and whether positions include
+/// the atmospheric refraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolarObserver {
+    lat: f64,
+    lon: f64,
+    height: f64,
+    refraction: bool,
+}
+
+impl SolarObserver {
+    /// Creates an observer at sea level without refraction.
+    ///
+    /// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    #[must_use]
+    pub const fn new(lat: f64, lon: f64) -> Self {
+        Self {
+            lat,
+            lon,
+            height: 0.0,
+            refraction: false,
+        }
+    }
+
+    /// Sets the height of the observer in meters above the horizon, which makes the sun
+    /// rise earlier and set later.
+    #[must_use]
+    pub const fn height(self, height: f64) -> Self {
+        Self { height, ..self }
+    }
+
+    /// Sets whether [`SolarObserver::position`] returns the apparent altitude lifted by
+    /// the atmospheric refraction instead of the geometric altitude.
+    ///
+    /// The times of the phases always include the standard refraction of their
+    /// definition, e.g. the 0.833° of the sunrise.
+    #[must_use]
+    pub const fn refraction(self, refraction: bool) -> Self {
+        Self { refraction, ..self }
+    }
+
+    /// Calculates the position of the sun, see [`pos`] and [`observed_pos`].
+    #[must_use]
+    pub fn position(&self, unixtime_in_ms: i64) -> Position {
+        if self.refraction {
+            observed_pos(unixtime_in_ms, self.lat, self.lon).position(AltitudeKind::Apparent)
+        } else {
+            pos(unixtime_in_ms, self.lat, self.lon)
+        }
+    }
+
+    /// Calculates the time of `phase` on the day of `unixtime_in_ms`, see
+    /// [`try_time_at_phase`].
+    #[must_use]
+    pub fn time_at(&self, unixtime_in_ms: i64, phase: SunPhase) -> PhaseOutcome {
+        try_time_at_phase(unixtime_in_ms, phase, self.lat, self.lon, self.height)
+    }
+
+    /// Finds the next time of `phase` after `unixtime_in_ms`, see [`next_time_at_phase`].
+    #[must_use]
+    pub fn next_time_at(&self, unixtime_in_ms: i64, phase: SunPhase) -> Option<i64> {
+        next_time_at_phase(unixtime_in_ms, phase, self.lat, self.lon, self.height)
+    }
+
+    /// Returns an iterator over the sun events in the range of
+    /// [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds, see
+    /// [`events`].
+    #[must_use]
+    pub const fn events(&self, range: RangeInclusive<i64>) -> Events {
+        events(
+            *range.start(),
+            *range.end(),
+            self.lat,
+            self.lon,
+            self.height,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let date = 1_362_441_600_000;
+        let observer = Observer::with_clock(48.0, 9.0, FixedClock(date));
+        let position = pos(date, 48.0, 9.0);
+        assert!((observer.current_pos().altitude - position.altitude).abs() < 1e-12);
+        let sunset = crate::time_at_phase(date, SunPhase::Sunset, 48.0, 9.0, 0.0);
+        assert_eq!(observer.next_sunset(), Some(sunset));
+        // after sunset the next one is on the following day
+        let evening = Observer::with_clock(48.0, 9.0, FixedClock(sunset + 1));
+        let next = evening.next_sunset().unwrap();
+        assert!((next - sunset - 24 * 60 * 60 * 1_000).abs() < 5 * 60 * 1_000);
+        // no sunset in polar night
+        let polar = Observer::with_clock(80.0, 9.0, FixedClock(date - 60 * 24 * 60 * 60 * 1_000));
+        assert_eq!(polar.next_sunset(), None);
+    }
+
+    #[test]
+    fn test_clock() {
+        let time = Cell::new(1_362_441_600_000);
+        let observer = Observer::with_clock(48.0, 9.0, || time.get());
+        let before = observer.current_pos();
+        time.set(time.get() + 60 * 60 * 1_000);
+        assert!((observer.current_pos().azimuth - before.azimuth).abs() > 0.1);
+        // the system time is after 2020
+        assert!(Observer::new(48.0, 9.0).now_in_ms() > 1_577_836_800_000);
+    }
+
+    #[test]
+    fn test_solar_observer() {
+        let date = 1_362_441_600_000;
+        let observer = SolarObserver::new(48.0, 9.0);
+        assert_eq!(
+            observer.time_at(date, SunPhase::Sunrise),
+            PhaseOutcome::Time(crate::time_at_phase(
+                date,
+                SunPhase::Sunrise,
+                48.0,
+                9.0,
+                0.0
+            ))
+        );
+        let elevated = observer.height(1_000.0);
+        let sunrise = |observer: SolarObserver| observer.time_at(date, SunPhase::Sunrise).time();
+        assert!(sunrise(elevated) < sunrise(observer));
+        assert_eq!(
+            elevated.next_time_at(date, SunPhase::Sunrise),
+            sunrise(elevated)
+        );
+        // the refraction lifts the sun
+        let noon = crate::solar_noon(date, 9.0);
+        let refracted = observer.refraction(true).position(noon);
+        assert!(refracted.altitude > observer.position(noon).altitude);
+        assert!((refracted.azimuth - observer.position(noon).azimuth).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_solar_observer_events() {
+        let date = 1_362_441_600_000;
+        let observer = SolarObserver::new(50.5, 30.5).height(100.0);
+        let end = date + 24 * 60 * 60 * 1_000;
+        let range: Vec<_> = observer.events(date..=end).collect();
+        assert_eq!(
+            range,
+            events(date, end, 50.5, 30.5, 100.0).collect::<Vec<_>>()
+        );
+        assert_eq!(range.len(), 14);
+    }
+}
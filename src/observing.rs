@@ -0,0 +1,316 @@
+//! Windows per night in which a fixed target of the sky, e.g. a star or a galaxy, is high
+//! enough while the sky is dark enough, for planning astronomical observations.
+//!
+//! The altitudes are geometric, i.e. without refraction, which shifts the windows by a
+//! few minutes for targets close to the minimum altitude at most. With the `moon`
+//! feature, the darkness can also require the moon to be down, thin enough or far
+//! enough from the target.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::coords::Equatorial;
+//! use sun::observing::{observing_windows, Darkness};
+//!
+//! // the Orion Nebula in the first week of 2013, at least 30° high
+//! let m42 = Equatorial {
+//!     right_ascension: 83.82_f64.to_radians(),
+//!     declination: (-5.39_f64).to_radians(),
+//! };
+//! let nights = observing_windows(
+//!     m42,
+//!     1_356_998_400_000,
+//!     1_357_603_200_000,
+//!     48.0,
+//!     9.0,
+//!     30.0,
+//!     &Darkness::ASTRONOMICAL,
+//! );
+//! for night in nights {
+//!     for window in night.windows {
+//!         println!("from {} to {}", window.start_in_ms, window.end_in_ms);
+//!     }
+//! }
+//! ```
+
+use crate::coords::{equatorial_to_horizontal, Equatorial};
+#[cfg(feature = "moon")]
+use crate::moon::{moon_horizontal, moon_illumination};
+use crate::{local_noon, pos, MILLISECONDS_PER_DAY_INT};
+
+const STEP_MS: i64 = 5 * 60 * 1_000;
+
+/// Limits of the moonlight, see [`Darkness::moon`].
+#[cfg(feature = "moon")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoonConstraint {
+    /// Largest illuminated fraction of the moon above the horizon, `0.0` to require the
+    /// moon to be down.
+    pub max_illumination: f64,
+    /// Smallest distance in degrees between the moon above the horizon and the target.
+    pub min_separation_deg: f64,
+}
+
+#[cfg(feature = "moon")]
+impl MoonConstraint {
+    /// The moon has to be below the horizon.
+    pub const BELOW_HORIZON: Self = Self {
+        max_illumination: 0.0,
+        min_separation_deg: 0.0,
+    };
+}
+
+/// Conditions for a sky dark enough to observe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Darkness {
+    /// Highest altitude of the sun in degrees, e.g. `-18.0` for astronomical darkness.
+    pub max_sun_altitude_deg: f64,
+    /// Limits of the moonlight, `None` to ignore the moon.
+    #[cfg(feature = "moon")]
+    pub moon: Option<MoonConstraint>,
+}
+
+impl Darkness {
+    /// The sun more than 12° below the horizon.
+    pub const NAUTICAL: Self = Self::with_sun_altitude(-12.0);
+    /// The sun more than 18° below the horizon.
+    pub const ASTRONOMICAL: Self = Self::with_sun_altitude(-18.0);
+
+    /// Requires the sun below `max_sun_altitude_deg` degrees, ignoring the moon.
+    #[must_use]
+    pub const fn with_sun_altitude(max_sun_altitude_deg: f64) -> Self {
+        Self {
+            max_sun_altitude_deg,
+            #[cfg(feature = "moon")]
+            moon: None,
+        }
+    }
+
+    /// Additionally limits the moonlight.
+    #[cfg(feature = "moon")]
+    #[must_use]
+    pub const fn with_moon(self, moon: MoonConstraint) -> Self {
+        Self {
+            moon: Some(moon),
+            ..self
+        }
+    }
+
+    /// Whether the sky is dark enough at a time to observe `target`.
+    fn is_dark(self, target: Equatorial, unixtime_in_ms: i64, lat: f64, lon: f64) -> bool {
+        if pos(unixtime_in_ms, lat, lon).altitude.to_degrees() > self.max_sun_altitude_deg {
+            return false;
+        }
+        #[cfg(feature = "moon")]
+        if let Some(constraint) = self.moon {
+            let (_, altitude, coords) = moon_horizontal(unixtime_in_ms, lat, lon);
+            if altitude > 0.0 {
+                let moon = Equatorial {
+                    right_ascension: coords.right_ascension,
+                    declination: coords.declination,
+                };
+                return moon_illumination(unixtime_in_ms).fraction <= constraint.max_illumination
+                    && moon.separation(target).to_degrees() >= constraint.min_separation_deg;
+            }
+        }
+        #[cfg(not(feature = "moon"))]
+        let _ = target;
+        true
+    }
+}
+
+/// A period in which the target can be observed, see [`observing_windows`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObservingWindow {
+    /// Start of the window.
+    pub start_in_ms: i64,
+    /// End of the window.
+    pub end_in_ms: i64,
+    /// Time of the highest altitude of the target within the window.
+    pub peak_in_ms: i64,
+    /// Highest altitude of the target within the window in degrees.
+    pub peak_altitude_deg: f64,
+}
+
+/// The observing windows of one night, see [`observing_windows`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservingNight {
+    /// Local solar noon before the night, the start of the searched period of 24 hours,
+    /// as [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    pub noon_in_ms: i64,
+    /// Windows in which the target can be observed, empty if it can't be observed in
+    /// this night.
+    pub windows: Vec<ObservingWindow>,
+}
+
+/// Narrows the change of `observable` in `[low, high]` down to a second by bisection.
+fn bisect(mut low: i64, mut high: i64, observable: impl Fn(i64) -> bool) -> i64 {
+    let at_low = observable(low);
+    while high - low > 1_000 {
+        let middle = (low + high) / 2;
+        if observable(middle) == at_low {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2
+}
+
+/// Finds the windows per night in which `target` stands at least `min_altitude_deg`
+/// degrees above the horizon while the sky is dark enough.
+///
+/// The nights start at the local solar noon of each UTC date from `start_in_ms` to
+/// `end_in_ms`. The windows are resolved to five minutes and their limits to a second.
+///
+/// * `target`    - right ascension and declination of the target for J2000.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub fn observing_windows(
+    target: Equatorial,
+    start_in_ms: i64,
+    end_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    min_altitude_deg: f64,
+    darkness: &Darkness,
+) -> Vec<ObservingNight> {
+    let altitude_deg = |time: i64| {
+        equatorial_to_horizontal(target, time, lat, lon)
+            .altitude
+            .to_degrees()
+    };
+    let observable = |time: i64| {
+        altitude_deg(time) >= min_altitude_deg && darkness.is_dark(target, time, lat, lon)
+    };
+    let first_day = start_in_ms.div_euclid(MILLISECONDS_PER_DAY_INT);
+    let last_day = end_in_ms.div_euclid(MILLISECONDS_PER_DAY_INT);
+    (first_day..=last_day)
+        .map(|day| {
+            let noon = local_noon(day * MILLISECONDS_PER_DAY_INT, lon);
+            let mut windows = Vec::new();
+            let mut open: Option<ObservingWindow> = None;
+            let mut previous = noon;
+            for step in 0..=MILLISECONDS_PER_DAY_INT / STEP_MS {
+                let time = noon + step * STEP_MS;
+                if observable(time) {
+                    let window = open.get_or_insert_with(|| {
+                        let start = if step == 0 {
+                            time
+                        } else {
+                            bisect(previous, time, observable)
+                        };
+                        ObservingWindow {
+                            start_in_ms: start,
+                            end_in_ms: time,
+                            peak_in_ms: time,
+                            peak_altitude_deg: altitude_deg(time),
+                        }
+                    });
+                    window.end_in_ms = time;
+                    let altitude = altitude_deg(time);
+                    if altitude > window.peak_altitude_deg {
+                        window.peak_in_ms = time;
+                        window.peak_altitude_deg = altitude;
+                    }
+                } else if let Some(mut window) = open.take() {
+                    window.end_in_ms = bisect(previous, time, observable);
+                    windows.push(window);
+                }
+                previous = time;
+            }
+            windows.extend(open);
+            ObservingNight {
+                noon_in_ms: noon,
+                windows,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{time_at_phase, SunPhase};
+
+    const HOUR: i64 = 60 * 60 * 1_000;
+
+    const M42: Equatorial = Equatorial {
+        right_ascension: 1.462_9,
+        declination: -0.094_1,
+    };
+
+    #[test]
+    fn test_observing_windows() {
+        // 2013-01-15 UTC, Orion is already high at the end of the dusk
+        let date = 1_358_208_000_000;
+        let nights = observing_windows(M42, date, date, 48.0, 9.0, 20.0, &Darkness::ASTRONOMICAL);
+        assert_eq!(nights.len(), 1);
+        let windows = &nights[0].windows;
+        assert_eq!(windows.len(), 1, "{windows:?}");
+        let window = windows[0];
+        // the approximation of the phase times differs by a few minutes
+        let night = time_at_phase(date, SunPhase::Night, 48.0, 9.0, 0.0);
+        assert!(
+            (window.start_in_ms - night).abs() < 5 * 60 * 1_000,
+            "{window:?}"
+        );
+        assert!(
+            window.end_in_ms - window.start_in_ms > 5 * HOUR,
+            "{window:?}"
+        );
+        // M42 culminates at 36.6° in Stuttgart
+        assert!((window.peak_altitude_deg - 36.6).abs() < 1.0, "{window:?}");
+        // in June it stands next to the sun
+        let june = observing_windows(
+            M42,
+            1_371_772_800_000,
+            1_372_377_600_000,
+            48.0,
+            9.0,
+            20.0,
+            &Darkness::ASTRONOMICAL,
+        );
+        assert_eq!(june.len(), 8);
+        assert!(june.iter().all(|night| night.windows.is_empty()));
+    }
+
+    #[test]
+    fn test_darkness() {
+        // no astronomical darkness in June at 52°, but nautical darkness
+        let date = 1_371_772_800_000;
+        let vega = Equatorial {
+            right_ascension: 279.23_f64.to_radians(),
+            declination: 38.78_f64.to_radians(),
+        };
+        let astronomical =
+            observing_windows(vega, date, date, 52.0, 9.0, 20.0, &Darkness::ASTRONOMICAL);
+        assert!(astronomical[0].windows.is_empty());
+        let nautical = observing_windows(vega, date, date, 52.0, 9.0, 20.0, &Darkness::NAUTICAL);
+        assert_eq!(nautical[0].windows.len(), 1);
+    }
+
+    #[cfg(feature = "moon")]
+    #[test]
+    fn test_moon_constraint() {
+        // 2013-01-27 UTC, full moon close to Orion
+        let date = 1_359_244_800_000;
+        let darkness = Darkness::ASTRONOMICAL.with_moon(MoonConstraint::BELOW_HORIZON);
+        let moonless = observing_windows(M42, date, date, 48.0, 9.0, 20.0, &darkness);
+        assert!(moonless[0].windows.is_empty(), "{moonless:?}");
+        let far = Darkness::ASTRONOMICAL.with_moon(MoonConstraint {
+            max_illumination: 1.0,
+            min_separation_deg: 10.0,
+        });
+        let windows = &observing_windows(M42, date, date, 48.0, 9.0, 20.0, &far)[0].windows;
+        assert!(!windows.is_empty());
+        for window in windows {
+            let (_, altitude, _) = moon_horizontal(window.peak_in_ms, 48.0, 9.0);
+            assert!(altitude > 0.0);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Parallel versions of the batch calculations using [rayon](https://docs.rs/rayon), e.g.
+//! for simulations over large grids of sites.
+//!
+//! The results are identical to the sequential versions, the work is split into chunks
+//! distributed over the global thread pool of rayon.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::parallel::par_pos_many;
+//!
+//! // a grid of 100 x 100 sites
+//! let sites: Vec<(f64, f64)> = (0..10_000)
+//!     .map(|i| (40.0 + f64::from(i / 100) * 0.1, f64::from(i % 100) * 0.1))
+//!     .collect();
+//! let positions = par_pos_many(1_362_484_800_000, &sites);
+//! assert_eq!(positions.len(), sites.len());
+//! ```
+
+use rayon::prelude::*;
+
+use crate::cursor::{events, Event};
+use crate::{pos_for_times, pos_many, Position};
+
+/// Number of sites or times computed by one task.
+const CHUNK_SIZE: usize = 1_024;
+
+/// Calculates the sun positions for many sites at the same instant in parallel, see
+/// [`pos_many`].
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `sites`     - [latitude](https://en.wikipedia.org/wiki/Latitude) and
+///   [longitude](https://en.wikipedia.org/wiki/Longitude) of the sites in degrees.
+#[must_use]
+pub fn par_pos_many(unixtime_in_ms: i64, sites: &[(f64, f64)]) -> Vec<Position> {
+    sites
+        .par_chunks(CHUNK_SIZE)
+        .flat_map_iter(|chunk| pos_many(unixtime_in_ms, chunk))
+        .collect()
+}
+
+/// Calculates the sun positions at many times for one site in parallel, see
+/// [`pos_for_times`].
+///
+/// * `times`     - [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Panics
+///
+/// Panics if `times` and `positions` differ in length.
+pub fn par_pos_for_times(times: &[i64], lat: f64, lon: f64, positions: &mut [Position]) {
+    assert_eq!(
+        times.len(),
+        positions.len(),
+        "one position is needed per time"
+    );
+    times
+        .par_chunks(CHUNK_SIZE)
+        .zip(positions.par_chunks_mut(CHUNK_SIZE))
+        .for_each(|(times, positions)| pos_for_times(times, lat, lon, positions));
+}
+
+/// Collects the sun events from `start_in_ms` to `end_in_ms` for many sites in parallel,
+/// see [`events`].
+///
+/// The events of each site are in chronological order, the sites in the given order.
+///
+/// * `start_in_ms` - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the start in milliseconds.
+/// * `end_in_ms`   - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the end in milliseconds.
+/// * `sites`       - [latitude](https://en.wikipedia.org/wiki/Latitude) and
+///   [longitude](https://en.wikipedia.org/wiki/Longitude) of the sites in degrees.
+/// * `height`      - Observer height in meters above the horizon
+#[must_use]
+pub fn par_events(
+    start_in_ms: i64,
+    end_in_ms: i64,
+    sites: &[(f64, f64)],
+    height: f64,
+) -> Vec<Vec<Event>> {
+    sites
+        .par_iter()
+        .map(|&(lat, lon)| events(start_in_ms, end_in_ms, lat, lon, height).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::SunPhase;
+
+    fn assert_identical(parallel: &[Position], sequential: &[Position]) {
+        assert_eq!(parallel.len(), sequential.len());
+        for (a, b) in parallel.iter().zip(sequential) {
+            assert_eq!(a.azimuth.to_bits(), b.azimuth.to_bits());
+            assert_eq!(a.altitude.to_bits(), b.altitude.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_par_pos() {
+        let date = 1_362_441_600_000;
+        let sites: Vec<(f64, f64)> = (0..5_000)
+            .map(|i| (f64::from(i % 180) - 89.5, f64::from(i % 360) - 179.5))
+            .collect();
+        assert_identical(&par_pos_many(date, &sites), &pos_many(date, &sites));
+
+        let times: Vec<i64> = (0..5_000).map(|i| date + i * 60 * 1_000).collect();
+        let empty = Position {
+            azimuth: 0.0,
+            altitude: 0.0,
+        };
+        let mut parallel = vec![empty; times.len()];
+        let mut sequential = vec![empty; times.len()];
+        par_pos_for_times(&times, 48.0, 9.0, &mut parallel);
+        pos_for_times(&times, 48.0, 9.0, &mut sequential);
+        assert_identical(&parallel, &sequential);
+    }
+
+    #[test]
+    fn test_par_events() {
+        let date = 1_362_441_600_000;
+        let end = date + 2 * 24 * 60 * 60 * 1_000;
+        let sites = [(48.0, 9.0), (-33.9, 18.4), (78.2, 15.6)];
+        let all = par_events(date, end, &sites, 0.0);
+        assert_eq!(all.len(), sites.len());
+        for (site_events, &(lat, lon)) in all.iter().zip(&sites) {
+            assert_eq!(
+                *site_events,
+                events(date, end, lat, lon, 0.0).collect::<Vec<_>>()
+            );
+        }
+        let sunrises = all[0]
+            .iter()
+            .filter(|event| event.phase == SunPhase::Sunrise)
+            .count();
+        assert_eq!(sunrises, 2);
+    }
+}
@@ -0,0 +1,141 @@
+//! Time windows of twilight phenomena like the
+//! [Belt of Venus](https://en.wikipedia.org/wiki/Belt_of_Venus) and
+//! [alpenglow](https://en.wikipedia.org/wiki/Alpenglow).
+//!
+//! Both phenomena depend on the depression of the sun below the horizon.
+//! The windows are estimates based on typical depression ranges, actual
+//! visibility depends on the weather.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::phenomena::{twilight_windows, TwilightPhenomenon};
+//!
+//! let windows = twilight_windows(1_362_441_600_000, TwilightPhenomenon::BeltOfVenus, 48.0, 9.0, 0.0);
+//! let evening = windows.evening.unwrap();
+//! assert!(evening.start_in_ms < evening.end_in_ms);
+//! ```
+
+use std::f64::consts::PI;
+
+use crate::{pos, try_time_at_phase, SunPhase};
+
+/// Twilight phenomena with a characteristic range of sun altitudes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwilightPhenomenon {
+    /// The pinkish anti-twilight arch above the earth's shadow, opposite of the sun.
+    BeltOfVenus,
+    /// The reddish glow on mountain peaks opposite of the sun.
+    Alpenglow,
+}
+
+impl TwilightPhenomenon {
+    /// Range of sun altitudes in degrees `(highest, lowest)` in which the phenomenon is visible.
+    #[must_use]
+    pub const fn altitude_range_deg(self) -> (f64, f64) {
+        match self {
+            TwilightPhenomenon::BeltOfVenus => (-0.833, -6.0),
+            TwilightPhenomenon::Alpenglow => (2.0, -4.0),
+        }
+    }
+}
+
+/// A time window of a twilight phenomenon.
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the start in milliseconds.
+    pub start_in_ms: i64,
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the end in milliseconds.
+    pub end_in_ms: i64,
+    /// Azimuth in radians opposite of the sun in the middle of the window,
+    /// i.e. where to look.
+    pub antisolar_azimuth: f64,
+}
+
+/// Morning and evening windows of a twilight phenomenon, `None` if the sun doesn't pass
+/// through the altitude range of the phenomenon, e.g. in the polar night.
+#[derive(Debug, Clone, Copy)]
+pub struct Windows {
+    pub morning: Option<Window>,
+    pub evening: Option<Window>,
+}
+
+fn window(start_in_ms: Option<i64>, end_in_ms: Option<i64>, lat: f64, lon: f64) -> Option<Window> {
+    let (start_in_ms, end_in_ms) = (start_in_ms?, end_in_ms?);
+    let middle = start_in_ms + (end_in_ms - start_in_ms) / 2;
+    let azimuth = pos(middle, lat, lon).azimuth;
+    Some(Window {
+        start_in_ms,
+        end_in_ms,
+        antisolar_azimuth: (azimuth + PI).rem_euclid(2.0 * PI),
+    })
+}
+
+/// Estimates the morning and evening windows of a twilight phenomenon for the given date.
+///
+/// * `unixtime`   - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `phenomenon` - the phenomenon to calculate the windows for.
+/// * `lat`        - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`        - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `height`     - Observer height in meters above the horizon
+#[must_use]
+pub fn twilight_windows(
+    unixtime_in_ms: i64,
+    phenomenon: TwilightPhenomenon,
+    lat: f64,
+    lon: f64,
+    height: f64,
+) -> Windows {
+    let (highest, lowest) = phenomenon.altitude_range_deg();
+    let time = |angle, rise| {
+        try_time_at_phase(
+            unixtime_in_ms,
+            SunPhase::custom(angle, rise),
+            lat,
+            lon,
+            height,
+        )
+        .time()
+    };
+    Windows {
+        morning: window(time(lowest, true), time(highest, true), lat, lon),
+        evening: window(time(highest, false), time(lowest, false), lat, lon),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_twilight_windows() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let sunset = crate::time_at_phase(date, SunPhase::Sunset, 50.5, 30.5, 0.0);
+        let venus = twilight_windows(date, TwilightPhenomenon::BeltOfVenus, 50.5, 30.5, 0.0);
+        let (morning, evening) = (venus.morning.unwrap(), venus.evening.unwrap());
+        assert_eq!(evening.start_in_ms, sunset);
+        assert!(morning.start_in_ms < morning.end_in_ms);
+        assert!(evening.start_in_ms < evening.end_in_ms);
+        // look east in the evening and west in the morning
+        assert!((evening.antisolar_azimuth.to_degrees() - 80.0).abs() < 15.0);
+        assert!((morning.antisolar_azimuth.to_degrees() - 280.0).abs() < 15.0);
+
+        let alpenglow = twilight_windows(date, TwilightPhenomenon::Alpenglow, 50.5, 30.5, 0.0)
+            .evening
+            .unwrap();
+        assert!(alpenglow.start_in_ms < sunset);
+        assert!(alpenglow.end_in_ms > sunset);
+
+        // polar night in Longyearbyen on 2013-12-21
+        let polar = twilight_windows(
+            1_387_584_000_000,
+            TwilightPhenomenon::Alpenglow,
+            78.2,
+            15.6,
+            0.0,
+        );
+        assert!(polar.morning.is_none() && polar.evening.is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! Simplified ephemerides of the bright planets, sufficient for rise and set
+//! times and the elongation from the sun.
+//!
+//! The positions are derived from the Keplerian elements of the
+//! [JPL approximation](https://ssd.jpl.nasa.gov/planets/approx_pos.html)
+//! valid from 1800 to 2050 with an accuracy of a few arc minutes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::planets::{elongation, Planet};
+//!
+//! // 2013-11-01, greatest eastern elongation of Venus
+//! let elongation = elongation(Planet::Venus, 1_383_264_000_000).to_degrees();
+//! assert!((elongation - 47.1).abs() < 0.5);
+//! ```
+
+use std::f64::consts::PI;
+
+use crate::search::horizon_crossings;
+use crate::{altitude, azimuth, sidereal_time, to_days, Position};
+
+const DAYS_PER_CENTURY: f64 = 36_525.0;
+const OBLIQUITY_J2000: f64 = 23.439_28 * PI / 180.0;
+/// Altitude of a point-like body at rise and set, i.e. the standard refraction.
+const PLANET_RISE_ALTITUDE: f64 = -0.566_7 * PI / 180.0;
+
+/// Planets with simplified ephemerides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Planet {
+    Mercury,
+    Venus,
+    Mars,
+    Jupiter,
+}
+
+/// Keplerian elements and their rates per century:
+/// semi-major axis (au), eccentricity, inclination, mean longitude,
+/// longitude of perihelion, longitude of the ascending node (degrees).
+type Elements = ([f64; 6], [f64; 6]);
+
+const EARTH: Elements = (
+    [
+        1.000_002_61,
+        0.016_711_23,
+        -0.000_015_31,
+        100.464_571_66,
+        102.937_681_93,
+        0.0,
+    ],
+    [
+        0.000_005_62,
+        -0.000_043_92,
+        -0.012_946_68,
+        35_999.372_449_81,
+        0.323_273_64,
+        0.0,
+    ],
+);
+
+impl Planet {
+    const fn elements(self) -> Elements {
+        match self {
+            Planet::Mercury => (
+                [
+                    0.387_099_27,
+                    0.205_635_93,
+                    7.004_979_02,
+                    252.250_323_5,
+                    77.457_796_28,
+                    48.330_765_93,
+                ],
+                [
+                    0.000_000_37,
+                    0.000_019_06,
+                    -0.005_947_49,
+                    149_472.674_111_75,
+                    0.160_476_89,
+                    -0.125_340_81,
+                ],
+            ),
+            Planet::Venus => (
+                [
+                    0.723_335_66,
+                    0.006_776_72,
+                    3.394_676_05,
+                    181.979_099_5,
+                    131.602_467_18,
+                    76.679_842_55,
+                ],
+                [
+                    0.000_003_9,
+                    -0.000_041_07,
+                    -0.000_788_9,
+                    58_517.815_387_29,
+                    0.002_683_29,
+                    -0.277_694_18,
+                ],
+            ),
+            Planet::Mars => (
+                [
+                    1.523_710_34,
+                    0.093_394_1,
+                    1.849_691_42,
+                    -4.553_432_05,
+                    -23.943_629_59,
+                    49.559_538_91,
+                ],
+                [
+                    0.000_018_47,
+                    0.000_078_82,
+                    -0.008_131_31,
+                    19_140.302_684_99,
+                    0.444_410_88,
+                    -0.292_573_43,
+                ],
+            ),
+            Planet::Jupiter => (
+                [
+                    5.202_887,
+                    0.048_386_24,
+                    1.304_396_95,
+                    34.396_440_51,
+                    14.728_479_83,
+                    100.473_909_09,
+                ],
+                [
+                    -0.000_116_07,
+                    -0.000_132_53,
+                    -0.001_837_14,
+                    3_034.746_127_75,
+                    0.212_526_68,
+                    0.204_691_06,
+                ],
+            ),
+        }
+    }
+}
+
+/// Heliocentric ecliptic coordinates (au) of a body with the given elements.
+fn heliocentric(elements: Elements, centuries: f64) -> [f64; 3] {
+    let (base, rate) = elements;
+    let value = |i: usize| base[i] + rate[i] * centuries;
+    let (a, e) = (value(0), value(1));
+    let inclination = value(2).to_radians();
+    let mean_longitude = value(3);
+    let perihelion = value(4);
+    let node = value(5).to_radians();
+    let argument = perihelion.to_radians() - node;
+    let mean_anomaly =
+        ((mean_longitude - perihelion + 180.0).rem_euclid(360.0) - 180.0).to_radians();
+
+    // solve Kepler's equation
+    let mut eccentric_anomaly = mean_anomaly + e * mean_anomaly.sin();
+    for _ in 0..10 {
+        eccentric_anomaly -= (eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly)
+            / (1.0 - e * eccentric_anomaly.cos());
+    }
+    let x_orbit = a * (eccentric_anomaly.cos() - e);
+    let y_orbit = a * (1.0 - e * e).sqrt() * eccentric_anomaly.sin();
+
+    let (sin_w, cos_w) = argument.sin_cos();
+    let (sin_n, cos_n) = node.sin_cos();
+    let (sin_i, cos_i) = inclination.sin_cos();
+    [
+        (cos_w * cos_n - sin_w * sin_n * cos_i) * x_orbit
+            + (-sin_w * cos_n - cos_w * sin_n * cos_i) * y_orbit,
+        (cos_w * sin_n + sin_w * cos_n * cos_i) * x_orbit
+            + (-sin_w * sin_n + cos_w * cos_n * cos_i) * y_orbit,
+        sin_w * sin_i * x_orbit + cos_w * sin_i * y_orbit,
+    ]
+}
+
+/// Geocentric ecliptic vectors of the planet and the sun.
+pub(crate) fn geocentric(planet: Planet, unixtime_in_ms: i64) -> ([f64; 3], [f64; 3]) {
+    #[allow(clippy::cast_precision_loss)]
+    let centuries = to_days(unixtime_in_ms as f64) / DAYS_PER_CENTURY;
+    let earth = heliocentric(EARTH, centuries);
+    let body = heliocentric(planet.elements(), centuries);
+    (
+        [body[0] - earth[0], body[1] - earth[1], body[2] - earth[2]],
+        [-earth[0], -earth[1], -earth[2]],
+    )
+}
+
+/// Geocentric right ascension, declination (radians) and distance (au) of a planet.
+fn equatorial(planet: Planet, unixtime_in_ms: i64) -> (f64, f64, f64) {
+    let ([x, y, z], _) = geocentric(planet, unixtime_in_ms);
+    let (sin_e, cos_e) = OBLIQUITY_J2000.sin_cos();
+    let y_eq = y * cos_e - z * sin_e;
+    let z_eq = y * sin_e + z * cos_e;
+    let distance = (x * x + y * y + z * z).sqrt();
+    (y_eq.atan2(x), (z_eq / distance).asin(), distance)
+}
+
+/// Calculates the position of a planet like [`pos`](crate::pos) does for the sun.
+#[must_use]
+pub fn planet_pos(planet: Planet, unixtime_in_ms: i64, lat: f64, lon: f64) -> Position {
+    let (right_ascension, declination, _) = equatorial(planet, unixtime_in_ms);
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let hour_angle = sidereal_time(days, -lon.to_radians()) - right_ascension;
+    let latitude_rad = lat.to_radians();
+    Position {
+        azimuth: azimuth(hour_angle, latitude_rad, declination),
+        altitude: altitude(hour_angle, latitude_rad, declination),
+    }
+}
+
+/// Calculates the distance between earth and a planet in astronomical units.
+#[must_use]
+pub fn planet_distance(planet: Planet, unixtime_in_ms: i64) -> f64 {
+    equatorial(planet, unixtime_in_ms).2
+}
+
+/// Calculates the [elongation](https://en.wikipedia.org/wiki/Elongation_(astronomy))
+/// of a planet from the sun in radians.
+///
+/// The sign tells on which side of the sun the planet is: positive values mean east of
+/// the sun (visible in the evening), negative values west of the sun (visible in the morning).
+#[must_use]
+pub fn elongation(planet: Planet, unixtime_in_ms: i64) -> f64 {
+    let (body, sun) = geocentric(planet, unixtime_in_ms);
+    let dot: f64 = body.iter().zip(&sun).map(|(a, b)| a * b).sum();
+    let norm = |v: [f64; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    let angle = (dot / (norm(body) * norm(sun))).clamp(-1.0, 1.0).acos();
+    let longitude_difference = body[1].atan2(body[0]) - sun[1].atan2(sun[0]);
+    if longitude_difference.sin() < 0.0 {
+        -angle
+    } else {
+        angle
+    }
+}
+
+/// Rise and set of a planet.
+#[derive(Debug, Clone, Copy)]
+pub struct PlanetTimes {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the rise in milliseconds,
+    /// `None` if the planet does not rise on that day.
+    pub rise: Option<i64>,
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the set in milliseconds,
+    /// `None` if the planet does not set on that day.
+    pub set: Option<i64>,
+}
+
+/// Calculates rise and set of a planet within the UTC day of `unixtime_in_ms`.
+#[must_use]
+pub fn planet_times(planet: Planet, unixtime_in_ms: i64, lat: f64, lon: f64) -> PlanetTimes {
+    let crossings = horizon_crossings(unixtime_in_ms, |time| {
+        planet_pos(planet, time, lat, lon).altitude - PLANET_RISE_ALTITUDE
+    });
+    PlanetTimes {
+        rise: crossings.rise,
+        set: crossings.set,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_venus_elongation() {
+        // 2013-11-01 greatest eastern elongation (47.1°)
+        let east = elongation(Planet::Venus, 1_383_264_000_000);
+        assert!((east.to_degrees() - 47.1).abs() < 0.5);
+        // 2014-03-22 greatest western elongation (46.6°)
+        let west = elongation(Planet::Venus, 1_395_446_400_000);
+        assert!((west.to_degrees() + 46.6).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_earth_orbit() {
+        // earth is closest to the sun at the beginning of January
+        let january = heliocentric(EARTH, (to_days(1_357_430_400_000.0)) / DAYS_PER_CENTURY);
+        let distance = (january[0].powi(2) + january[1].powi(2) + january[2].powi(2)).sqrt();
+        assert!((distance - 0.983_3).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_venus_sets_after_the_sun_as_evening_star() {
+        // 2013-11-01 UTC in Berlin
+        let date = 1_383_264_000_000;
+        let venus = planet_times(Planet::Venus, date, 52.5, 13.4);
+        let sunset = crate::time_at_phase(date, crate::SunPhase::Sunset, 52.5, 13.4, 0.0);
+        assert!(venus.set.unwrap() > sunset);
+        assert!(planet_distance(Planet::Venus, date) < 1.0);
+    }
+}
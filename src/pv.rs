@@ -0,0 +1,190 @@
+//! Row-to-row shading of fixed photovoltaic arrays, e.g. to choose the distance between
+//! the rows of a ground-mounted system.
+//!
+//! The rows are assumed to be infinitely long and to stand on flat ground, so the
+//! shading only depends on the angle of the sun in the plane perpendicular to the rows.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::pv::{daily_shaded_fraction, shading_windows, RowGeometry};
+//!
+//! let rows = RowGeometry {
+//!     pitch: 5.0,
+//!     collector_width: 2.0,
+//!     tilt_deg: 30.0,
+//!     azimuth_deg: 180.0,
+//! };
+//! let start = 1_387_584_000_000; // 2013-12-21 UTC
+//! for (from, to) in shading_windows(start, start + 24 * 60 * 60 * 1_000, 48.0, 9.0, rows) {
+//!     println!("the rows shade each other between {from} and {to}");
+//! }
+//! let loss = daily_shaded_fraction(start, 48.0, 9.0, rows);
+//! println!("{:.1}% of the direct light is shaded", loss * 100.0);
+//! ```
+
+use crate::{pos, Position, MILLISECONDS_PER_DAY_INT};
+
+const STEP_MS: i64 = 60 * 1_000;
+
+/// Layout of parallel rows of tilted collectors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RowGeometry {
+    /// Distance between the lower edges of two neighbouring rows in meters.
+    pub pitch: f64,
+    /// Width of the collector along its slope in meters.
+    pub collector_width: f64,
+    /// Tilt of the collectors against the ground in degrees.
+    pub tilt_deg: f64,
+    /// Direction the collectors face in degrees clockwise from north.
+    pub azimuth_deg: f64,
+}
+
+impl RowGeometry {
+    /// Ground coverage ratio, the collector width divided by the pitch.
+    #[must_use]
+    pub fn ground_coverage_ratio(&self) -> f64 {
+        self.collector_width / self.pitch
+    }
+
+    /// Cosine of the angle between the sun and the normal of the collectors, negative if
+    /// the sun shines on their back.
+    fn cos_incidence(&self, sun: Position) -> f64 {
+        let tilt = self.tilt_deg.to_radians();
+        tilt.cos() * sun.altitude.sin()
+            + tilt.sin() * sun.altitude.cos() * (sun.azimuth - self.azimuth_deg.to_radians()).cos()
+    }
+}
+
+/// Calculates the share of the collector width of a row that lies in the shadow of the
+/// row in front of it, between 0 and 1.
+///
+/// Returns 0 if the sun is below the horizon or behind the collectors, as no direct
+/// light reaches their front then.
+#[must_use]
+pub fn shaded_fraction(rows: RowGeometry, sun: Position) -> f64 {
+    if sun.altitude <= 0.0 || rows.cos_incidence(sun) <= 0.0 {
+        return 0.0;
+    }
+    let facing = (sun.azimuth - rows.azimuth_deg.to_radians()).cos();
+    if facing <= 0.0 {
+        // the sun shines along the rows or from behind over the top edge
+        return 0.0;
+    }
+    // altitude of the sun projected into the plane perpendicular to the rows
+    let profile = (sun.altitude.tan() / facing).atan();
+    let tilt = rows.tilt_deg.to_radians();
+    let unshaded = profile.sin() / (profile + tilt).sin() / rows.ground_coverage_ratio();
+    (1.0 - unshaded).clamp(0.0, 1.0)
+}
+
+/// Finds the periods between `start_in_ms` and `end_in_ms` in which a row shades the
+/// next one.
+///
+/// The periods are returned as pairs of [unix times](https://en.wikipedia.org/wiki/Unix_time)
+/// in milliseconds with a resolution of one minute.
+#[must_use]
+pub fn shading_windows(
+    start_in_ms: i64,
+    end_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    rows: RowGeometry,
+) -> Vec<(i64, i64)> {
+    let mut windows = Vec::new();
+    let mut open: Option<i64> = None;
+    let mut time = start_in_ms;
+    while time <= end_in_ms {
+        let shaded = shaded_fraction(rows, pos(time, lat, lon)) > 0.0;
+        match (shaded, open) {
+            (true, None) => open = Some(time),
+            (false, Some(from)) => {
+                windows.push((from, time));
+                open = None;
+            }
+            _ => {}
+        }
+        time += STEP_MS;
+    }
+    if let Some(from) = open {
+        windows.push((from, end_in_ms));
+    }
+    windows
+}
+
+/// Calculates the share of the direct sunlight on a row that is lost to the shadow of
+/// the row in front of it over the UTC day of `unixtime_in_ms`.
+///
+/// Every minute is weighted with the direct light falling on the collector, i.e. the
+/// cosine of the angle of incidence. Diffuse light and the attenuation by the
+/// atmosphere are ignored.
+#[must_use]
+pub fn daily_shaded_fraction(unixtime_in_ms: i64, lat: f64, lon: f64, rows: RowGeometry) -> f64 {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let (mut shaded, mut total) = (0.0, 0.0);
+    for step in 0..MILLISECONDS_PER_DAY_INT / STEP_MS {
+        let sun = pos(start + step * STEP_MS, lat, lon);
+        let light = rows.cos_incidence(sun);
+        if sun.altitude > 0.0 && light > 0.0 {
+            shaded += light * shaded_fraction(rows, sun);
+            total += light;
+        }
+    }
+    if total > 0.0 {
+        shaded / total
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    const ROWS: RowGeometry = RowGeometry {
+        pitch: 5.0,
+        collector_width: 2.0,
+        tilt_deg: 30.0,
+        azimuth_deg: 180.0,
+    };
+
+    #[test]
+    fn test_shaded_fraction() {
+        let sun = |altitude_deg: f64| Position {
+            azimuth: PI,
+            altitude: altitude_deg.to_radians(),
+        };
+        assert!(shaded_fraction(ROWS, sun(60.0)).abs() < 1e-12);
+        // the top edge is 1 m high and 4.27 m in front of the next row: no shadow
+        // above an altitude of 13.2°
+        let limit = (1.0 / (5.0 - 3_f64.sqrt())).atan().to_degrees();
+        assert!(shaded_fraction(ROWS, sun(limit + 0.1)).abs() < 1e-12);
+        assert!(shaded_fraction(ROWS, sun(limit - 0.1)) > 0.0);
+        assert!((shaded_fraction(ROWS, sun(0.01)) - 1.0).abs() < 0.01);
+        // from behind or below the horizon
+        let behind = Position {
+            azimuth: 0.0,
+            altitude: 0.2,
+        };
+        assert!(shaded_fraction(ROWS, behind).abs() < 1e-12);
+        assert!(shaded_fraction(ROWS, sun(-1.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_days() {
+        let winter = 1_387_584_000_000; // 2013-12-21 UTC
+        let summer = 1_371_772_800_000; // 2013-06-21 UTC
+        let windows = shading_windows(winter, winter + MILLISECONDS_PER_DAY_INT, 48.0, 9.0, ROWS);
+        // in the morning and in the afternoon, but not at noon
+        assert_eq!(windows.len(), 2);
+        let noon = crate::solar_noon(winter + MILLISECONDS_PER_DAY_INT / 2, 9.0);
+        assert!(windows[0].1 < noon && noon < windows[1].0);
+        let winter_loss = daily_shaded_fraction(winter, 48.0, 9.0, ROWS);
+        let summer_loss = daily_shaded_fraction(summer, 48.0, 9.0, ROWS);
+        assert!(winter_loss > summer_loss);
+        assert!(winter_loss > 0.0 && winter_loss < 0.5);
+    }
+}
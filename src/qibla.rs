@@ -0,0 +1,136 @@
+//! Days on which the sun stands directly above the [Kaaba](https://en.wikipedia.org/wiki/Kaaba)
+//! in Mecca or above its antipode.
+//!
+//! At these instants the sun stands in the direction of the
+//! [qibla](https://en.wikipedia.org/wiki/Qibla) everywhere it is visible, so the shadow of
+//! a vertical stick points away from Mecca. At the antipode the sun stands opposite of
+//! Mecca, so the shadow points towards it.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::qibla::qibla_days;
+//!
+//! for day in qibla_days(2024, 0.5) {
+//!     println!("sun over the antipode: {}, at {}", day.antipode, day.unixtime_in_ms);
+//! }
+//! ```
+
+use crate::calendar::days_from_civil;
+use crate::coords::angular_separation;
+use crate::terminator::{subsolar, GeoPoint};
+use crate::{local_noon, solar_noon, MILLISECONDS_PER_DAY_INT};
+
+/// Position of the Kaaba in Mecca.
+pub const KAABA: GeoPoint = GeoPoint {
+    lat: 21.4225,
+    lon: 39.8262,
+};
+
+/// Closest approach of the sun to the zenith of the Kaaba or its antipode on a day, see
+/// [`qibla_days`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QiblaDay {
+    /// [Unix time](https://en.wikipedia.org/wiki/Unix_time) of the transit of the sun in
+    /// milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Whether the sun stands above the antipode of the Kaaba instead of the Kaaba.
+    pub antipode: bool,
+    /// Angle between the sun and the zenith of the Kaaba or its antipode in degrees.
+    pub distance_deg: f64,
+}
+
+/// Calculates the direction of the qibla at a place in degrees clockwise from north,
+/// along the great circle to the Kaaba.
+///
+/// * `lat` - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon` - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub fn qibla_bearing(lat: f64, lon: f64) -> f64 {
+    let (lat, kaaba_lat) = (lat.to_radians(), KAABA.lat.to_radians());
+    let delta_lon = (KAABA.lon - lon).to_radians();
+    let y = delta_lon.sin();
+    let x = lat.cos() * kaaba_lat.tan() - lat.sin() * delta_lon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Finds the days of `year` on which the sun passes within `tolerance_deg` of the zenith
+/// of the Kaaba or its antipode.
+///
+/// Each day is reported with the transit of the sun through the meridian of the Kaaba or
+/// its antipode, the instant of the closest approach. The days are returned in order and
+/// form two windows for the Kaaba, in late May and mid July, and two for the antipode,
+/// in late November and mid January.
+#[must_use]
+pub fn qibla_days(year: i32, tolerance_deg: f64) -> Vec<QiblaDay> {
+    let antipode_lon = KAABA.lon - 180.0;
+    (days_from_civil(year, 1, 1)..days_from_civil(year + 1, 1, 1))
+        .flat_map(|day| {
+            let day_start = day * MILLISECONDS_PER_DAY_INT;
+            [(KAABA.lon, false), (antipode_lon, true)].map(|(lon, antipode)| {
+                let unixtime_in_ms = solar_noon(local_noon(day_start, lon), lon);
+                let (sun_lat, sun_lon) = subsolar(unixtime_in_ms);
+                let (lat, lon) = if antipode {
+                    (-KAABA.lat.to_radians(), antipode_lon.to_radians())
+                } else {
+                    (KAABA.lat.to_radians(), KAABA.lon.to_radians())
+                };
+                QiblaDay {
+                    unixtime_in_ms,
+                    antipode,
+                    distance_deg: angular_separation(sun_lon, sun_lat, lon, lat).to_degrees(),
+                }
+            })
+        })
+        .filter(|day| day.distance_deg <= tolerance_deg)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::calendar::civil_from_days;
+
+    #[test]
+    fn test_qibla_bearing() {
+        // London looks towards south-east, Jakarta towards north-west
+        assert!((qibla_bearing(51.5, -0.13) - 119.0).abs() < 0.5);
+        assert!((qibla_bearing(-6.2, 106.8) - 295.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_qibla_days() {
+        let days = qibla_days(2024, 0.5);
+        let best = |antipode: bool, month: u32| {
+            let day = days
+                .iter()
+                .filter(|day| day.antipode == antipode)
+                .filter(|day| {
+                    civil_from_days(day.unixtime_in_ms / MILLISECONDS_PER_DAY_INT).1 == month
+                })
+                .min_by(|a, b| a.distance_deg.total_cmp(&b.distance_deg))
+                .unwrap();
+            let minutes = day.unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT) / 60_000;
+            let (_, _, date) = civil_from_days(day.unixtime_in_ms / MILLISECONDS_PER_DAY_INT);
+            (date, minutes)
+        };
+        // published as May 27/28 at 09:18 UTC and July 15/16 at 09:27 UTC
+        let (may, may_minutes) = best(false, 5);
+        assert!((27..=28).contains(&may), "{may}");
+        assert!((may_minutes - (9 * 60 + 18)).abs() <= 3, "{may_minutes}");
+        let (july, july_minutes) = best(false, 7);
+        assert!((15..=16).contains(&july), "{july}");
+        assert!((july_minutes - (9 * 60 + 27)).abs() <= 3, "{july_minutes}");
+        // and above the antipode on November 28 and January 12/13, give or take a day in
+        // this model, around 21:10 to 21:30 UTC
+        let (november, _) = best(true, 11);
+        assert!((27..=29).contains(&november), "{november}");
+        let (january, _) = best(true, 1);
+        assert!((12..=14).contains(&january), "{january}");
+        assert!(days.iter().all(|day| {
+            let month = civil_from_days(day.unixtime_in_ms / MILLISECONDS_PER_DAY_INT).1;
+            matches!((day.antipode, month), (false, 5 | 7) | (true, 1 | 11))
+        }));
+    }
+}
@@ -0,0 +1,160 @@
+//! Solar angles for every pixel of a georeferenced raster, e.g. for the
+//! illumination correction of satellite or aerial imagery.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::raster::{solar_angles, GeoTransform};
+//!
+//! // 0.01° pixels starting at 48°N 9°E
+//! let transform = GeoTransform([9.0, 0.01, 0.0, 48.0, 0.0, -0.01]);
+//! let (width, height) = (100, 50);
+//! let mut zenith = vec![0.0; width * height];
+//! let mut azimuth = vec![0.0; width * height];
+//! solar_angles(&transform, width, height, 1_362_484_800_000, &mut zenith, &mut azimuth);
+//! assert!(zenith.iter().all(|z| *z > 40.0 && *z < 60.0));
+//! ```
+
+use std::f64::consts::PI;
+
+use crate::{
+    declination, ecliptic_longitude, right_ascension, sidereal_time, solar_mean_anomaly, to_days,
+    trig,
+};
+
+/// Affine transform from pixel to geographic coordinates in the order used by GDAL:
+/// `[origin_lon, pixel_width, row_rotation, origin_lat, column_rotation, pixel_height]`.
+///
+/// The coordinates of the pixel corner `(column, row)` are
+/// `lon = t[0] + column * t[1] + row * t[2]` and `lat = t[3] + column * t[4] + row * t[5]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoTransform(pub [f64; 6]);
+
+impl GeoTransform {
+    /// Returns the longitude and latitude in degrees of the center of a pixel.
+    #[must_use]
+    pub fn pixel_center(&self, column: usize, row: usize) -> (f64, f64) {
+        #[allow(clippy::cast_precision_loss)]
+        let (x, y) = (column as f64 + 0.5, row as f64 + 0.5);
+        let t = &self.0;
+        (t[0] + x * t[1] + y * t[2], t[3] + x * t[4] + y * t[5])
+    }
+
+    const fn is_north_up(&self) -> bool {
+        self.0[2] == 0.0 && self.0[4] == 0.0
+    }
+}
+
+/// Calculates the solar zenith and azimuth angles in degrees for the center of every pixel
+/// of a `width` × `height` raster at the given time.
+///
+/// The results are written row by row into `zenith` and `azimuth`. The azimuth is
+/// measured clockwise from north like [`pos`](crate::pos) does. The time dependent parts
+/// are computed only once and, for north-up rasters, the latitude and longitude dependent
+/// parts once per row and column.
+///
+/// # Panics
+///
+/// Panics if `zenith` or `azimuth` do not hold exactly `width * height` values.
+pub fn solar_angles(
+    transform: &GeoTransform,
+    width: usize,
+    height: usize,
+    unixtime_in_ms: i64,
+    zenith: &mut [f64],
+    azimuth: &mut [f64],
+) {
+    assert_eq!(zenith.len(), width * height, "zenith has the wrong size");
+    assert_eq!(azimuth.len(), width * height, "azimuth has the wrong size");
+
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let ecliptic_longitude = ecliptic_longitude(solar_mean_anomaly(days));
+    let (sin_dec, cos_dec) = declination(ecliptic_longitude, 0.0).sin_cos();
+    let tan_dec = sin_dec / cos_dec;
+    let base_hour_angle = sidereal_time(days, 0.0) - right_ascension(ecliptic_longitude, 0.0);
+
+    let kernel = |sin_lat: f64, cos_lat: f64, sin_h: f64, cos_h: f64| {
+        let altitude = trig::asin(sin_lat * sin_dec + cos_lat * cos_dec * cos_h);
+        let azimuth = sin_h.atan2(cos_h * sin_lat - tan_dec * cos_lat) + PI;
+        ((PI / 2.0 - altitude).to_degrees(), azimuth.to_degrees())
+    };
+
+    if transform.is_north_up() {
+        let columns: Vec<(f64, f64)> = (0..width)
+            .map(|column| {
+                (base_hour_angle + transform.pixel_center(column, 0).0.to_radians()).sin_cos()
+            })
+            .collect();
+        for row in 0..height {
+            let (sin_lat, cos_lat) = transform.pixel_center(0, row).1.to_radians().sin_cos();
+            let offset = row * width;
+            let zenith_row = &mut zenith[offset..offset + width];
+            let azimuth_row = &mut azimuth[offset..offset + width];
+            for ((z, a), (sin_h, cos_h)) in zenith_row
+                .iter_mut()
+                .zip(azimuth_row.iter_mut())
+                .zip(&columns)
+            {
+                (*z, *a) = kernel(sin_lat, cos_lat, *sin_h, *cos_h);
+            }
+        }
+    } else {
+        for row in 0..height {
+            for column in 0..width {
+                let (lon, lat) = transform.pixel_center(column, row);
+                let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+                let (sin_h, cos_h) = (base_hour_angle + lon.to_radians()).sin_cos();
+                let i = row * width + column;
+                (zenith[i], azimuth[i]) = kernel(sin_lat, cos_lat, sin_h, cos_h);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::pos;
+
+    fn check(transform: &GeoTransform) {
+        // the sines of the hour angle and latitude are exact here
+        let tolerance = if cfg!(feature = "fast-math") {
+            crate::fast_math::POSITION_MAX_ERROR_ARCMIN / 60.0
+        } else {
+            1e-9
+        };
+        let (width, height) = (7, 5);
+        let mut zenith = vec![0.0; width * height];
+        let mut azimuth = vec![0.0; width * height];
+        let date = 1_362_484_800_000;
+        solar_angles(transform, width, height, date, &mut zenith, &mut azimuth);
+        for row in 0..height {
+            for column in 0..width {
+                let (lon, lat) = transform.pixel_center(column, row);
+                let expected = pos(date, lat, lon);
+                let i = row * width + column;
+                assert!((zenith[i] - (90.0 - expected.altitude.to_degrees())).abs() < tolerance);
+                assert!((azimuth[i] - expected.azimuth.to_degrees()).abs() < tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn test_north_up_raster_matches_pos() {
+        check(&GeoTransform([9.0, 0.5, 0.0, 48.0, 0.0, -0.5]));
+    }
+
+    #[test]
+    fn test_rotated_raster_matches_pos() {
+        check(&GeoTransform([9.0, 0.5, 0.1, 48.0, 0.1, -0.5]));
+    }
+
+    #[test]
+    #[should_panic(expected = "zenith has the wrong size")]
+    fn test_wrong_buffer_size() {
+        let transform = GeoTransform([0.0, 1.0, 0.0, 0.0, 0.0, -1.0]);
+        solar_angles(&transform, 2, 2, 0, &mut [0.0; 3], &mut [0.0; 4]);
+    }
+}
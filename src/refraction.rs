@@ -0,0 +1,95 @@
+//! Atmospheric refraction close to the horizon, e.g. to correct sextant or camera
+//! measurements with the formulas the crate uses internally.
+//!
+//! The refraction is the angle by which the atmosphere lifts a body: the apparent
+//! altitude is the true altitude plus the refraction. The formulas are scaled to the
+//! temperature and the pressure of the air.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::refraction::{refraction_from_apparent, STANDARD_PRESSURE, STANDARD_TEMPERATURE};
+//!
+//! // the lower limb of the sun measured 5° above the sea horizon
+//! let apparent = 5_f64.to_radians();
+//! let refraction = refraction_from_apparent(apparent, STANDARD_TEMPERATURE, STANDARD_PRESSURE);
+//! println!("true altitude: {:.3}°", (apparent - refraction).to_degrees());
+//! ```
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+
+/// Standard air temperature in degrees Celsius.
+pub const STANDARD_TEMPERATURE: f64 = 10.0;
+/// Standard air pressure in hPa.
+pub const STANDARD_PRESSURE: f64 = 1_010.0;
+
+/// Below this true altitude in degrees the refraction formulas diverge and are clamped.
+const MIN_ALTITUDE_DEG: f64 = -1.0;
+
+fn correction(temperature: f64, pressure: f64) -> f64 {
+    (pressure / STANDARD_PRESSURE) * (273.0 + STANDARD_TEMPERATURE) / (273.0 + temperature)
+}
+
+/// Calculates the refraction in radians for a true (geometric) altitude in radians
+/// after [Sæmundsson](https://en.wikipedia.org/wiki/Atmospheric_refraction#Calculating_refraction).
+///
+/// * `altitude`    - true altitude in radians.
+/// * `temperature` - air temperature in degrees Celsius.
+/// * `pressure`    - air pressure in hPa.
+#[must_use]
+pub fn refraction_from_true(altitude: f64, temperature: f64, pressure: f64) -> f64 {
+    let h = altitude.to_degrees().max(MIN_ALTITUDE_DEG);
+    let arcmin = 1.02 / (h + 10.3 / (h + 5.11)).to_radians().tan();
+    (arcmin * correction(temperature, pressure) / 60.0).to_radians()
+}
+
+/// Calculates the refraction in radians for an apparent (observed) altitude in radians
+/// after [Bennett](https://en.wikipedia.org/wiki/Atmospheric_refraction#Calculating_refraction),
+/// the inverse of [`refraction_from_true`] to within a few arc seconds.
+///
+/// * `altitude`    - apparent altitude in radians.
+/// * `temperature` - air temperature in degrees Celsius.
+/// * `pressure`    - air pressure in hPa.
+#[must_use]
+pub fn refraction_from_apparent(altitude: f64, temperature: f64, pressure: f64) -> f64 {
+    let h = altitude.to_degrees().max(MIN_ALTITUDE_DEG);
+    let arcmin = 1.0 / (h + 7.31 / (h + 4.4)).to_radians().tan();
+    (arcmin * correction(temperature, pressure) / 60.0).to_radians()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_refraction_from_true() {
+        let horizon = refraction_from_true(0.0, STANDARD_TEMPERATURE, STANDARD_PRESSURE);
+        assert!((horizon.to_degrees() * 60.0 - 29.0).abs() < 1.0);
+        let zenith =
+            refraction_from_true(90_f64.to_radians(), STANDARD_TEMPERATURE, STANDARD_PRESSURE);
+        assert!(zenith.to_degrees().abs() < 0.001);
+        let cold = refraction_from_true(0.0, -20.0, STANDARD_PRESSURE);
+        assert!(cold > horizon);
+    }
+
+    #[test]
+    fn test_refraction_from_apparent() {
+        for altitude_deg in [0.0, 1.0, 5.0, 20.0, 60.0] {
+            let apparent = f64::to_radians(altitude_deg);
+            let refraction =
+                refraction_from_apparent(apparent, STANDARD_TEMPERATURE, STANDARD_PRESSURE);
+            // back to the apparent altitude within a few arc seconds
+            let true_altitude = apparent - refraction;
+            let back = true_altitude
+                + refraction_from_true(true_altitude, STANDARD_TEMPERATURE, STANDARD_PRESSURE);
+            assert!(
+                (back - apparent).to_degrees().abs() * 3_600.0 < 10.0,
+                "{altitude_deg}"
+            );
+        }
+        let horizon = refraction_from_apparent(0.0, STANDARD_TEMPERATURE, STANDARD_PRESSURE);
+        assert!((horizon.to_degrees() * 60.0 - 34.5).abs() < 1.0);
+    }
+}
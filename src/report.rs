@@ -0,0 +1,293 @@
+//! A combined daily report of sun and moon events and summaries over longer periods.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "moon")]
+use crate::moon::{moon_illumination, moon_times};
+use crate::{
+    local_noon, next_phase_within, pos, solar_noon, try_time_at_phase, SunPhase,
+    MILLISECONDS_PER_DAY_INT,
+};
+
+/// Time of day of an event in local mean time, used to compare events of different dates.
+fn local_time_of_day(unixtime_in_ms: i64, lon: f64) -> i64 {
+    (local_noon(unixtime_in_ms, -lon) - MILLISECONDS_PER_DAY_INT / 2)
+        .rem_euclid(MILLISECONDS_PER_DAY_INT)
+}
+
+/// Sun and moon events of a day, see [`daily_report`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DailyReport {
+    /// Sunrise, `None` during polar day or polar night.
+    pub sunrise: Option<i64>,
+    /// Sunset, `None` during polar day or polar night.
+    pub sunset: Option<i64>,
+    /// Transit of the sun through the meridian.
+    pub solar_noon: i64,
+    /// Time between sunrise and sunset in milliseconds.
+    pub day_length_ms: i64,
+    /// Moonrise, `None` if the moon does not rise on that day.
+    #[cfg(feature = "moon")]
+    pub moonrise: Option<i64>,
+    /// Moonset, `None` if the moon does not set on that day.
+    #[cfg(feature = "moon")]
+    pub moonset: Option<i64>,
+    /// Phase of the moon at solar noon, see [`MoonIllumination::phase`](crate::moon::MoonIllumination::phase).
+    #[cfg(feature = "moon")]
+    pub moon_phase: f64,
+    /// Illuminated fraction of the moon at solar noon.
+    #[cfg(feature = "moon")]
+    pub moon_illumination: f64,
+}
+
+pub(crate) fn event(unixtime_in_ms: i64, sun_phase: SunPhase, lat: f64, lon: f64) -> Option<i64> {
+    try_time_at_phase(unixtime_in_ms, sun_phase, lat, lon, 0.0).time()
+}
+
+/// Finds the first occurrence of a phase after `unixtime_in_ms` within the next `max_days` days.
+pub(crate) fn next_event(
+    unixtime_in_ms: i64,
+    sun_phase: SunPhase,
+    lat: f64,
+    lon: f64,
+    max_days: i64,
+) -> Option<i64> {
+    next_phase_within(unixtime_in_ms, sun_phase, lat, lon, 0.0, max_days)
+}
+
+/// Sunrise, sunset, solar noon and day length shared by the different reports.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SunEvents {
+    pub(crate) sunrise: Option<i64>,
+    pub(crate) sunset: Option<i64>,
+    pub(crate) solar_noon: i64,
+    pub(crate) day_length_ms: i64,
+}
+
+pub(crate) fn sun_events(unixtime_in_ms: i64, lat: f64, lon: f64) -> SunEvents {
+    let sunrise = event(unixtime_in_ms, SunPhase::Sunrise, lat, lon);
+    let sunset = event(unixtime_in_ms, SunPhase::Sunset, lat, lon);
+    let solar_noon = solar_noon(unixtime_in_ms, lon);
+    let day_length_ms = match (sunrise, sunset) {
+        (Some(rise), Some(set)) => set - rise,
+        _ if pos(solar_noon, lat, lon).altitude > 0.0 => MILLISECONDS_PER_DAY_INT,
+        _ => 0,
+    };
+    SunEvents {
+        sunrise,
+        sunset,
+        solar_noon,
+        day_length_ms,
+    }
+}
+
+/// Calculates the sun and moon events for the UTC day of a given date and latitude/longitude.
+///
+/// # Examples
+///
+/// ```rust
+/// let report = sun::report::daily_report(1_362_441_600_000, 48.0, 9.0);
+/// assert_eq!(report.sunrise, Some(1_362_463_116_241));
+/// assert!(report.day_length_ms > 11 * 60 * 60 * 1_000);
+/// ```
+#[must_use]
+pub fn daily_report(unixtime_in_ms: i64, lat: f64, lon: f64) -> DailyReport {
+    let SunEvents {
+        sunrise,
+        sunset,
+        solar_noon,
+        day_length_ms,
+    } = sun_events(unixtime_in_ms, lat, lon);
+    #[cfg(feature = "moon")]
+    let moon_times = moon_times(unixtime_in_ms, lat, lon);
+    #[cfg(feature = "moon")]
+    let illumination = moon_illumination(solar_noon);
+    DailyReport {
+        sunrise,
+        sunset,
+        solar_noon,
+        day_length_ms,
+        #[cfg(feature = "moon")]
+        moonrise: moon_times.rise,
+        #[cfg(feature = "moon")]
+        moonset: moon_times.set,
+        #[cfg(feature = "moon")]
+        moon_phase: illumination.phase,
+        #[cfg(feature = "moon")]
+        moon_illumination: illumination.fraction,
+    }
+}
+
+/// Aggregated sun events over a period, see [`summary`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// The earliest and latest events are compared by their local mean time of day, so
+/// they do not depend on time zones or daylight saving time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PeriodSummary {
+    /// Number of days in the period.
+    pub days: u32,
+    /// Mean time between sunrise and sunset in milliseconds.
+    pub mean_day_length_ms: f64,
+    /// Shortest day length in milliseconds.
+    pub min_day_length_ms: i64,
+    /// Longest day length in milliseconds.
+    pub max_day_length_ms: i64,
+    /// Sum of all day lengths in milliseconds.
+    pub total_daylight_ms: i64,
+    /// Number of days on which the sun does not rise, i.e. polar day or polar night.
+    pub days_without_sunrise: u32,
+    /// Number of days on which the sun does not set, i.e. polar day or polar night.
+    pub days_without_sunset: u32,
+    /// Sunrise with the earliest time of day.
+    pub earliest_sunrise: Option<i64>,
+    /// Sunrise with the latest time of day.
+    pub latest_sunrise: Option<i64>,
+    /// Sunset with the earliest time of day.
+    pub earliest_sunset: Option<i64>,
+    /// Sunset with the latest time of day.
+    pub latest_sunset: Option<i64>,
+}
+
+/// Keeps the events with the earliest and the latest time of day.
+fn track_extremes(extremes: &mut (Option<i64>, Option<i64>), event: Option<i64>, lon: f64) {
+    let Some(event) = event else {
+        return;
+    };
+    let time_of_day = local_time_of_day(event, lon);
+    if extremes
+        .0
+        .is_none_or(|earliest| time_of_day < local_time_of_day(earliest, lon))
+    {
+        extremes.0 = Some(event);
+    }
+    if extremes
+        .1
+        .is_none_or(|latest| time_of_day > local_time_of_day(latest, lon))
+    {
+        extremes.1 = Some(event);
+    }
+}
+
+/// Summarizes the sun events of every UTC date from the one of `start_in_ms` up to,
+/// but excluding, `end_in_ms` at a given latitude/longitude.
+///
+/// Returns `None` if the period contains no date.
+///
+/// # Examples
+///
+/// ```rust
+/// // the year 2013 in Stuttgart
+/// let summary = sun::report::summary(1_356_998_400_000, 1_388_534_400_000, 48.8, 9.2).unwrap();
+/// assert_eq!(summary.days, 365);
+/// assert!(summary.max_day_length_ms > 16 * 60 * 60 * 1_000);
+/// ```
+#[must_use]
+pub fn summary(start_in_ms: i64, end_in_ms: i64, lat: f64, lon: f64) -> Option<PeriodSummary> {
+    let mut day_start = start_in_ms - start_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let mut days = 0;
+    let mut min_day_length_ms = i64::MAX;
+    let mut max_day_length_ms = i64::MIN;
+    let mut total_daylight_ms = 0;
+    let mut days_without_sunrise = 0;
+    let mut days_without_sunset = 0;
+    let mut sunrises = (None, None);
+    let mut sunsets = (None, None);
+    while day_start < end_in_ms {
+        let events = sun_events(local_noon(day_start, lon), lat, lon);
+        days += 1;
+        min_day_length_ms = min_day_length_ms.min(events.day_length_ms);
+        max_day_length_ms = max_day_length_ms.max(events.day_length_ms);
+        total_daylight_ms += events.day_length_ms;
+        days_without_sunrise += u32::from(events.sunrise.is_none());
+        days_without_sunset += u32::from(events.sunset.is_none());
+        track_extremes(&mut sunrises, events.sunrise, lon);
+        track_extremes(&mut sunsets, events.sunset, lon);
+        day_start += MILLISECONDS_PER_DAY_INT;
+    }
+    if days == 0 {
+        return None;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mean_day_length_ms = total_daylight_ms as f64 / f64::from(days);
+    Some(PeriodSummary {
+        days,
+        mean_day_length_ms,
+        min_day_length_ms,
+        max_day_length_ms,
+        total_daylight_ms,
+        days_without_sunrise,
+        days_without_sunset,
+        earliest_sunrise: sunrises.0,
+        latest_sunrise: sunrises.1,
+        earliest_sunset: sunsets.0,
+        latest_sunset: sunsets.1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_daily_report() {
+        // 2013-03-05 UTC
+        let report = daily_report(1_362_441_600_000, 50.5, 30.5);
+        assert_eq!(report.sunrise, Some(1_362_458_096_440));
+        assert_eq!(report.sunset, Some(1_362_498_417_875));
+        assert_eq!(report.day_length_ms, 1_362_498_417_875 - 1_362_458_096_440);
+    }
+
+    #[cfg(feature = "moon")]
+    #[test]
+    fn test_daily_report_moon() {
+        let report = daily_report(1_362_441_600_000, 50.5, 30.5);
+        assert!(report.moonset.is_some());
+        assert!(report.moon_illumination > 0.4 && report.moon_illumination < 0.5);
+    }
+
+    #[test]
+    fn test_polar_night_and_day() {
+        // 2013-12-21 UTC in Tromsø
+        let winter = daily_report(1_387_584_000_000, 69.65, 18.96);
+        assert_eq!(winter.sunrise, None);
+        assert_eq!(winter.day_length_ms, 0);
+        // 2013-06-21 UTC
+        let summer = daily_report(1_371_772_800_000, 69.65, 18.96);
+        assert_eq!(summer.sunset, None);
+        assert_eq!(summer.day_length_ms, MILLISECONDS_PER_DAY_INT);
+    }
+
+    #[test]
+    fn test_summary() {
+        // 2013 in Stuttgart
+        let year = summary(1_356_998_400_000, 1_388_534_400_000, 48.8, 9.2).unwrap();
+        assert_eq!(year.days, 365);
+        assert_eq!(year.days_without_sunrise, 0);
+        assert!(year.min_day_length_ms < 8 * 60 * 60 * 1_000 + 30 * 60 * 1_000);
+        // the earliest sunset is in December, but before the solstice
+        let earliest_sunset = year.earliest_sunset.unwrap();
+        assert!(earliest_sunset > 1_386_000_000_000 && earliest_sunset < 1_387_584_000_000);
+        // the latest sunrise is around new year
+        let latest_sunrise = year.latest_sunrise.unwrap();
+        assert!(!(1_358_000_000_000..=1_388_000_000_000).contains(&latest_sunrise));
+        // Tromsø in winter
+        let winter = summary(
+            1_387_584_000_000,
+            1_387_584_000_000 + 7 * MILLISECONDS_PER_DAY_INT,
+            69.65,
+            18.96,
+        )
+        .unwrap();
+        assert_eq!(winter.days_without_sunrise, 7);
+        assert_eq!(winter.total_daylight_ms, 0);
+        assert!(winter.earliest_sunrise.is_none());
+        assert!(summary(1_387_584_000_000, 1_387_584_000_000, 0.0, 0.0).is_none());
+    }
+}
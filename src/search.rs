@@ -0,0 +1,86 @@
+//! Numerical searches shared by the different bodies.
+
+use crate::MILLISECONDS_PER_DAY_INT;
+
+const MILLISECONDS_PER_HOUR: i64 = 60 * 60 * 1_000;
+
+/// Horizon crossings of a body within the UTC day of `unixtime_in_ms`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Crossings {
+    pub(crate) rise: Option<i64>,
+    pub(crate) set: Option<i64>,
+    /// Whether the body stays above the horizon if it neither rises nor sets.
+    #[cfg_attr(not(feature = "moon"), allow(dead_code))]
+    pub(crate) always_up: bool,
+}
+
+/// Finds the times at which `height` (the altitude above the rise/set altitude)
+/// changes its sign within the UTC day of `unixtime_in_ms`.
+///
+/// The day is searched in two hour steps, fitting a parabola through three
+/// heights each, like suncalc does for the moon.
+pub(crate) fn horizon_crossings(unixtime_in_ms: i64, height: impl Fn(i64) -> f64) -> Crossings {
+    crossings_within_day(
+        unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT),
+        height,
+    )
+}
+
+/// Finds the times at which `height` changes its sign within the 24 hours after `start`,
+/// see [`horizon_crossings`].
+pub(crate) fn crossings_within_day(start: i64, height: impl Fn(i64) -> f64) -> Crossings {
+    let height_at = |hour: i64| height(start + hour * MILLISECONDS_PER_HOUR);
+    let mut h0 = height_at(0);
+    let mut rise = None;
+    let mut set = None;
+    let mut ye = 0.0;
+
+    for i in (1..=24).step_by(2) {
+        let hour = f64::from(i32::try_from(i).unwrap_or_default());
+        let h1 = height_at(i);
+        let h2 = height_at(i + 1);
+        let a = (h0 + h2) / 2.0 - h1;
+        let b = (h2 - h0) / 2.0;
+        let xe = -b / (2.0 * a);
+        ye = (a * xe + b) * xe + h1;
+        let d = b * b - 4.0 * a * h1;
+        let mut roots = 0;
+        let (mut x1, mut x2) = (0.0, 0.0);
+        if d >= 0.0 {
+            let dx = d.sqrt() / (a.abs() * 2.0);
+            x1 = xe - dx;
+            x2 = xe + dx;
+            if x1.abs() <= 1.0 {
+                roots += 1;
+            }
+            if x2.abs() <= 1.0 {
+                roots += 1;
+            }
+            if x1 < -1.0 {
+                x1 = x2;
+            }
+        }
+        if roots == 1 {
+            if h0 < 0.0 {
+                rise = Some(hour + x1);
+            } else {
+                set = Some(hour + x1);
+            }
+        } else if roots == 2 {
+            rise = Some(hour + if ye < 0.0 { x2 } else { x1 });
+            set = Some(hour + if ye < 0.0 { x1 } else { x2 });
+        }
+        if rise.is_some() && set.is_some() {
+            break;
+        }
+        h0 = h2;
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    let to_time = |hours: f64| start + (hours * MILLISECONDS_PER_HOUR as f64).round() as i64;
+    Crossings {
+        rise: rise.map(to_time),
+        set: set.map(to_time),
+        always_up: rise.is_none() && set.is_none() && ye > 0.0,
+    }
+}
@@ -0,0 +1,225 @@
+//! An embeddable [axum](https://docs.rs/axum) router serving the sun position, the times
+//! of a day and the upcoming events as JSON.
+//!
+//! | Endpoint    | Query                                   | Response                    |
+//! |-------------|-----------------------------------------|-----------------------------|
+//! | `/position` | `lat`, `lon`, `time`                    | azimuth and altitude        |
+//! | `/times`    | `lat`, `lon`, `time`                    | [`SunTimes`] of the UTC day |
+//! | `/events`   | `lat`, `lon`, `time`, `count` (max 100) | the next events             |
+//!
+//! `time` is a unix time in milliseconds within the range of a JavaScript `Date`, about
+//! ±275,000 years, and defaults to now. Invalid queries are
+//! answered with status 400 and `{"error": "<message>"}`. The `sun-serve` binary serves
+//! the router on its own.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # async fn run() -> std::io::Result<()> {
+//! let app = axum::Router::new().nest("/sun", sun::serve::router());
+//! let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+//! axum::serve(listener, app).await
+//! # }
+//! ```
+
+use axum::extract::rejection::QueryRejection;
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::cursor::EventCursor;
+use crate::observer::{Clock, SystemClock};
+use crate::pos;
+use crate::times::SunTimes;
+
+/// Largest number of events returned by `/events`.
+const MAX_EVENTS: usize = 100;
+/// Largest absolute `time` in milliseconds, the range of a JavaScript `Date`.
+const MAX_TIME_IN_MS: i64 = 8_640_000_000_000_000;
+
+/// Query of the endpoints.
+#[derive(Debug, Deserialize)]
+struct Location {
+    lat: f64,
+    lon: f64,
+    time: Option<i64>,
+    count: Option<usize>,
+}
+
+impl Location {
+    fn validate(self) -> Result<Self, ApiError> {
+        if !(-90.0..=90.0).contains(&self.lat) {
+            return Err(ApiError(format!("lat out of range: {}", self.lat)));
+        }
+        if !(-180.0..=180.0).contains(&self.lon) {
+            return Err(ApiError(format!("lon out of range: {}", self.lon)));
+        }
+        if let Some(time) = self
+            .time
+            .filter(|time| !(-MAX_TIME_IN_MS..=MAX_TIME_IN_MS).contains(time))
+        {
+            return Err(ApiError(format!("time out of range: {time}")));
+        }
+        Ok(self)
+    }
+
+    fn time(&self) -> i64 {
+        self.time.unwrap_or_else(|| SystemClock.now_in_ms())
+    }
+}
+
+/// Response of `/position`.
+#[derive(Debug, Serialize)]
+struct PositionResponse {
+    unixtime_in_ms: i64,
+    /// Azimuth clockwise from north in radians.
+    azimuth: f64,
+    /// Altitude in radians.
+    altitude: f64,
+}
+
+/// An item of the response of `/events`.
+#[derive(Debug, Serialize)]
+struct EventResponse {
+    unixtime_in_ms: i64,
+    phase: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// A bad request.
+#[derive(Debug)]
+struct ApiError(String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: self.0 }),
+        )
+            .into_response()
+    }
+}
+
+fn location(query: Result<Query<Location>, QueryRejection>) -> Result<Location, ApiError> {
+    let Query(location) = query.map_err(|rejection| ApiError(rejection.body_text()))?;
+    location.validate()
+}
+
+#[allow(clippy::unused_async)]
+async fn position(
+    query: Result<Query<Location>, QueryRejection>,
+) -> Result<Json<PositionResponse>, ApiError> {
+    let location = location(query)?;
+    let unixtime_in_ms = location.time();
+    let position = pos(unixtime_in_ms, location.lat, location.lon);
+    Ok(Json(PositionResponse {
+        unixtime_in_ms,
+        azimuth: position.azimuth,
+        altitude: position.altitude,
+    }))
+}
+
+#[allow(clippy::unused_async)]
+async fn times(query: Result<Query<Location>, QueryRejection>) -> Result<Json<SunTimes>, ApiError> {
+    let location = location(query)?;
+    Ok(Json(SunTimes::new(
+        location.time(),
+        location.lat,
+        location.lon,
+    )))
+}
+
+#[allow(clippy::unused_async)]
+async fn events(
+    query: Result<Query<Location>, QueryRejection>,
+) -> Result<Json<Vec<EventResponse>>, ApiError> {
+    let location = location(query)?;
+    let count = location.count.unwrap_or(10).min(MAX_EVENTS);
+    let events = EventCursor::new(location.time(), location.lat, location.lon)
+        .take(count)
+        .map(|event| EventResponse {
+            unixtime_in_ms: event.unixtime_in_ms,
+            phase: event.phase.name(),
+        })
+        .collect();
+    Ok(Json(events))
+}
+
+/// Creates the router with the endpoints `/position`, `/times` and `/events`, see the
+/// [module](self) documentation.
+pub fn router() -> Router {
+    Router::new()
+        .route("/position", get(position))
+        .route("/times", get(times))
+        .route("/events", get(events))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn get(uri: &str) -> (StatusCode, String) {
+        let response = router()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_position_and_times() {
+        let (status, body) = get("/position?lat=50.5&lon=30.5&time=1362441600000").await;
+        assert_eq!(status, StatusCode::OK);
+        let position = pos(1_362_441_600_000, 50.5, 30.5);
+        assert_eq!(
+            body,
+            format!(
+                r#"{{"unixtime_in_ms":1362441600000,"azimuth":{:?},"altitude":{:?}}}"#,
+                position.azimuth, position.altitude
+            )
+        );
+        let (status, body) = get("/times?lat=50.5&lon=30.5&time=1362441600000").await;
+        assert_eq!(status, StatusCode::OK);
+        let times = SunTimes::new(1_362_441_600_000, 50.5, 30.5);
+        assert!(body.contains(&format!(r#""sunrise":{}"#, times.sunrise.unwrap())));
+    }
+
+    #[tokio::test]
+    async fn test_events_and_errors() {
+        let (status, body) = get("/events?lat=50.5&lon=30.5&time=1362441600000&count=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.starts_with(r#"[{"unixtime_in_ms":"#));
+        assert!(body.contains(r#""phase":"night_end"}"#));
+        assert_eq!(body.matches("phase").count(), 2);
+        let (status, body) = get("/position?lat=91&lon=0").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, r#"{"error":"lat out of range: 91"}"#);
+        let (status, body) = get("/times?lat=50&lon=10&time=9223372036854775807").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body,
+            r#"{"error":"time out of range: 9223372036854775807"}"#
+        );
+        let (status, _) = get("/events?lat=50&lon=10&time=-9223372036854775808").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get("/events?lat=50&lon=10&time=-8640000000000000").await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = get("/times?lat=48").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("missing field `lon`"));
+    }
+}
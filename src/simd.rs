@@ -0,0 +1,191 @@
+//! A vectorized kernel of [`pos`](crate::pos) computing the sun positions of [`LANES`]
+//! times per iteration, e.g. for rendering shadows with millions of positions per frame.
+//!
+//! The kernel works on a structure of arrays, times in and azimuths and altitudes out, with
+//! the `f64x4` vectors of [wide](https://docs.rs/wide), which use the SIMD instructions of
+//! the target and fall back to scalar code elsewhere. The trigonometric functions of wide
+//! differ from the ones of the standard library in the last bits, so the positions deviate
+//! by at most [`MAX_ERROR`] from [`pos`](crate::pos) without the `fast-math` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::simd::pos_for_times;
+//!
+//! // every second of 2013-03-05 UTC
+//! let times: Vec<i64> = (0..86_400).map(|s| 1_362_441_600_000 + s * 1_000).collect();
+//! let mut azimuths = vec![0.0; times.len()];
+//! let mut altitudes = vec![0.0; times.len()];
+//! pos_for_times(&times, 48.0, 9.0, &mut azimuths, &mut altitudes);
+//! // the sun is up at noon
+//! assert!(altitudes[43_200] > 0.0);
+//! ```
+
+use core::f64::consts::PI;
+
+use wide::f64x4;
+
+use crate::body::Body;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float as _;
+use crate::to_days;
+
+/// Number of times computed per iteration.
+pub const LANES: usize = 4;
+/// Largest difference of the azimuth and the altitude to [`pos`](crate::pos) in radians.
+pub const MAX_ERROR: f64 = 1e-9;
+
+const TO_RAD: f64 = PI / 180.0;
+
+/// Calculates the azimuths and altitudes of the sun at the given times for one site.
+///
+/// * `times`     - [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+///
+/// # Panics
+///
+/// Panics if `times`, `azimuths` and `altitudes` differ in length.
+pub fn pos_for_times(
+    times: &[i64],
+    lat: f64,
+    lon: f64,
+    azimuths: &mut [f64],
+    altitudes: &mut [f64],
+) {
+    assert!(
+        times.len() == azimuths.len() && times.len() == altitudes.len(),
+        "one azimuth and one altitude is needed per time"
+    );
+    let kernel = Kernel::new(lat, lon);
+    let mut times = times.chunks_exact(LANES);
+    let mut azimuths = azimuths.chunks_exact_mut(LANES);
+    let mut altitudes = altitudes.chunks_exact_mut(LANES);
+    for ((times, azimuths), altitudes) in (&mut times).zip(&mut azimuths).zip(&mut altitudes) {
+        let (azimuth, altitude) = kernel.positions(times);
+        azimuths.copy_from_slice(&azimuth);
+        altitudes.copy_from_slice(&altitude);
+    }
+    // the remainder is padded with its last time
+    let times = times.remainder();
+    if let Some(&last) = times.last() {
+        let mut padded = [last; LANES];
+        padded[..times.len()].copy_from_slice(times);
+        let (azimuth, altitude) = kernel.positions(&padded);
+        azimuths
+            .into_remainder()
+            .copy_from_slice(&azimuth[..times.len()]);
+        altitudes
+            .into_remainder()
+            .copy_from_slice(&altitude[..times.len()]);
+    }
+}
+
+/// The constants of the site and the earth, splatted to all lanes.
+struct Kernel {
+    sin_lat: f64x4,
+    cos_lat: f64x4,
+    longitude_rad: f64x4,
+}
+
+impl Kernel {
+    fn new(lat: f64, lon: f64) -> Self {
+        let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+        Self {
+            sin_lat: f64x4::splat(sin_lat),
+            cos_lat: f64x4::splat(cos_lat),
+            longitude_rad: f64x4::splat(lon.to_radians()),
+        }
+    }
+
+    /// Azimuths and altitudes at [`LANES`] times, following `full_pos_at` of the body.
+    fn positions(&self, times: &[i64]) -> ([f64; LANES], [f64; LANES]) {
+        let earth = Body::EARTH;
+        let elements = earth.elements;
+        #[allow(clippy::cast_precision_loss)]
+        let days = f64x4::new([
+            to_days(times[0] as f64),
+            to_days(times[1] as f64),
+            to_days(times[2] as f64),
+            to_days(times[3] as f64),
+        ]);
+        let to_rad = f64x4::splat(TO_RAD);
+
+        // the ecliptic longitude, see `OrbitalElements::ecliptic_longitude`
+        let mean_anomaly = (f64x4::splat(elements.mean_anomaly_at_epoch)
+            + f64x4::splat(elements.mean_anomaly_rate) * days)
+            * to_rad;
+        let [c1, c2, c3, ..] = elements.equation_of_center;
+        let center = (f64x4::splat(c1) * mean_anomaly.sin()
+            + f64x4::splat(c2) * (f64x4::splat(2.0) * mean_anomaly).sin()
+            + f64x4::splat(c3) * (f64x4::splat(3.0) * mean_anomaly).sin())
+            * to_rad;
+        let perihelion = f64x4::splat(elements.perihelion.to_radians());
+        let longitude = mean_anomaly + center + perihelion + f64x4::splat(PI);
+
+        // the equatorial coordinates, see `OrbitalElements::equatorial`
+        let (sin_obliquity, cos_obliquity) = elements.obliquity.to_radians().sin_cos();
+        let (sin_longitude, cos_longitude) = longitude.sin_cos();
+        let right_ascension = (sin_longitude * f64x4::splat(cos_obliquity)).atan2(cos_longitude);
+        let declination = (f64x4::splat(sin_obliquity) * sin_longitude).asin();
+
+        // the horizontal coordinates, see `azimuth` and `altitude`
+        let rotation = (f64x4::splat(earth.rotation_at_epoch)
+            + f64x4::splat(earth.rotation_rate) * days)
+            * to_rad;
+        let hour_angle = rotation + self.longitude_rad - right_ascension;
+        let (sin_hour_angle, cos_hour_angle) = hour_angle.sin_cos();
+        let (sin_declination, cos_declination) = declination.sin_cos();
+        let azimuth = sin_hour_angle
+            .atan2(cos_hour_angle * self.sin_lat - declination.tan() * self.cos_lat)
+            + f64x4::splat(PI);
+        let altitude = (self.sin_lat * sin_declination
+            + self.cos_lat * cos_declination * cos_hour_angle)
+            .asin();
+        (azimuth.to_array(), altitude.to_array())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::pos;
+
+    // the approximations of `fast-math` are less accurate than the kernel
+    #[cfg(not(feature = "fast-math"))]
+    #[test]
+    fn test_pos_for_times() {
+        // every four hours from 1990 to 2030, with a remainder of one time
+        let times: Vec<i64> = (0..87_661)
+            .map(|step| 631_152_000_000 + step * 4 * 60 * 60 * 1_000)
+            .collect();
+        for (lat, lon) in [(48.0, 9.0), (-89.9, 179.9), (0.0, -179.9), (78.2, 15.6)] {
+            let mut azimuths = vec![0.0; times.len()];
+            let mut altitudes = vec![0.0; times.len()];
+            pos_for_times(&times, lat, lon, &mut azimuths, &mut altitudes);
+            for ((&time, azimuth), altitude) in times.iter().zip(azimuths).zip(altitudes) {
+                let expected = pos(time, lat, lon);
+                assert!(
+                    (azimuth - expected.azimuth).abs() < MAX_ERROR,
+                    "{time} {lat} {lon}"
+                );
+                assert!(
+                    (altitude - expected.altitude).abs() < MAX_ERROR,
+                    "{time} {lat} {lon}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_remainder() {
+        let times = [1_362_441_600_000, 1_362_445_200_000];
+        let (mut azimuths, mut altitudes) = ([0.0; 2], [0.0; 2]);
+        pos_for_times(&times, 48.0, 9.0, &mut azimuths, &mut altitudes);
+        let expected = pos(times[1], 48.0, 9.0);
+        assert!((azimuths[1] - expected.azimuth).abs() < 1e-3);
+        assert!((altitudes[1] - expected.altitude).abs() < 1e-3);
+        pos_for_times(&[], 48.0, 9.0, &mut [], &mut []);
+    }
+}
@@ -0,0 +1,131 @@
+//! An approximate model of the brightness of the night sky at the zenith,
+//! combining twilight and moonlight.
+//!
+//! The moonlight follows Krisciunas & Schaefer (1991), "A model of the
+//! brightness of moonlight", the twilight is interpolated from typical
+//! zenith measurements. The result is given in V magnitudes per square arc
+//! second, larger numbers mean a darker sky; about 21.8 is a natural dark sky.
+//!
+//! # Example
+//!
+//! ```rust
+//! let brightness = sun::sky::zenith_sky_brightness(1_362_441_600_000, 48.0, 9.0);
+//! println!("The sky brightness is {brightness:.1} mag/arcsec²");
+//! ```
+
+use crate::moon::{moon_horizontal, moon_illumination};
+use crate::pos;
+
+/// Brightness of a natural dark sky at the zenith in mag/arcsec².
+pub const DARK_SKY_BRIGHTNESS: f64 = 21.8;
+/// Extinction coefficient in the V band.
+const EXTINCTION: f64 = 0.172;
+
+/// Zenith brightness in mag/arcsec² for sun altitudes in degrees, from the horizon down
+/// to the end of astronomical twilight.
+const TWILIGHT: [(f64, f64); 5] = [
+    (0.0, 9.0),
+    (-6.0, 14.0),
+    (-12.0, 19.0),
+    (-15.0, 20.5),
+    (-18.0, 21.8),
+];
+
+/// Converts a surface brightness in mag/arcsec² into nanoLamberts.
+fn to_nano_lamberts(magnitude: f64) -> f64 {
+    34.08 * (20.723_3 - 0.921_04 * magnitude).exp()
+}
+
+/// Converts a surface brightness in nanoLamberts into mag/arcsec².
+fn to_magnitude(nano_lamberts: f64) -> f64 {
+    (20.723_3 - (nano_lamberts / 34.08).ln()) / 0.921_04
+}
+
+fn airmass(zenith_distance: f64) -> f64 {
+    (1.0 - 0.96 * zenith_distance.sin().powi(2)).powf(-0.5)
+}
+
+/// Zenith brightness of the twilight sky in nanoLamberts, without the dark sky.
+fn twilight_brightness(sun_altitude_deg: f64) -> f64 {
+    let magnitude = if sun_altitude_deg >= TWILIGHT[0].0 {
+        // extrapolate into daylight with the slope of the first segment
+        TWILIGHT[0].1 - (sun_altitude_deg - TWILIGHT[0].0) * 5.0 / 6.0
+    } else if sun_altitude_deg <= TWILIGHT[TWILIGHT.len() - 1].0 {
+        return 0.0;
+    } else {
+        TWILIGHT
+            .windows(2)
+            .find(|w| sun_altitude_deg <= w[0].0 && sun_altitude_deg >= w[1].0)
+            .map_or(DARK_SKY_BRIGHTNESS, |w| {
+                let t = (sun_altitude_deg - w[0].0) / (w[1].0 - w[0].0);
+                w[0].1 + t * (w[1].1 - w[0].1)
+            })
+    };
+    (to_nano_lamberts(magnitude) - to_nano_lamberts(DARK_SKY_BRIGHTNESS)).max(0.0)
+}
+
+/// Zenith brightness of the moonlight in nanoLamberts.
+fn moonlight_brightness(moon_altitude: f64, phase_angle_deg: f64) -> f64 {
+    if moon_altitude <= 0.0 {
+        return 0.0;
+    }
+    let moon_zenith_distance = std::f64::consts::FRAC_PI_2 - moon_altitude;
+    let illuminance =
+        10f64.powf(-0.4 * (3.84 + 0.026 * phase_angle_deg.abs() + 4e-9 * phase_angle_deg.powi(4)));
+    // scattering at the angle between moon and zenith
+    let separation = moon_zenith_distance;
+    let scattering = 10f64.powf(5.36) * (1.06 + separation.cos().powi(2))
+        + 10f64.powf(6.15 - separation.to_degrees() / 40.0);
+    scattering
+        * illuminance
+        * 10f64.powf(-0.4 * EXTINCTION * airmass(moon_zenith_distance))
+        * (1.0 - 10f64.powf(-0.4 * EXTINCTION * airmass(0.0)))
+}
+
+/// Estimates the brightness of the sky at the zenith in mag/arcsec².
+#[must_use]
+pub fn zenith_sky_brightness(unixtime_in_ms: i64, lat: f64, lon: f64) -> f64 {
+    let sun_altitude = pos(unixtime_in_ms, lat, lon).altitude.to_degrees();
+    let (_, moon_altitude, _) = moon_horizontal(unixtime_in_ms, lat, lon);
+    let fraction = moon_illumination(unixtime_in_ms).fraction;
+    let phase_angle = (2.0 * fraction - 1.0).clamp(-1.0, 1.0).acos().to_degrees();
+    to_magnitude(
+        to_nano_lamberts(DARK_SKY_BRIGHTNESS)
+            + twilight_brightness(sun_altitude)
+            + moonlight_brightness(moon_altitude, phase_angle),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_conversion() {
+        let magnitude = to_magnitude(to_nano_lamberts(DARK_SKY_BRIGHTNESS));
+        assert!((magnitude - DARK_SKY_BRIGHTNESS).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_moonlight() {
+        // full moon in the zenith brightens the sky by a few magnitudes
+        let full =
+            to_magnitude(to_nano_lamberts(DARK_SKY_BRIGHTNESS) + moonlight_brightness(1.5, 0.0));
+        assert!(full > 16.0 && full < 19.5);
+        assert!(moonlight_brightness(-0.1, 0.0).abs() < f64::EPSILON);
+        assert!(moonlight_brightness(1.0, 90.0) < moonlight_brightness(1.0, 0.0));
+    }
+
+    #[test]
+    fn test_zenith_sky_brightness() {
+        // 2013-03-11, new moon, midnight in Berlin
+        let dark = zenith_sky_brightness(1_363_042_800_000, 52.5, 13.4);
+        assert!((dark - DARK_SKY_BRIGHTNESS).abs() < 0.1);
+        // 2013-03-11 sunset in Berlin
+        let sunset =
+            crate::time_at_phase(1_363_042_800_000, crate::SunPhase::Sunset, 52.5, 13.4, 0.0);
+        let twilight = zenith_sky_brightness(sunset, 52.5, 13.4);
+        assert!(twilight < 10.0);
+    }
+}
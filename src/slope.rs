@@ -0,0 +1,176 @@
+//! Direct sunlight on inclined terrain, e.g. for avalanche forecasting, vineyards or
+//! habitat models that depend on how long a slope receives the sun.
+//!
+//! A slope shades itself once the sun is behind its plane, even when the sun is above
+//! the horizon. Shadows of the surrounding terrain are not considered, see
+//! [`terrain`](crate::terrain) for those.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::slope::{direct_sun_hours, Slope};
+//!
+//! // a 35° slope facing north in the Alps at the winter solstice
+//! let slope = Slope {
+//!     slope_deg: 35.0,
+//!     aspect_deg: 0.0,
+//! };
+//! let hours = direct_sun_hours(1_387_584_000_000, 47.0, 11.0, slope);
+//! assert_eq!(hours, 0.0);
+//! ```
+
+use std::f64::consts::FRAC_PI_2;
+
+use crate::{pos, Position, MILLISECONDS_PER_DAY_INT};
+
+const STEP_MS: i64 = 60 * 1_000;
+
+/// Orientation of a terrain element.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slope {
+    /// Inclination against the horizontal in degrees.
+    pub slope_deg: f64,
+    /// Direction the slope faces downhill in degrees clockwise from north.
+    pub aspect_deg: f64,
+}
+
+impl Slope {
+    /// A horizontal surface.
+    pub const FLAT: Self = Self {
+        slope_deg: 0.0,
+        aspect_deg: 0.0,
+    };
+
+    /// Calculates the angle of incidence in radians between the sun and the normal of the
+    /// slope. Angles above π/2 mean that the sun is behind the slope.
+    #[must_use]
+    pub fn incidence(&self, sun: Position) -> f64 {
+        let slope = self.slope_deg.to_radians();
+        (slope.cos() * sun.altitude.sin()
+            + slope.sin() * sun.altitude.cos() * (sun.azimuth - self.aspect_deg.to_radians()).cos())
+        .clamp(-1.0, 1.0)
+        .acos()
+    }
+}
+
+/// Angle of incidence of the sun on a slope at a point in time, see [`incidence_series`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IncidenceSample {
+    /// [Unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Angle of incidence in radians.
+    pub incidence: f64,
+    /// Whether the sun is above the horizon and in front of the slope.
+    pub direct: bool,
+}
+
+fn sample(unixtime_in_ms: i64, lat: f64, lon: f64, slope: Slope) -> IncidenceSample {
+    let sun = pos(unixtime_in_ms, lat, lon);
+    let incidence = slope.incidence(sun);
+    IncidenceSample {
+        unixtime_in_ms,
+        incidence,
+        direct: sun.altitude > 0.0 && incidence < FRAC_PI_2,
+    }
+}
+
+/// Calculates the angle of incidence on a slope from `start_in_ms` to `end_in_ms` in
+/// steps of `step_ms` milliseconds.
+///
+/// # Panics
+///
+/// Panics if `step_ms` is not positive.
+#[must_use]
+pub fn incidence_series(
+    start_in_ms: i64,
+    end_in_ms: i64,
+    step_ms: i64,
+    lat: f64,
+    lon: f64,
+    slope: Slope,
+) -> Vec<IncidenceSample> {
+    assert!(step_ms > 0, "the step must be positive");
+    let step = usize::try_from(step_ms).unwrap_or(usize::MAX);
+    (start_in_ms..=end_in_ms)
+        .step_by(step)
+        .map(|time| sample(time, lat, lon, slope))
+        .collect()
+}
+
+/// Calculates the hours of direct sunlight on a slope during the UTC day of
+/// `unixtime_in_ms` with a resolution of one minute.
+#[must_use]
+pub fn direct_sun_hours(unixtime_in_ms: i64, lat: f64, lon: f64, slope: Slope) -> f64 {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let minutes = (0..MILLISECONDS_PER_DAY_INT / STEP_MS)
+        .filter(|step| sample(start + step * STEP_MS + STEP_MS / 2, lat, lon, slope).direct)
+        .count();
+    #[allow(clippy::cast_precision_loss)]
+    let hours = minutes as f64 / 60.0;
+    hours
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+
+    use super::*;
+    use crate::report::daily_report;
+
+    #[test]
+    fn test_incidence() {
+        let south = Slope {
+            slope_deg: 30.0,
+            aspect_deg: 180.0,
+        };
+        let sun = Position {
+            azimuth: 180_f64.to_radians(),
+            altitude: 60_f64.to_radians(),
+        };
+        // the sun stands perpendicular above the slope
+        assert!(south.incidence(sun).abs() < 1e-6);
+        assert!((Slope::FLAT.incidence(sun) - 30_f64.to_radians()).abs() < 1e-9);
+        let north = Slope {
+            slope_deg: 30.0,
+            aspect_deg: 0.0,
+        };
+        assert!((north.incidence(sun) - 60_f64.to_radians()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_direct_sun_hours() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let flat = direct_sun_hours(date, 47.0, 11.0, Slope::FLAT);
+        let day_length =
+            f64::from(i32::try_from(daily_report(date, 47.0, 11.0).day_length_ms / 1_000).unwrap())
+                / 3_600.0;
+        // the report counts from the upper limb with refraction
+        assert!(flat < day_length && day_length - flat < 0.2);
+        let steep_north = Slope {
+            slope_deg: 60.0,
+            aspect_deg: 0.0,
+        };
+        assert_eq!(direct_sun_hours(date, 47.0, 11.0, steep_north), 0.0);
+        let east = Slope {
+            slope_deg: 30.0,
+            aspect_deg: 90.0,
+        };
+        let series = incidence_series(
+            date,
+            date + MILLISECONDS_PER_DAY_INT,
+            60 * 60 * 1_000,
+            47.0,
+            11.0,
+            east,
+        );
+        assert_eq!(series.len(), 25);
+        // an eastern slope gets the morning sun
+        let first = series.iter().find(|sample| sample.direct).unwrap();
+        let last = series.iter().rev().find(|sample| sample.direct).unwrap();
+        let noon = crate::solar_noon(date + MILLISECONDS_PER_DAY_INT / 2, 11.0);
+        assert!(
+            first.unixtime_in_ms < noon && noon - first.unixtime_in_ms > last.unixtime_in_ms - noon
+        );
+    }
+}
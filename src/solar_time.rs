@@ -0,0 +1,118 @@
+//! Differences between the civil time of a time zone and the solar time at a place, e.g.
+//! to explain why the sun culminates at 13:27 rather than at noon.
+//!
+//! The local mean time runs ahead of UTC by four minutes per degree of eastern
+//! longitude. The local apparent time, the time of a sundial, differs from it by the
+//! [equation of time](https://en.wikipedia.org/wiki/Equation_of_time).
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::solar_time::solar_time_offset;
+//!
+//! // Madrid in summer time, UTC+2, on 2024-06-21
+//! let offset = solar_time_offset(1_718_928_000_000, -3.7, 120);
+//! let (hours, minutes) = (offset.solar_noon_minutes / 60, offset.solar_noon_minutes % 60);
+//! println!("solar noon is at {hours}:{minutes:02} here");
+//! assert_eq!(hours, 14);
+//! ```
+
+use crate::{equation_of_time, local_noon, MILLISECONDS_PER_DAY_INT};
+
+const MINUTES_PER_DEGREE: f64 = 4.0;
+
+/// Differences between civil and solar time of a day, see [`solar_time_offset`].
+///
+/// All differences are in minutes and positive if the clock is ahead of the sun.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolarTimeOffset {
+    /// Clock time minus local mean time, caused by the distance from the meridian of the
+    /// time zone and by summer time.
+    pub mean_minutes: f64,
+    /// [Equation of time](https://en.wikipedia.org/wiki/Equation_of_time), local apparent
+    /// time minus local mean time.
+    pub equation_of_time: f64,
+    /// Clock time minus local apparent time, i.e. the time of a sundial.
+    pub apparent_minutes: f64,
+    /// Clock time of the solar noon in minutes after midnight, rounded to the minute. It
+    /// may differ by a minute from [`solar_noon`](crate::solar_noon), which includes a
+    /// small empirical correction.
+    pub solar_noon_minutes: i64,
+}
+
+/// Calculates the offset of the local mean time from UTC in minutes, four minutes per
+/// degree of longitude.
+///
+/// * `lon` - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub fn mean_solar_offset_minutes(lon: f64) -> f64 {
+    lon * MINUTES_PER_DEGREE
+}
+
+/// Calculates the meridian of a time zone in degrees, e.g. `15.0` for UTC+1.
+///
+/// * `utc_offset_minutes` - offset of the time zone from UTC in minutes.
+#[must_use]
+pub fn zone_meridian(utc_offset_minutes: i32) -> f64 {
+    f64::from(utc_offset_minutes) / MINUTES_PER_DEGREE
+}
+
+/// Calculates the differences between the clock time and the solar time at a place for
+/// the UTC day of `unixtime_in_ms`.
+///
+/// * `unixtime`           - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lon`                - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `utc_offset_minutes` - offset of the clock time from UTC in minutes, including summer
+///   time.
+#[must_use]
+pub fn solar_time_offset(
+    unixtime_in_ms: i64,
+    lon: f64,
+    utc_offset_minutes: i32,
+) -> SolarTimeOffset {
+    let day_start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let mean_minutes = f64::from(utc_offset_minutes) - mean_solar_offset_minutes(lon);
+    let equation_of_time = equation_of_time(local_noon(day_start, lon));
+    let apparent_minutes = mean_minutes - equation_of_time;
+    #[allow(clippy::cast_possible_truncation)]
+    let solar_noon_minutes = (12.0 * 60.0 + apparent_minutes).round() as i64;
+    SolarTimeOffset {
+        mean_minutes,
+        equation_of_time,
+        apparent_minutes,
+        solar_noon_minutes: solar_noon_minutes.rem_euclid(24 * 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_helpers() {
+        assert!((mean_solar_offset_minutes(15.0) - 60.0).abs() < 1e-9);
+        assert!((mean_solar_offset_minutes(-3.7) + 14.8).abs() < 1e-9);
+        assert!((zone_meridian(60) - 15.0).abs() < 1e-9);
+        assert!((zone_meridian(-300) + 75.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_solar_time_offset() {
+        // Madrid, 2024-06-21 in summer time
+        let offset = solar_time_offset(1_718_928_000_000, -3.7, 120);
+        assert!((offset.mean_minutes - 134.8).abs() < 1e-9);
+        assert!(
+            (offset.apparent_minutes - offset.mean_minutes + offset.equation_of_time).abs() < 1e-9
+        );
+        // solar noon at 14:16:35
+        assert_eq!(offset.solar_noon_minutes, 14 * 60 + 17);
+        let noon = crate::solar_noon(1_718_928_000_000 + MILLISECONDS_PER_DAY_INT / 2, -3.7);
+        let clock_noon = (noon + 120 * 60 * 1_000).rem_euclid(MILLISECONDS_PER_DAY_INT) / 60_000;
+        assert!((offset.solar_noon_minutes - clock_noon).abs() <= 2);
+        // Greenwich, 2013-02-11, close to the minimum of the equation of time
+        let offset = solar_time_offset(1_360_584_000_000, 0.0, 0);
+        assert!((offset.equation_of_time + 14.2).abs() < 0.2);
+        assert!((offset.solar_noon_minutes - (12 * 60 + 14)).abs() <= 1);
+    }
+}
@@ -0,0 +1,439 @@
+//! Hour lines of horizontal and vertical (declining) sundials with a polar gnomon,
+//! and layouts of analemmatic sundials with a movable vertical gnomon.
+//!
+//! All angles are in degrees. Hours are local apparent solar time, i.e. 12.0 is the
+//! moment the sun crosses the meridian.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::sundial::{hour_lines, DialPlane};
+//!
+//! // a horizontal dial in Paris
+//! for line in hour_lines(48.85, DialPlane::Horizontal, 60.0) {
+//!     println!("{:>5.2} h: {:.2}°", line.hour, line.angle);
+//! }
+//! ```
+
+use crate::solar_geometry;
+
+const MAX_DECLINATION_DEG: f64 = 23.44;
+const MINUTES_PER_DEGREE: f64 = 4.0;
+
+type Vector = [f64; 3];
+
+fn dot(a: Vector, b: Vector) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Orientation of the dial face.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DialPlane {
+    /// A horizontal dial.
+    Horizontal,
+    /// A vertical dial on a wall facing the given azimuth in degrees clockwise from north,
+    /// e.g. `180.0` for a direct south dial.
+    Vertical {
+        /// Direction the wall faces in degrees clockwise from north.
+        azimuth: f64,
+    },
+}
+
+impl DialPlane {
+    /// Normal of the dial face and the direction to the right of a viewer facing the dial,
+    /// in local coordinates (east, north, up).
+    fn frame(self) -> (Vector, Vector) {
+        match self {
+            DialPlane::Horizontal => ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0]),
+            DialPlane::Vertical { azimuth } => {
+                let (sin, cos) = azimuth.to_radians().sin_cos();
+                ([sin, cos, 0.0], [-cos, sin, 0.0])
+            }
+        }
+    }
+}
+
+/// Geometry of the polar gnomon of a dial.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gnomon {
+    /// Angle between the style (the shadow casting edge) and the dial face in degrees.
+    pub style_height: f64,
+    /// Angle between the substyle (the foot of the style on the dial) and the noon line
+    /// in degrees, positive towards the afternoon side.
+    pub substyle_angle: f64,
+}
+
+/// An hour line on the dial face.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HourLine {
+    /// Local apparent solar time in hours.
+    pub hour: f64,
+    /// Angle between the hour line and the noon line in degrees, positive towards the
+    /// afternoon side.
+    pub angle: f64,
+}
+
+/// Direction of the earth's axis towards the celestial pole above the horizon.
+fn polar_axis(lat: f64) -> Vector {
+    let (sin, cos) = lat.to_radians().sin_cos();
+    if lat >= 0.0 {
+        [0.0, cos, sin]
+    } else {
+        [0.0, -cos, -sin]
+    }
+}
+
+/// Direction to the sun at the given hour angle and declination.
+fn sun_direction(lat: f64, hour_angle: f64, declination: f64) -> Vector {
+    let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+    let (sin_h, cos_h) = hour_angle.sin_cos();
+    let (sin_d, cos_d) = declination.sin_cos();
+    // combination of the meridian point of the celestial equator, the west point and the pole
+    let meridian = [0.0, -sin_lat, cos_lat];
+    let north_pole = [0.0, cos_lat, sin_lat];
+    [
+        -cos_d * sin_h,
+        cos_d * cos_h * meridian[1] + sin_d * north_pole[1],
+        cos_d * cos_h * meridian[2] + sin_d * north_pole[2],
+    ]
+}
+
+fn shadow_direction(lat: f64, normal: Vector, hour_angle: f64) -> Vector {
+    let sun = sun_direction(lat, hour_angle, 0.0);
+    let line = cross(normal, cross(polar_axis(lat), sun));
+    if dot(line, sun) > 0.0 {
+        [-line[0], -line[1], -line[2]]
+    } else {
+        line
+    }
+}
+
+fn hour_angle(hour: f64) -> f64 {
+    (hour - 12.0) * 15f64.to_radians()
+}
+
+/// Whether the sun shines on a face with the given normal at the hour angle on any day of the year.
+fn lit(lat: f64, normal: Vector, hour_angle: f64) -> bool {
+    (-4..=4).any(|i| {
+        let declination = (MAX_DECLINATION_DEG * f64::from(i) / 4.0).to_radians();
+        let sun = sun_direction(lat, hour_angle, declination);
+        sun[2] > 1e-9 && dot(sun, normal) > 1e-9
+    })
+}
+
+fn hours(step_minutes: f64) -> impl Iterator<Item = f64> {
+    assert!(step_minutes > 0.0, "the step must be positive");
+    let step = step_minutes / 60.0;
+    #[allow(clippy::cast_possible_truncation)]
+    let steps = (24.0 / step).floor() as i32;
+    (0..=steps).map(move |i| f64::from(i) * step)
+}
+
+/// Calculates the gnomon geometry of a dial.
+///
+/// * `lat`   - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `plane` - orientation of the dial face.
+#[must_use]
+pub fn gnomon(lat: f64, plane: DialPlane) -> Gnomon {
+    let (normal, right) = plane.frame();
+    let mut axis = polar_axis(lat);
+    if dot(axis, normal) < 0.0 {
+        axis = [-axis[0], -axis[1], -axis[2]];
+    }
+    let along = dot(axis, normal);
+    let substyle = [
+        axis[0] - along * normal[0],
+        axis[1] - along * normal[1],
+        axis[2] - along * normal[2],
+    ];
+    let noon = shadow_direction(lat, normal, 0.0);
+    Gnomon {
+        style_height: along.clamp(-1.0, 1.0).asin().to_degrees(),
+        substyle_angle: dot(substyle, right).atan2(dot(substyle, noon)).to_degrees(),
+    }
+}
+
+/// Calculates the angle of the hour line for the given local apparent solar time.
+///
+/// Returns `None` if the sun cannot shine on the dial face at that hour on any day of the
+/// year, or if the style is parallel to the dial face and all hour lines are parallel.
+///
+/// * `lat`   - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `plane` - orientation of the dial face.
+/// * `hour`  - local apparent solar time in hours.
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::sundial::{hour_line_angle, DialPlane};
+///
+/// // tan(angle) = sin(lat) * tan(hour angle) on a horizontal dial
+/// let angle = hour_line_angle(30.0, DialPlane::Horizontal, 15.0).unwrap();
+/// assert!((angle - 26.57).abs() < 0.01);
+/// ```
+#[must_use]
+pub fn hour_line_angle(lat: f64, plane: DialPlane, hour: f64) -> Option<f64> {
+    let (normal, right) = plane.frame();
+    if dot(polar_axis(lat), normal).abs() < 1e-9 {
+        return None;
+    }
+    let hour_angle = hour_angle(hour);
+    if !lit(lat, normal, hour_angle) {
+        return None;
+    }
+    let noon = shadow_direction(lat, normal, 0.0);
+    let shadow = shadow_direction(lat, normal, hour_angle);
+    Some(dot(shadow, right).atan2(dot(shadow, noon)).to_degrees())
+}
+
+/// Calculates the hour lines of a dial every `step_minutes` over the day, skipping the hours
+/// in which the dial never receives sunlight.
+///
+/// * `lat`          - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `plane`        - orientation of the dial face.
+/// * `step_minutes` - interval between the hour lines in minutes, e.g. `60.0` or `30.0`.
+///
+/// # Panics
+///
+/// Panics if `step_minutes` is not positive.
+#[must_use]
+pub fn hour_lines(lat: f64, plane: DialPlane, step_minutes: f64) -> Vec<HourLine> {
+    hours(step_minutes)
+        .filter_map(|hour| hour_line_angle(lat, plane, hour).map(|angle| HourLine { hour, angle }))
+        .collect()
+}
+
+/// Correction of a sundial reading for one day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Correction {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the day in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Minutes to add to the sundial reading to get the clock time.
+    pub minutes: f64,
+}
+
+/// Creates a table of corrections from local apparent solar time to the mean time of a time
+/// zone, combining the equation of time with the longitude offset from the zone meridian.
+///
+/// * `start_in_ms`         - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the first day in milliseconds.
+/// * `days`                - number of days in the table.
+/// * `lon`                 - [longitude](https://en.wikipedia.org/wiki/Longitude) of the dial in degrees.
+/// * `zone_meridian`       - meridian of the time zone in degrees, e.g. `15.0` for CET.
+#[must_use]
+pub fn correction_table(
+    start_in_ms: i64,
+    days: u32,
+    lon: f64,
+    zone_meridian: f64,
+) -> Vec<Correction> {
+    (0..days)
+        .map(|day| {
+            let unixtime_in_ms = start_in_ms + i64::from(day) * 24 * 60 * 60 * 1_000;
+            let equation_of_time = solar_geometry(unixtime_in_ms, lon).equation_of_time;
+            Correction {
+                unixtime_in_ms,
+                minutes: (zone_meridian - lon) * MINUTES_PER_DEGREE - equation_of_time,
+            }
+        })
+        .collect()
+}
+
+/// A point of an analemmatic dial in meters from its center, `x` towards east and `y` towards north.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DialPoint {
+    /// Distance towards east.
+    pub x: f64,
+    /// Distance towards north.
+    pub y: f64,
+}
+
+/// An hour point on the ellipse of an analemmatic dial.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HourPoint {
+    /// Local apparent solar time in hours.
+    pub hour: f64,
+    /// Position of the hour marker.
+    pub point: DialPoint,
+}
+
+/// A mark of the date scale on the north-south axis of an analemmatic dial.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateMark {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the date in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Declination of the sun at that date in degrees.
+    pub declination: f64,
+    /// Distance of the gnomon position towards north from the center.
+    pub y: f64,
+}
+
+/// Plot-ready layout of an analemmatic sundial.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalemmaticLayout {
+    /// Semi-axis of the ellipse in east-west direction.
+    pub semi_major_axis: f64,
+    /// Semi-axis of the ellipse in north-south direction.
+    pub semi_minor_axis: f64,
+    /// Hour markers on the ellipse.
+    pub hour_points: Vec<HourPoint>,
+    /// Positions at which the vertical gnomon has to stand on the given dates.
+    pub date_scale: Vec<DateMark>,
+}
+
+/// Lays out an analemmatic sundial.
+///
+/// The hour points lie on an ellipse; the vertical gnomon, e.g. a person, stands on the
+/// date scale on the minor axis. Hours in which the sun never shines are skipped.
+///
+/// * `lat`             - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `semi_major_axis` - half the east-west extent of the dial in meters.
+/// * `step_minutes`    - interval between the hour points in minutes.
+/// * `dates`           - [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds of the dates to mark on the date scale.
+///
+/// # Examples
+///
+/// ```rust
+/// use sun::sundial::analemmatic_layout;
+///
+/// // mark the solstices and an equinox on a dial of 3 m width
+/// let dates = [1_363_780_800_000, 1_371_816_000_000, 1_387_627_200_000];
+/// let layout = analemmatic_layout(51.5, 1.5, 60.0, &dates);
+/// assert!((layout.semi_minor_axis - 1.5 * 51.5f64.to_radians().sin()).abs() < 1e-9);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `step_minutes` is not positive.
+#[must_use]
+pub fn analemmatic_layout(
+    lat: f64,
+    semi_major_axis: f64,
+    step_minutes: f64,
+    dates: &[i64],
+) -> AnalemmaticLayout {
+    let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+    let hour_points = hours(step_minutes)
+        .map(|hour| (hour, hour_angle(hour)))
+        .filter(|&(_, hour_angle)| lit(lat, [0.0, 0.0, 1.0], hour_angle))
+        .map(|(hour, hour_angle)| HourPoint {
+            hour,
+            point: DialPoint {
+                x: semi_major_axis * hour_angle.sin(),
+                y: semi_major_axis * sin_lat * hour_angle.cos(),
+            },
+        })
+        .collect();
+    let date_scale = dates
+        .iter()
+        .map(|&unixtime_in_ms| {
+            let declination = solar_geometry(unixtime_in_ms, 0.0).declination;
+            DateMark {
+                unixtime_in_ms,
+                declination: declination.to_degrees(),
+                y: semi_major_axis * declination.tan() * cos_lat,
+            }
+        })
+        .collect();
+    AnalemmaticLayout {
+        semi_major_axis,
+        semi_minor_axis: semi_major_axis * sin_lat.abs(),
+        hour_points,
+        date_scale,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_horizontal_dial() {
+        for hour in [8.0, 10.0, 13.0, 16.5] {
+            let lat: f64 = 48.85;
+            let hour_angle = ((hour - 12.0) * 15.0_f64).to_radians();
+            let expected = (lat.to_radians().sin() * hour_angle.tan())
+                .atan()
+                .to_degrees();
+            let angle = hour_line_angle(lat, DialPlane::Horizontal, hour).unwrap();
+            assert!((angle - expected).abs() < 1e-9);
+        }
+        // the afternoon lies on the east side of the noon line on both hemispheres
+        let north = hour_line_angle(35.0, DialPlane::Horizontal, 15.0).unwrap();
+        let south = hour_line_angle(-35.0, DialPlane::Horizontal, 15.0).unwrap();
+        assert!((north - south).abs() < 1e-9);
+        let gnomon = gnomon(48.85, DialPlane::Horizontal);
+        assert!((gnomon.style_height - 48.85).abs() < 1e-9);
+        assert!(gnomon.substyle_angle.abs() < 1e-9);
+        // the sun never shines at midnight in Paris
+        assert!(hour_line_angle(48.85, DialPlane::Horizontal, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_vertical_dial() {
+        let lat: f64 = 48.85;
+        let south = DialPlane::Vertical { azimuth: 180.0 };
+        let hour_angle = 30f64.to_radians();
+        let expected = (lat.to_radians().cos() * hour_angle.tan())
+            .atan()
+            .to_degrees();
+        let angle = hour_line_angle(lat, south, 14.0).unwrap();
+        assert!((angle - expected).abs() < 1e-9);
+        let gnomon_south = gnomon(lat, south);
+        assert!((gnomon_south.style_height - (90.0 - lat)).abs() < 1e-9);
+        // a dial declining to the west faces the afternoon sun
+        let west = gnomon(lat, DialPlane::Vertical { azimuth: 210.0 });
+        assert!(west.substyle_angle > 0.0);
+        // a west facing wall gets no morning sun
+        assert!(hour_line_angle(lat, DialPlane::Vertical { azimuth: 270.0 }, 9.0).is_none());
+        // a direct south dial is lit only between six in the morning and six in the evening
+        assert_eq!(hour_lines(lat, south, 60.0).len(), 11);
+    }
+
+    #[test]
+    fn test_correction_table() {
+        // 2013-02-11 and 2013-11-03 in Paris, clock on CET
+        let february = correction_table(1_360_584_000_000, 1, 2.35, 15.0)[0];
+        assert!((february.minutes - (12.65 * 4.0 + 14.2)).abs() < 0.5);
+        let table = correction_table(1_383_480_000_000, 3, 2.35, 15.0);
+        assert_eq!(table.len(), 3);
+        assert!((table[0].minutes - (12.65 * 4.0 - 16.4)).abs() < 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "the step must be positive")]
+    fn test_zero_step() {
+        let _ = hour_lines(51.5, DialPlane::Horizontal, 0.0);
+    }
+
+    #[test]
+    fn test_analemmatic_layout() {
+        // 2013-06-21 and 2013-12-21
+        let dates = [1_371_816_000_000, 1_387_627_200_000];
+        let layout = analemmatic_layout(51.5, 2.0, 60.0, &dates);
+        for hour_point in &layout.hour_points {
+            let DialPoint { x, y } = hour_point.point;
+            let on_ellipse =
+                (x / layout.semi_major_axis).powi(2) + (y / layout.semi_minor_axis).powi(2);
+            assert!((on_ellipse - 1.0).abs() < 1e-9);
+        }
+        let noon = layout
+            .hour_points
+            .iter()
+            .find(|p| (p.hour - 12.0).abs() < 1e-9)
+            .unwrap();
+        assert!((noon.point.y - layout.semi_minor_axis).abs() < 1e-9);
+        // the gnomon stands north of the center in summer
+        let [summer, winter] = [layout.date_scale[0], layout.date_scale[1]];
+        assert!((summer.declination - 23.44).abs() < 0.1);
+        assert!(summer.y > 0.0 && (summer.y + winter.y).abs() < 0.01);
+    }
+}
@@ -0,0 +1,318 @@
+//! The [terminator](https://en.wikipedia.org/wiki/Terminator_(solar)), the line between
+//! day and night on the earth, and the areas of night and twilight.
+//!
+//! The lines and areas are given for a depression of the sun below the horizon, `0°`
+//! for the terminator and `6°`, `12°` and `18°` for the end of civil, nautical and
+//! astronomical twilight. Refraction and the size of the sun are ignored. Lines and
+//! polygons are split at the antimeridian, as map libraries and
+//! [GeoJSON](https://datatracker.ietf.org/doc/html/rfc7946#section-3.1.9) expect; the
+//! [`geojson`](crate::geojson) module writes them as features.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::terminator::{night_polygons, subsolar_point, terminator_lines};
+//!
+//! let now = 1_362_484_800_000;
+//! let sun = subsolar_point(now);
+//! println!("the sun stands in the zenith at {:.1}° {:.1}°", sun.lat, sun.lon);
+//! let lines = terminator_lines(now, 0.0, 360);
+//! let night = night_polygons(now, 6.0, 360);
+//! assert!(!lines.is_empty() && !night.is_empty());
+//! ```
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use crate::{
+    declination, ecliptic_longitude, right_ascension, sidereal_time, solar_mean_anomaly, to_days,
+};
+
+/// A point on the earth.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    /// [Latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    pub lat: f64,
+    /// [Longitude](https://en.wikipedia.org/wiki/Longitude) in degrees, in `[-180, 180]`.
+    pub lon: f64,
+}
+
+/// Declination of the sun and longitude of the subsolar point in radians.
+pub(crate) fn subsolar(unixtime_in_ms: i64) -> (f64, f64) {
+    #[allow(clippy::cast_precision_loss)]
+    let days = to_days(unixtime_in_ms as f64);
+    let ecliptic_longitude = ecliptic_longitude(solar_mean_anomaly(days));
+    let right_ascension = right_ascension(ecliptic_longitude, 0.0);
+    let declination = declination(ecliptic_longitude, 0.0);
+    let lon = right_ascension - sidereal_time(days, 0.0);
+    (declination, (lon + PI).rem_euclid(2.0 * PI) - PI)
+}
+
+/// Calculates the point at which the sun stands in the zenith.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[must_use]
+pub fn subsolar_point(unixtime_in_ms: i64) -> GeoPoint {
+    let (lat, lon) = subsolar(unixtime_in_ms);
+    GeoPoint {
+        lat: lat.to_degrees(),
+        lon: lon.to_degrees(),
+    }
+}
+
+/// Points at the angular distance `radius` from `center`, all in radians, with the
+/// longitudes continuous instead of wrapped.
+fn circle(center_lat: f64, center_lon: f64, radius: f64, n_points: usize) -> Vec<(f64, f64)> {
+    (0..=n_points)
+        .map(|i| {
+            #[allow(clippy::cast_precision_loss)]
+            let bearing = 2.0 * PI * i as f64 / n_points as f64;
+            let lat = (center_lat.sin() * radius.cos()
+                + center_lat.cos() * radius.sin() * bearing.cos())
+            .asin();
+            let lon = center_lon
+                + (bearing.sin() * radius.sin() * center_lat.cos())
+                    .atan2(radius.cos() - center_lat.sin() * lat.sin());
+            (lat, lon)
+        })
+        .collect()
+}
+
+/// Splits a closed line at the antimeridian, adding the crossing points to both parts.
+///
+/// `crossing` refines the linearly interpolated latitude of a crossing.
+fn split_line(points: &[(f64, f64)], crossing: &dyn Fn(f64) -> f64) -> Vec<Vec<GeoPoint>> {
+    let wrap = |lon: f64| (lon + PI).rem_euclid(2.0 * PI) - PI;
+    let to_point = |lat: f64, lon: f64| GeoPoint {
+        lat: lat.to_degrees(),
+        lon: lon.to_degrees(),
+    };
+    let mut parts: Vec<Vec<GeoPoint>> = Vec::new();
+    let mut current = Vec::new();
+    for pair in points.windows(2) {
+        let ((lat0, lon0), (lat1, lon1)) = (pair[0], pair[1]);
+        let start = wrap(lon0);
+        let end = start + (lon1 - lon0);
+        if current.is_empty() {
+            current.push(to_point(lat0, start));
+        }
+        if (-PI..=PI).contains(&end) {
+            current.push(to_point(lat1, end));
+        } else {
+            let edge = PI.copysign(end);
+            let lat = crossing(lat0 + (lat1 - lat0) * (edge - start) / (end - start));
+            current.push(to_point(lat, edge));
+            parts.push(current);
+            current = vec![to_point(lat, -edge), to_point(lat1, end - 2.0 * edge)];
+        }
+    }
+    // the line is closed, so the last part continues with the first one
+    if parts.is_empty() {
+        parts.push(current);
+    } else {
+        current.extend(parts[0].iter().skip(1));
+        parts[0] = current;
+    }
+    parts
+}
+
+/// Calculates the lines along which the sun stands `depression_deg` below the horizon,
+/// split at the antimeridian.
+///
+/// * `unixtime`       - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `depression_deg` - depression of the sun below the horizon in degrees.
+/// * `n_points`       - number of points on the whole circle.
+#[must_use]
+pub fn terminator_lines(
+    unixtime_in_ms: i64,
+    depression_deg: f64,
+    n_points: usize,
+) -> Vec<Vec<GeoPoint>> {
+    let (declination, lon) = subsolar(unixtime_in_ms);
+    let radius = FRAC_PI_2 + depression_deg.to_radians();
+    let crossing = |estimate| closest_crossing(declination, lon, radius, estimate);
+    split_line(
+        &circle(declination, lon, radius, n_points.max(3)),
+        &crossing,
+    )
+}
+
+/// Clips a ring of `(lat, lon)` points in radians to the longitudes `[-π, π]`.
+///
+/// `crossing` refines the linearly interpolated latitude of a crossing.
+fn clip_ring(ring: &[(f64, f64)], crossing: &dyn Fn(f64) -> f64) -> Vec<(f64, f64)> {
+    let clip = |ring: &[(f64, f64)], inside: &dyn Fn(f64) -> bool, edge: f64| {
+        let mut clipped = Vec::new();
+        for (i, &(lat1, lon1)) in ring.iter().enumerate() {
+            let (lat0, lon0) = ring[(i + ring.len() - 1) % ring.len()];
+            let crossing = || {
+                let estimate = lat0 + (lat1 - lat0) * (edge - lon0) / (lon1 - lon0);
+                (crossing(estimate), edge)
+            };
+            match (inside(lon0), inside(lon1)) {
+                (true, true) => clipped.push((lat1, lon1)),
+                (true, false) => clipped.push(crossing()),
+                (false, true) => {
+                    clipped.push(crossing());
+                    clipped.push((lat1, lon1));
+                }
+                (false, false) => {}
+            }
+        }
+        clipped
+    };
+    let ring = clip(ring, &|lon| lon >= -PI, -PI);
+    if ring.is_empty() {
+        return ring;
+    }
+    clip(&ring, &|lon| lon <= PI, PI)
+}
+
+/// Calculates the areas in which the sun stands more than `depression_deg` below the
+/// horizon, as closed rings split at the antimeridian.
+///
+/// * `unixtime`       - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `depression_deg` - depression of the sun below the horizon in degrees.
+/// * `n_points`       - number of points on the whole boundary.
+#[must_use]
+pub fn night_polygons(
+    unixtime_in_ms: i64,
+    depression_deg: f64,
+    n_points: usize,
+) -> Vec<Vec<GeoPoint>> {
+    let (declination, subsolar_lon) = subsolar(unixtime_in_ms);
+    // the night is the cap around the antisolar point
+    let (center_lat, center_lon) = (-declination, subsolar_lon + PI);
+    let radius = FRAC_PI_2 - depression_deg.to_radians();
+    if radius <= 0.0 {
+        return Vec::new();
+    }
+    let n_points = n_points.max(3);
+    let to_points = |ring: Vec<(f64, f64)>| -> Vec<GeoPoint> {
+        let mut points: Vec<GeoPoint> = ring
+            .into_iter()
+            .map(|(lat, lon)| GeoPoint {
+                lat: lat.to_degrees(),
+                lon: lon.to_degrees(),
+            })
+            .collect();
+        if let Some(&first) = points.first() {
+            points.push(first);
+        }
+        points
+    };
+    if FRAC_PI_2 - center_lat.abs() < radius {
+        // the cap contains a pole: the boundary crosses every meridian once
+        let pole = FRAC_PI_2.copysign(center_lat);
+        let mut ring: Vec<(f64, f64)> = (0..=n_points)
+            .map(|i| {
+                #[allow(clippy::cast_precision_loss)]
+                let lon = -PI + 2.0 * PI * i as f64 / n_points as f64;
+                (boundary_lat(center_lat, center_lon, radius, lon), lon)
+            })
+            .collect();
+        ring.push((pole, PI));
+        ring.push((pole, -PI));
+        vec![to_points(ring)]
+    } else {
+        let mut ring = circle(center_lat, center_lon, radius, n_points);
+        ring.pop();
+        [-2.0 * PI, 0.0, 2.0 * PI]
+            .iter()
+            .map(|shift| {
+                let shifted: Vec<(f64, f64)> =
+                    ring.iter().map(|&(lat, lon)| (lat, lon + shift)).collect();
+                clip_ring(&shifted, &|estimate| {
+                    closest_crossing(center_lat, center_lon, radius, estimate)
+                })
+            })
+            .filter(|ring| ring.len() >= 3)
+            .map(to_points)
+            .collect()
+    }
+}
+
+/// Latitudes in radians at which the meridian `lon` crosses the circle with the angular
+/// `radius` around the center, one of which may lie outside `[-π/2, π/2]`.
+fn meridian_crossings(center_lat: f64, center_lon: f64, radius: f64, lon: f64) -> [f64; 2] {
+    // cos(radius) = sin(lat) sin(c) + cos(lat) cos(c) cos(lon - c) = r sin(lat + phase)
+    let a = center_lat.sin();
+    let b = center_lat.cos() * (lon - center_lon).cos();
+    let r = a.hypot(b);
+    let phase = b.atan2(a);
+    let angle = (radius.cos() / r).clamp(-1.0, 1.0).asin();
+    let wrap = |lat: f64| (lat + PI).rem_euclid(2.0 * PI) - PI;
+    [wrap(angle - phase), wrap(PI - angle - phase)]
+}
+
+/// Latitude in radians at which the meridian `lon` crosses the boundary of a cap that
+/// contains a pole.
+fn boundary_lat(center_lat: f64, center_lon: f64, radius: f64, lon: f64) -> f64 {
+    let [first, second] = meridian_crossings(center_lat, center_lon, radius, lon);
+    if (-FRAC_PI_2..=FRAC_PI_2).contains(&first) {
+        first
+    } else {
+        second
+    }
+}
+
+/// Latitude in radians closest to `estimate` at which the antimeridian crosses the circle.
+fn closest_crossing(center_lat: f64, center_lon: f64, radius: f64, estimate: f64) -> f64 {
+    let [first, second] = meridian_crossings(center_lat, center_lon, radius, PI);
+    if (first - estimate).abs() <= (second - estimate).abs() {
+        first
+    } else {
+        second
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn altitude_deg(unixtime_in_ms: i64, point: GeoPoint) -> f64 {
+        crate::pos(unixtime_in_ms, point.lat, point.lon)
+            .altitude
+            .to_degrees()
+    }
+
+    #[test]
+    fn test_subsolar_point() {
+        // 2013-03-05 12:00 UTC
+        let noon = 1_362_484_800_000;
+        let sun = subsolar_point(noon);
+        assert!((altitude_deg(noon, sun) - 90.0).abs() < 0.01);
+        assert!(sun.lat < -5.0 && sun.lat > -7.0);
+        assert!(sun.lon.abs() < 5.0);
+    }
+
+    #[test]
+    fn test_lines_and_polygons() {
+        for (time, depression) in [
+            (1_362_484_800_000, 0.0),
+            (1_371_816_000_000, 12.0),
+            (1_371_772_800_000, 6.0),
+        ] {
+            let lines = terminator_lines(time, depression, 180);
+            for point in lines.iter().flatten() {
+                assert!((altitude_deg(time, *point) + depression).abs() < 0.01);
+                assert!((-180.0..=180.0).contains(&point.lon));
+            }
+            let polygons = night_polygons(time, depression, 180);
+            assert!(!polygons.is_empty());
+            for polygon in &polygons {
+                assert_eq!(polygon.first(), polygon.last());
+                for point in polygon
+                    .iter()
+                    .filter(|point| point.lat.abs() < 89.9 && point.lon.abs() < 179.9)
+                {
+                    assert!((altitude_deg(time, *point) + depression).abs() < 0.01);
+                }
+            }
+        }
+        // the terminator crosses the antimeridian twice at noon UTC
+        assert_eq!(terminator_lines(1_362_484_800_000, 0.0, 180).len(), 2);
+        // the areas of astronomical night don't reach the antimeridian at midnight UTC
+        assert_eq!(night_polygons(1_362_441_600_000, 18.0, 180).len(), 1);
+    }
+}
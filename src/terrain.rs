@@ -0,0 +1,444 @@
+//! Sunrise and sunset behind the local terrain, e.g. in valleys and mountain towns where
+//! the sun appears long after the astronomical sunrise.
+//!
+//! The terrain is given by a [`HorizonProvider`], which returns the altitude of the
+//! visible horizon in any direction. [`TerrainHorizon`] derives it from a digital
+//! elevation model behind an [`ElevationSampler`] by marching along rays from the
+//! observer, and [`HorizonProfile`] caches the horizon of any provider.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::terrain::{terrain_sun_times, ElevationSampler, HorizonProfile, TerrainHorizon};
+//!
+//! /// A ridge of 1000 m east of the observer.
+//! struct Ridge;
+//!
+//! impl ElevationSampler for Ridge {
+//!     fn elevation(&self, _lat: f64, lon: f64) -> Option<f64> {
+//!         Some(if lon > 9.05 { 1_000.0 } else { 0.0 })
+//!     }
+//! }
+//!
+//! let horizon = TerrainHorizon::new(&Ridge, 48.0, 9.0, 2.0);
+//! // ray-march once per degree instead of for every step of the search
+//! let profile = HorizonProfile::new(&horizon, 360);
+//! let times = terrain_sun_times(1_362_441_600_000, 48.0, 9.0, &profile);
+//! println!("the sun reaches the house at {:?}", times.sunrise);
+//! ```
+//!
+//! [`summit_shadow_passages`] tells when the shadow of a single peak sweeps over an
+//! observer, like the shadow of the Matterhorn over the valley of Zermatt.
+
+use std::f64::consts::{PI, TAU};
+
+use crate::{observed_pos, Position, MILLISECONDS_PER_DAY_INT};
+
+const STEP_MS: i64 = 2 * 60 * 1_000;
+const EARTH_RADIUS_IN_M: f64 = 6_371_000.0;
+/// Refraction coefficient of the line of sight over the terrain.
+const TERRESTRIAL_REFRACTION: f64 = 0.13;
+/// Apparent radius of the sun in radians, the upper limb is the first light to appear.
+const SUN_RADIUS: f64 = 0.266 * PI / 180.0;
+
+/// Elevations of the terrain, e.g. backed by a digital elevation model.
+pub trait ElevationSampler {
+    /// Elevation in meters above sea level at the location in degrees, `None` outside
+    /// of the covered area.
+    fn elevation(&self, lat: f64, lon: f64) -> Option<f64>;
+}
+
+/// Altitude of the visible horizon as seen by an observer.
+pub trait HorizonProvider {
+    /// Altitude of the horizon in radians in the direction `azimuth` in radians
+    /// clockwise from north.
+    fn horizon_altitude(&self, azimuth: f64) -> f64;
+}
+
+impl<F: Fn(f64) -> f64> HorizonProvider for F {
+    fn horizon_altitude(&self, azimuth: f64) -> f64 {
+        self(azimuth)
+    }
+}
+
+/// Altitude in radians at which a point `height_difference` meters above the eye and
+/// `distance` meters away is seen, lowered by the curvature of the earth.
+fn sight_altitude(height_difference: f64, distance: f64) -> f64 {
+    let drop = distance * distance * (1.0 - TERRESTRIAL_REFRACTION) / (2.0 * EARTH_RADIUS_IN_M);
+    ((height_difference - drop) / distance).atan()
+}
+
+/// Horizon of an observer derived from the terrain by marching along rays.
+#[derive(Debug, Clone, Copy)]
+pub struct TerrainHorizon<'a, S> {
+    sampler: &'a S,
+    lat: f64,
+    lon: f64,
+    eye_elevation: f64,
+    max_distance: f64,
+    step: f64,
+}
+
+impl<'a, S: ElevationSampler> TerrainHorizon<'a, S> {
+    /// Creates the horizon of an observer `height` meters above the terrain, searching
+    /// the terrain up to 50 km away in steps of 50 m.
+    ///
+    /// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    #[must_use]
+    pub fn new(sampler: &'a S, lat: f64, lon: f64, height: f64) -> Self {
+        Self {
+            sampler,
+            lat,
+            lon,
+            eye_elevation: sampler.elevation(lat, lon).unwrap_or(0.0) + height,
+            max_distance: 50_000.0,
+            step: 50.0,
+        }
+    }
+
+    /// Sets the distance in meters up to which the terrain is searched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `meters` is not positive and finite.
+    #[must_use]
+    pub const fn max_distance(mut self, meters: f64) -> Self {
+        assert!(
+            meters > 0.0 && meters < f64::INFINITY,
+            "the distance must be positive and finite"
+        );
+        self.max_distance = meters;
+        self
+    }
+
+    /// Sets the distance in meters between the samples along a ray, which should match
+    /// the resolution of the elevation model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `meters` is not positive.
+    #[must_use]
+    pub const fn step(mut self, meters: f64) -> Self {
+        assert!(meters > 0.0, "the step must be positive");
+        self.step = meters;
+        self
+    }
+}
+
+impl<S: ElevationSampler> HorizonProvider for TerrainHorizon<'_, S> {
+    fn horizon_altitude(&self, azimuth: f64) -> f64 {
+        let lat = self.lat.to_radians();
+        let lon = self.lon.to_radians();
+        let mut horizon = -PI / 2.0;
+        let mut distance = self.step;
+        while distance <= self.max_distance {
+            let angular = distance / EARTH_RADIUS_IN_M;
+            let target_lat =
+                (lat.sin() * angular.cos() + lat.cos() * angular.sin() * azimuth.cos()).asin();
+            let target_lon = lon
+                + (azimuth.sin() * angular.sin() * lat.cos())
+                    .atan2(angular.cos() - lat.sin() * target_lat.sin());
+            if let Some(elevation) = self
+                .sampler
+                .elevation(target_lat.to_degrees(), target_lon.to_degrees())
+            {
+                horizon = horizon.max(sight_altitude(elevation - self.eye_elevation, distance));
+            }
+            distance += self.step;
+        }
+        horizon
+    }
+}
+
+/// Horizon of a [`HorizonProvider`] sampled at evenly spaced azimuths and interpolated
+/// linearly in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HorizonProfile {
+    altitudes: Vec<f64>,
+}
+
+impl HorizonProfile {
+    /// Samples `provider` at `samples` azimuths starting at north.
+    #[must_use]
+    pub fn new(provider: &impl HorizonProvider, samples: usize) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let altitudes = (0..samples.max(1))
+            .map(|i| provider.horizon_altitude(TAU * i as f64 / samples.max(1) as f64))
+            .collect();
+        Self { altitudes }
+    }
+
+    /// Creates a profile from altitudes in radians at evenly spaced azimuths starting at
+    /// north, e.g. measured with an inclinometer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `altitudes` is empty.
+    #[must_use]
+    pub fn from_altitudes(altitudes: Vec<f64>) -> Self {
+        assert!(
+            !altitudes.is_empty(),
+            "a profile needs at least one altitude"
+        );
+        Self { altitudes }
+    }
+}
+
+impl HorizonProvider for HorizonProfile {
+    fn horizon_altitude(&self, azimuth: f64) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let position = azimuth.rem_euclid(TAU) / TAU * self.altitudes.len() as f64;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let index = position.floor() as usize % self.altitudes.len();
+        let next = (index + 1) % self.altitudes.len();
+        let fraction = position.fract();
+        self.altitudes[index] * (1.0 - fraction) + self.altitudes[next] * fraction
+    }
+}
+
+/// Times at which the sun is visible above the terrain during a day, see
+/// [`terrain_sun_times`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerrainSunTimes {
+    /// First appearance of the sun above the terrain, `None` if it doesn't appear.
+    pub sunrise: Option<i64>,
+    /// Last disappearance of the sun behind the terrain, `None` if it doesn't disappear.
+    pub sunset: Option<i64>,
+    /// Periods of direct sunlight as pairs of unix times in milliseconds, clipped to
+    /// the day. A peak in front of the sun splits the day into several periods.
+    pub periods: Vec<(i64, i64)>,
+}
+
+/// Calculates when the upper limb of the sun is visible above the terrain during the UTC
+/// day of `unixtime_in_ms`.
+///
+/// The day is searched in steps of two minutes and the times are refined to a second, so
+/// gaps in the terrain that the sun crosses in less than two minutes may be missed.
+///
+/// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `horizon`   - horizon of the observer.
+#[must_use]
+pub fn terrain_sun_times(
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    horizon: &impl HorizonProvider,
+) -> TerrainSunTimes {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let end = start + MILLISECONDS_PER_DAY_INT;
+    let visible = |time: i64| {
+        let sun = observed_pos(time, lat, lon);
+        sun.apparent_altitude + SUN_RADIUS > horizon.horizon_altitude(sun.azimuth)
+    };
+    let refine = |mut before: i64, mut after: i64, was_visible: bool| {
+        while after - before > 1_000 {
+            let middle = before + (after - before) / 2;
+            if visible(middle) == was_visible {
+                before = middle;
+            } else {
+                after = middle;
+            }
+        }
+        after
+    };
+
+    let mut periods = Vec::new();
+    let mut was_visible = visible(start);
+    let mut open = was_visible.then_some(start);
+    let mut time = start;
+    while time < end {
+        let next = (time + STEP_MS).min(end);
+        let is_visible = visible(next);
+        if is_visible != was_visible {
+            let crossing = refine(time, next, was_visible);
+            match open.take() {
+                Some(from) => periods.push((from, crossing)),
+                None => open = Some(crossing),
+            }
+        }
+        was_visible = is_visible;
+        time = next;
+    }
+    if let Some(from) = open {
+        periods.push((from, end));
+    }
+
+    TerrainSunTimes {
+        sunrise: periods
+            .first()
+            .map(|&(from, _)| from)
+            .filter(|&from| from > start),
+        sunset: periods.last().map(|&(_, to)| to).filter(|&to| to < end),
+        periods,
+    }
+}
+
+/// A point on the terrain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Site {
+    /// [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    pub lat: f64,
+    /// [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    pub lon: f64,
+    /// Elevation in meters above sea level.
+    pub elevation: f64,
+}
+
+impl Site {
+    /// Direction in which `target` is seen from this site, with the azimuth in radians
+    /// clockwise from north and the altitude in radians lowered by the curvature of the
+    /// earth.
+    #[must_use]
+    pub fn direction_to(&self, target: Self) -> Position {
+        let (lat1, lat2) = (self.lat.to_radians(), target.lat.to_radians());
+        let delta_lon = (target.lon - self.lon).to_radians();
+        let azimuth = (delta_lon.sin() * lat2.cos())
+            .atan2(lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_lon.cos())
+            .rem_euclid(TAU);
+        let haversine = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * (delta_lon / 2.0).sin().powi(2);
+        let distance = 2.0 * EARTH_RADIUS_IN_M * haversine.sqrt().asin();
+        Position {
+            azimuth,
+            altitude: sight_altitude(target.elevation - self.elevation, distance),
+        }
+    }
+}
+
+/// Finds the times within the UTC day of `unixtime_in_ms` at which the shadow of the
+/// summit of `peak` sweeps over `observer`.
+///
+/// That happens when the sun passes behind the summit as seen from the observer, i.e. it
+/// has the azimuth of the summit and is above the horizon but below the summit. The
+/// times are refined to a second. Whether the observer stays in the shadow of the whole
+/// mountain before or after depends on the shape of its flanks, which
+/// [`terrain_sun_times`] takes into account.
+#[must_use]
+pub fn summit_shadow_passages(unixtime_in_ms: i64, peak: Site, observer: Site) -> Vec<i64> {
+    let start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+    let summit = observer.direction_to(peak);
+    // azimuth of the sun relative to the summit in (-π, π]
+    let offset = |time: i64| {
+        let sun = observed_pos(time, observer.lat, observer.lon);
+        PI - (summit.azimuth - sun.azimuth + PI).rem_euclid(TAU)
+    };
+    let mut passages = Vec::new();
+    let mut time = start;
+    while time < start + MILLISECONDS_PER_DAY_INT {
+        let (mut before, mut after) = (time, time + STEP_MS);
+        let (first, second) = (offset(before), offset(after));
+        // a change of sign away from the opposite direction
+        if first.signum() != second.signum() && (first - second).abs() < PI {
+            while after - before > 1_000 {
+                let middle = before + (after - before) / 2;
+                if offset(middle).signum() == first.signum() {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+            let sun = observed_pos(after, observer.lat, observer.lon);
+            if sun.apparent_altitude + SUN_RADIUS > 0.0 && sun.apparent_altitude < summit.altitude {
+                passages.push(after);
+            }
+        }
+        time += STEP_MS;
+    }
+    passages
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{time_at_phase, SunPhase};
+
+    struct Ridge;
+
+    impl ElevationSampler for Ridge {
+        fn elevation(&self, _lat: f64, lon: f64) -> Option<f64> {
+            Some(if lon > 9.05 { 1_000.0 } else { 0.0 })
+        }
+    }
+
+    #[test]
+    fn test_terrain_horizon() {
+        let horizon = TerrainHorizon::new(&Ridge, 48.0, 9.0, 0.0);
+        // the ridge starts 3.7 km to the east
+        let east = horizon.horizon_altitude(PI / 2.0).to_degrees();
+        assert!((east - (1_000.0_f64 / 3_730.0).atan().to_degrees()).abs() < 0.3);
+        // only the curvature of the earth lowers the horizon to the west
+        let west = horizon.horizon_altitude(3.0 * PI / 2.0);
+        assert!(west < 0.0 && west > -0.01);
+        let profile = HorizonProfile::new(&horizon, 360);
+        assert!(
+            (profile.horizon_altitude(PI / 2.0) - horizon.horizon_altitude(PI / 2.0)).abs() < 1e-9
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the step must be positive")]
+    fn test_zero_step() {
+        let _ = TerrainHorizon::new(&Ridge, 48.0, 9.0, 0.0).step(0.0);
+    }
+
+    #[test]
+    fn test_summit_shadow_passages() {
+        let observer = Site {
+            lat: 46.0,
+            lon: 7.6,
+            elevation: 1_600.0,
+        };
+        // a summit of 4478 m 10 km to the east
+        let peak = Site {
+            lat: 46.0,
+            lon: 7.6 + (10_000.0 / (EARTH_RADIUS_IN_M * 46_f64.to_radians().cos())).to_degrees(),
+            elevation: 4_478.0,
+        };
+        let summit = observer.direction_to(peak);
+        assert!((summit.azimuth.to_degrees() - 90.0).abs() < 0.1);
+        assert!(
+            (summit.altitude.to_degrees() - (2_878.0_f64 / 10_000.0).atan().to_degrees()).abs()
+                < 0.1
+        );
+        // 2013-04-10 UTC, the sun passes the east at an altitude of 10° in the morning
+        let passages = summit_shadow_passages(1_365_552_000_000, peak, observer);
+        assert_eq!(passages.len(), 1);
+        let sun = crate::pos(passages[0], observer.lat, observer.lon);
+        assert!((sun.azimuth - summit.azimuth).abs() < 1e-3);
+        assert!(sun.altitude < summit.altitude);
+        // 2013-06-21 UTC, the sun is already high when it stands in the east
+        assert!(summit_shadow_passages(1_371_772_800_000, peak, observer).is_empty());
+    }
+
+    #[test]
+    fn test_terrain_sun_times() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let flat = terrain_sun_times(date, 48.0, 9.0, &|_| 0.0);
+        let sunrise = time_at_phase(date, SunPhase::Sunrise, 48.0, 9.0, 0.0);
+        let sunset = time_at_phase(date, SunPhase::Sunset, 48.0, 9.0, 0.0);
+        assert!((flat.sunrise.unwrap() - sunrise).abs() < 60_000);
+        assert!((flat.sunset.unwrap() - sunset).abs() < 60_000);
+        assert_eq!(flat.periods.len(), 1);
+
+        let profile = HorizonProfile::new(&TerrainHorizon::new(&Ridge, 48.0, 9.0, 2.0), 360);
+        let valley = terrain_sun_times(date, 48.0, 9.0, &profile);
+        // the sun needs more than an hour to climb above the ridge
+        assert!(valley.sunrise.unwrap() - sunrise > 60 * 60_000);
+        assert!((valley.sunset.unwrap() - sunset).abs() < 60_000);
+        // polar night
+        let polar = terrain_sun_times(1_387_584_000_000, 78.2, 15.6, &|_| 0.0);
+        assert_eq!(
+            polar,
+            TerrainSunTimes {
+                sunrise: None,
+                sunset: None,
+                periods: Vec::new()
+            }
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Switch times of a light/dark scheme following the sun, e.g. for desktop or app theming.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::theme::{Theme, ThemeSchedule};
+//!
+//! let schedule = ThemeSchedule::default();
+//! let now = 1_362_441_600_000;
+//! if schedule.current(now, 48.0, 9.0) == Theme::Dark {
+//!     println!("switching to dark mode");
+//! }
+//! for switch in schedule.switches(now, 48.0, 9.0) {
+//!     println!("{:?} at {}", switch.theme, switch.unixtime_in_ms);
+//! }
+//! ```
+
+use crate::report::event;
+use crate::{local_noon, pos, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+/// A color scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    /// The light scheme for the day.
+    Light,
+    /// The dark scheme for the night.
+    Dark,
+}
+
+/// A change of the color scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Switch {
+    /// [unix time](https://en.wikipedia.org/wiki/Unix_time) of the switch in milliseconds.
+    pub unixtime_in_ms: i64,
+    /// Scheme to switch to.
+    pub theme: Theme,
+}
+
+/// Sun phases at which the scheme switches.
+#[derive(Debug, Clone, Copy)]
+pub struct ThemeSchedule {
+    /// Phase in the morning at which the light scheme starts.
+    pub light_phase: SunPhase,
+    /// Phase in the evening at which the dark scheme starts.
+    pub dark_phase: SunPhase,
+}
+
+impl Default for ThemeSchedule {
+    /// Switches at civil dawn and civil dusk.
+    fn default() -> Self {
+        Self::new(SunPhase::Dawn, SunPhase::Dusk)
+    }
+}
+
+impl ThemeSchedule {
+    /// Creates a schedule switching to light at `light_phase` and to dark at `dark_phase`.
+    #[must_use]
+    pub const fn new(light_phase: SunPhase, dark_phase: SunPhase) -> Self {
+        Self {
+            light_phase,
+            dark_phase,
+        }
+    }
+
+    fn switches_of_day(&self, day_start_in_ms: i64, lat: f64, lon: f64) -> [Option<Switch>; 2] {
+        let noon = local_noon(day_start_in_ms, lon);
+        let switch = |phase, theme| {
+            event(noon, phase, lat, lon).map(|unixtime_in_ms| Switch {
+                unixtime_in_ms,
+                theme,
+            })
+        };
+        [
+            switch(self.light_phase, Theme::Light),
+            switch(self.dark_phase, Theme::Dark),
+        ]
+    }
+
+    /// Calculates the switches of the UTC day of `unixtime_in_ms` and of the following day
+    /// in chronological order.
+    ///
+    /// Days of polar day or polar night have no switches.
+    #[must_use]
+    pub fn switches(&self, unixtime_in_ms: i64, lat: f64, lon: f64) -> Vec<Switch> {
+        let today = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+        let mut switches: Vec<Switch> = [today, today + MILLISECONDS_PER_DAY_INT]
+            .iter()
+            .flat_map(|&day| self.switches_of_day(day, lat, lon))
+            .flatten()
+            .collect();
+        switches.sort_by_key(|switch| switch.unixtime_in_ms);
+        switches
+    }
+
+    /// Returns the scheme in effect at `unixtime_in_ms`.
+    ///
+    /// Without a switch in the preceding day, e.g. in polar night, the light scheme is in
+    /// effect while the sun stands above the altitude of the light phase.
+    #[must_use]
+    pub fn current(&self, unixtime_in_ms: i64, lat: f64, lon: f64) -> Theme {
+        let today = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+        [
+            today - MILLISECONDS_PER_DAY_INT,
+            today,
+            today + MILLISECONDS_PER_DAY_INT,
+        ]
+        .iter()
+        .flat_map(|&day| self.switches_of_day(day, lat, lon))
+        .flatten()
+        .filter(|switch| {
+            switch.unixtime_in_ms <= unixtime_in_ms
+                && unixtime_in_ms - switch.unixtime_in_ms < MILLISECONDS_PER_DAY_INT
+        })
+        .max_by_key(|switch| switch.unixtime_in_ms)
+        .map_or_else(
+            || {
+                let altitude = pos(unixtime_in_ms, lat, lon).altitude.to_degrees();
+                if altitude > self.light_phase.angle_deg() {
+                    Theme::Light
+                } else {
+                    Theme::Dark
+                }
+            },
+            |switch| switch.theme,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_switches() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let schedule = ThemeSchedule::default();
+        let switches = schedule.switches(date, 50.5, 30.5);
+        assert_eq!(switches.len(), 4);
+        assert_eq!(switches[0].theme, Theme::Light);
+        assert_eq!(
+            switches[0].unixtime_in_ms,
+            crate::time_at_phase(date + 12 * 60 * 60 * 1_000, SunPhase::Dawn, 50.5, 30.5, 0.0)
+        );
+        assert_eq!(switches[1].theme, Theme::Dark);
+        assert!(switches[2].unixtime_in_ms - switches[0].unixtime_in_ms > 23 * 60 * 60 * 1_000);
+        // a custom schedule switches later in the morning
+        let golden = ThemeSchedule::new(SunPhase::GoldenHourEnd, SunPhase::GoldenHour);
+        assert!(golden.switches(date, 50.5, 30.5)[0].unixtime_in_ms > switches[0].unixtime_in_ms);
+    }
+
+    #[test]
+    fn test_current() {
+        let date = 1_362_441_600_000;
+        let schedule = ThemeSchedule::default();
+        let switches = schedule.switches(date, 50.5, 30.5);
+        assert_eq!(schedule.current(date, 50.5, 30.5), Theme::Dark);
+        let light = switches[0].unixtime_in_ms;
+        assert_eq!(schedule.current(light - 1, 50.5, 30.5), Theme::Dark);
+        assert_eq!(schedule.current(light, 50.5, 30.5), Theme::Light);
+        // polar night and polar day in Longyearbyen
+        assert_eq!(schedule.current(1_387_627_200_000, 78.2, 15.6), Theme::Dark);
+        assert!(schedule.switches(1_387_627_200_000, 78.2, 15.6).is_empty());
+        assert_eq!(
+            schedule.current(1_371_772_800_000, 78.2, 15.6),
+            Theme::Light
+        );
+    }
+}
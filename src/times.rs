@@ -0,0 +1,258 @@
+//! The times of all sun phases of a day and a lazy stream of them over consecutive days.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::times::days;
+//!
+//! // the next week in Stuttgart
+//! for times in days(1_362_441_600_000, 48.8, 9.2).take(7) {
+//!     println!("{:?} - {:?}", times.sunrise, times.sunset);
+//! }
+//! ```
+
+use std::iter::FusedIterator;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{from_julian, local_noon, SunPhase, Transit, MILLISECONDS_PER_DAY_INT};
+
+/// The phases of [`SunTimes`], those of suncalc in their order over the day followed by
+/// the blue hour, so that the indices of [`EventKey`](crate::cursor::EventKey) stay stable.
+pub(crate) const PHASES: [SunPhase; 14] = [
+    SunPhase::NightEnd,
+    SunPhase::NauticalDawn,
+    SunPhase::Dawn,
+    SunPhase::Sunrise,
+    SunPhase::SunriseEnd,
+    SunPhase::GoldenHourEnd,
+    SunPhase::GoldenHour,
+    SunPhase::SunsetStart,
+    SunPhase::Sunset,
+    SunPhase::Dusk,
+    SunPhase::NauticalDusk,
+    SunPhase::Night,
+    SunPhase::BlueHourEnd(SunPhase::BLUE_HOUR_DEG),
+    SunPhase::BlueHourStart(SunPhase::BLUE_HOUR_DEG),
+];
+
+/// Times of the sun phases of a UTC day, like `getTimes` of suncalc.
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds,
+/// `None` if the sun doesn't reach the altitude of the phase on that day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SunTimes {
+    /// Start of the UTC day.
+    pub unixtime_in_ms: i64,
+    /// End of the astronomical night.
+    pub night_end: Option<i64>,
+    /// Nautical dawn.
+    pub nautical_dawn: Option<i64>,
+    /// Civil dawn.
+    pub dawn: Option<i64>,
+    /// End of the morning blue hour.
+    pub blue_hour_end: Option<i64>,
+    /// Sunrise.
+    pub sunrise: Option<i64>,
+    /// End of sunrise.
+    pub sunrise_end: Option<i64>,
+    /// End of the morning golden hour.
+    pub golden_hour_end: Option<i64>,
+    /// Transit of the sun through the meridian.
+    pub solar_noon: i64,
+    /// Start of the evening golden hour.
+    pub golden_hour: Option<i64>,
+    /// Start of sunset.
+    pub sunset_start: Option<i64>,
+    /// Sunset.
+    pub sunset: Option<i64>,
+    /// Start of the evening blue hour.
+    pub blue_hour_start: Option<i64>,
+    /// Civil dusk.
+    pub dusk: Option<i64>,
+    /// Nautical dusk.
+    pub nautical_dusk: Option<i64>,
+    /// Start of the astronomical night.
+    pub night: Option<i64>,
+    /// Lowest position of the sun, half a day before the solar noon.
+    pub nadir: i64,
+}
+
+impl SunTimes {
+    /// Calculates the times of the UTC day of `unixtime_in_ms`.
+    ///
+    /// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    /// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    #[must_use]
+    pub fn new(unixtime_in_ms: i64, lat: f64, lon: f64) -> Self {
+        Self::at_height(unixtime_in_ms, lat, lon, 0.0)
+    }
+
+    /// Calculates the times like [`SunTimes::new`] for an observer `height` meters above
+    /// the horizon.
+    pub(crate) fn at_height(unixtime_in_ms: i64, lat: f64, lon: f64, height: f64) -> Self {
+        let day_start = unixtime_in_ms - unixtime_in_ms.rem_euclid(MILLISECONDS_PER_DAY_INT);
+        Self::from_transit(
+            day_start,
+            &Transit::new(local_noon(day_start, lon), lon),
+            lat,
+            height,
+        )
+    }
+
+    /// Calculates the times of the day of `transit` for an observer `height` meters above
+    /// the horizon, computing the orbit once for all phases.
+    pub(crate) fn from_transit(day_start: i64, transit: &Transit, lat: f64, height: f64) -> Self {
+        let time = |phase| transit.phase_outcome(phase, lat, height).time();
+        let solar_noon = from_julian(transit.julian_noon);
+        Self {
+            unixtime_in_ms: day_start,
+            night_end: time(SunPhase::NightEnd),
+            nautical_dawn: time(SunPhase::NauticalDawn),
+            dawn: time(SunPhase::Dawn),
+            blue_hour_end: time(SunPhase::BlueHourEnd(SunPhase::BLUE_HOUR_DEG)),
+            sunrise: time(SunPhase::Sunrise),
+            sunrise_end: time(SunPhase::SunriseEnd),
+            golden_hour_end: time(SunPhase::GoldenHourEnd),
+            solar_noon,
+            golden_hour: time(SunPhase::GoldenHour),
+            sunset_start: time(SunPhase::SunsetStart),
+            sunset: time(SunPhase::Sunset),
+            blue_hour_start: time(SunPhase::BlueHourStart(SunPhase::BLUE_HOUR_DEG)),
+            dusk: time(SunPhase::Dusk),
+            nautical_dusk: time(SunPhase::NauticalDusk),
+            night: time(SunPhase::Night),
+            nadir: solar_noon - MILLISECONDS_PER_DAY_INT / 2,
+        }
+    }
+
+    /// Times of the [`PHASES`] in their order.
+    pub(crate) const fn phase_times(&self) -> [Option<i64>; 14] {
+        [
+            self.night_end,
+            self.nautical_dawn,
+            self.dawn,
+            self.sunrise,
+            self.sunrise_end,
+            self.golden_hour_end,
+            self.golden_hour,
+            self.sunset_start,
+            self.sunset,
+            self.dusk,
+            self.nautical_dusk,
+            self.night,
+            self.blue_hour_end,
+            self.blue_hour_start,
+        ]
+    }
+}
+
+/// Iterator over the [`SunTimes`] of consecutive days, see [`days`].
+#[derive(Debug, Clone)]
+pub struct Days {
+    next_day_in_ms: i64,
+    lat: f64,
+    lon: f64,
+}
+
+impl Iterator for Days {
+    type Item = SunTimes;
+
+    fn next(&mut self) -> Option<SunTimes> {
+        let times = SunTimes::new(self.next_day_in_ms, self.lat, self.lon);
+        self.next_day_in_ms += MILLISECONDS_PER_DAY_INT;
+        Some(times)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for Days {}
+
+/// Returns an endless iterator over the [`SunTimes`] of the days starting with the UTC
+/// day of `start_in_ms`.
+///
+/// The days are computed lazily without allocation; use [`Iterator::take`] or
+/// [`Iterator::take_while`] to bound the stream.
+///
+/// * `start_in_ms` - [unix time](https://en.wikipedia.org/wiki/Unix_time) of the first day in milliseconds.
+/// * `lat`         - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`         - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+#[must_use]
+pub const fn days(start_in_ms: i64, lat: f64, lon: f64) -> Days {
+    Days {
+        next_day_in_ms: start_in_ms,
+        lat,
+        lon,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_sun_times() {
+        // 2013-03-05 UTC
+        let date = 1_362_441_600_000;
+        let times = SunTimes::new(date + 5 * 60 * 60 * 1_000, 50.5, 30.5);
+        assert_eq!(times.unixtime_in_ms, date);
+        let noon = local_noon(date, 30.5);
+        assert_eq!(
+            times.sunrise,
+            Some(crate::time_at_phase(
+                noon,
+                SunPhase::Sunrise,
+                50.5,
+                30.5,
+                0.0
+            ))
+        );
+        assert_eq!(times.solar_noon, crate::solar_noon(noon, 30.5));
+        let ordered = [
+            times.night_end,
+            times.nautical_dawn,
+            times.dawn,
+            times.sunrise,
+            times.sunrise_end,
+            times.golden_hour_end,
+            Some(times.solar_noon),
+            times.golden_hour,
+            times.sunset_start,
+            times.sunset,
+            times.dusk,
+            times.nautical_dusk,
+            times.night,
+        ];
+        assert!(ordered
+            .windows(2)
+            .all(|pair| pair[0].unwrap() < pair[1].unwrap()));
+        // polar night in Longyearbyen
+        let polar = SunTimes::new(1_387_584_000_000, 78.2, 15.6);
+        assert_eq!(polar.sunrise, None);
+        assert!(polar.night_end.is_some());
+    }
+
+    #[test]
+    fn test_days() {
+        let date = 1_362_441_600_000;
+        let week: Vec<SunTimes> = days(date, 50.5, 30.5).take(7).collect();
+        assert_eq!(week.len(), 7);
+        assert_eq!(week[6].unixtime_in_ms, date + 6 * MILLISECONDS_PER_DAY_INT);
+        assert_eq!(
+            week[3],
+            SunTimes::new(date + 3 * MILLISECONDS_PER_DAY_INT, 50.5, 30.5)
+        );
+        // days get longer in March
+        assert!(
+            week[6].sunset.unwrap() - week[6].sunrise.unwrap()
+                > week[0].sunset.unwrap() - week[0].sunrise.unwrap()
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! [Transits](https://en.wikipedia.org/wiki/Astronomical_transit) of Mercury and Venus
+//! across the disc of the sun.
+//!
+//! The contacts are geocentric and derived from the simplified ephemerides of the
+//! [`planets`](crate::planets) module, so they are accurate to a few minutes. The
+//! parallax shifts the contacts for an observer by up to seven minutes for Venus and
+//! two minutes for Mercury.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sun::planets::Planet;
+//! use sun::transits::transits;
+//!
+//! // 2019 to 2020 from Berlin
+//! for transit in transits(Planet::Mercury, 1_546_300_800_000, 1_609_459_200_000, 52.5, 13.4) {
+//!     println!(
+//!         "transit from {} to {}, visible: {}",
+//!         transit.first_contact, transit.fourth_contact, transit.visible
+//!     );
+//! }
+//! ```
+
+use crate::planets::{geocentric, Planet};
+use crate::{pos, ASTRONOMICAL_UNIT_IN_KM, MILLISECONDS_PER_DAY_INT};
+
+const VISIBILITY_STEP_MS: i64 = 10 * 60 * 1_000;
+const SUN_RADIUS_KM: f64 = 696_000.0;
+
+/// A transit of a planet across the disc of the sun, see [`transits`].
+///
+/// All times are [unix times](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanetTransit {
+    /// The transiting planet.
+    pub planet: Planet,
+    /// First contact, the disc of the planet touches the disc of the sun from outside.
+    pub first_contact: i64,
+    /// Second contact, the disc of the planet is completely inside the disc of the sun,
+    /// `None` for a grazing transit.
+    pub second_contact: Option<i64>,
+    /// Greatest transit, the planet is closest to the center of the sun.
+    pub greatest: i64,
+    /// Third contact, the disc of the planet starts to leave the disc of the sun, `None`
+    /// for a grazing transit.
+    pub third_contact: Option<i64>,
+    /// Fourth contact, the end of the transit.
+    pub fourth_contact: i64,
+    /// Distance between the centers of the planet and the sun at the greatest transit in
+    /// degrees.
+    pub min_separation_deg: f64,
+    /// Whether the sun is above the horizon of the observer during part of the transit.
+    pub visible: bool,
+}
+
+impl Planet {
+    /// Radius in km, `None` for the planets that can't transit the sun.
+    const fn radius_km(self) -> Option<f64> {
+        match self {
+            Planet::Mercury => Some(2_439.7),
+            Planet::Venus => Some(6_051.8),
+            Planet::Mars | Planet::Jupiter => None,
+        }
+    }
+}
+
+/// Geocentric separation of the centers, angular radius of the sun and of the planet in
+/// radians, and whether the planet is closer than the sun.
+fn geometry(planet: Planet, radius_km: f64, unixtime_in_ms: i64) -> (f64, f64, f64, bool) {
+    let (body, sun) = geocentric(planet, unixtime_in_ms);
+    let norm = |v: [f64; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    let (body_distance, sun_distance) = (norm(body), norm(sun));
+    let dot: f64 = body.iter().zip(&sun).map(|(a, b)| a * b).sum();
+    let separation = (dot / (body_distance * sun_distance))
+        .clamp(-1.0, 1.0)
+        .acos();
+    (
+        separation,
+        (SUN_RADIUS_KM / (sun_distance * ASTRONOMICAL_UNIT_IN_KM)).asin(),
+        (radius_km / (body_distance * ASTRONOMICAL_UNIT_IN_KM)).asin(),
+        body_distance < sun_distance,
+    )
+}
+
+/// Narrows a sign change of `f` in `[low, high]` down to a second by bisection.
+fn bisect(mut low: i64, mut high: i64, f: impl Fn(i64) -> f64) -> i64 {
+    let negative = f(low) < 0.0;
+    while high - low > 1_000 {
+        let middle = (low + high) / 2;
+        if (f(middle) < 0.0) == negative {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2
+}
+
+/// Narrows a minimum of `f` in `[low, high]` down to a second by ternary search.
+fn minimize(mut low: i64, mut high: i64, f: impl Fn(i64) -> f64) -> i64 {
+    while high - low > 1_000 {
+        let third = (high - low) / 3;
+        if f(low + third) < f(high - third) {
+            high -= third;
+        } else {
+            low += third;
+        }
+    }
+    (low + high) / 2
+}
+
+/// Finds the transits of `planet` across the sun between `start_in_ms` and `end_in_ms`.
+///
+/// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) of the observer in degrees.
+/// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) of the observer in degrees.
+///
+/// Only Mercury and Venus pass between the earth and the sun; for other planets the
+/// result is empty.
+#[must_use]
+pub fn transits(
+    planet: Planet,
+    start_in_ms: i64,
+    end_in_ms: i64,
+    lat: f64,
+    lon: f64,
+) -> Vec<PlanetTransit> {
+    let Some(radius_km) = planet.radius_km() else {
+        return Vec::new();
+    };
+    let separation = |time: i64| geometry(planet, radius_km, time).0;
+    let samples: Vec<(i64, f64)> = (start_in_ms..=end_in_ms)
+        .step_by(usize::try_from(MILLISECONDS_PER_DAY_INT).unwrap_or(usize::MAX))
+        .map(|time| (time, separation(time)))
+        .collect();
+
+    let mut transits = Vec::new();
+    for window in samples.windows(3) {
+        let [(_, before), (time, value), (_, after)] = [window[0], window[1], window[2]];
+        if value > before || value >= after {
+            continue;
+        }
+        let greatest = minimize(
+            time - MILLISECONDS_PER_DAY_INT,
+            time + MILLISECONDS_PER_DAY_INT,
+            separation,
+        );
+        let (min_separation, sun_radius, planet_radius, inferior) =
+            geometry(planet, radius_km, greatest);
+        if !inferior || min_separation >= sun_radius + planet_radius {
+            continue;
+        }
+        let contact = |limit: f64, ingress: bool| {
+            let offset = |time: i64| separation(time) - limit;
+            if ingress {
+                bisect(greatest - MILLISECONDS_PER_DAY_INT / 2, greatest, offset)
+            } else {
+                bisect(greatest, greatest + MILLISECONDS_PER_DAY_INT / 2, offset)
+            }
+        };
+        let inner =
+            (min_separation < sun_radius - planet_radius).then_some(sun_radius - planet_radius);
+        let first_contact = contact(sun_radius + planet_radius, true);
+        let fourth_contact = contact(sun_radius + planet_radius, false);
+        let visible = (first_contact..=fourth_contact)
+            .step_by(usize::try_from(VISIBILITY_STEP_MS).unwrap_or(usize::MAX))
+            .chain([fourth_contact])
+            .any(|time| pos(time, lat, lon).altitude > 0.0);
+        transits.push(PlanetTransit {
+            planet,
+            first_contact,
+            second_contact: inner.map(|limit| contact(limit, true)),
+            greatest,
+            third_contact: inner.map(|limit| contact(limit, false)),
+            fourth_contact,
+            min_separation_deg: min_separation.to_degrees(),
+            visible,
+        });
+    }
+    transits
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const MINUTE: i64 = 60 * 1_000;
+
+    #[test]
+    fn test_venus_2012() {
+        // 2012-01-01 to 2013-01-01 UTC
+        let found = transits(
+            Planet::Venus,
+            1_325_376_000_000,
+            1_356_998_400_000,
+            52.5,
+            13.4,
+        );
+        assert_eq!(found.len(), 1);
+        let transit = found[0];
+        // 2012-06-05 22:09:38, 06-06 01:29:36 and 04:49:35 UTC
+        assert!(
+            (transit.first_contact - 1_338_934_178_000).abs() < 15 * MINUTE,
+            "{transit:?}"
+        );
+        assert!(
+            (transit.greatest - 1_338_946_176_000).abs() < 15 * MINUTE,
+            "{transit:?}"
+        );
+        assert!(
+            (transit.fourth_contact - 1_338_958_175_000).abs() < 15 * MINUTE,
+            "{transit:?}"
+        );
+        assert!(transit.second_contact.unwrap() > transit.first_contact);
+        assert!(transit.third_contact.unwrap() < transit.fourth_contact);
+        // the end was visible at sunrise in Berlin, but not in Buenos Aires
+        assert!(transit.visible);
+        let buenos_aires = transits(
+            Planet::Venus,
+            1_325_376_000_000,
+            1_356_998_400_000,
+            -34.6,
+            -58.4,
+        );
+        assert!(!buenos_aires[0].visible);
+        // no transit of Venus until 2117
+        assert!(transits(
+            Planet::Venus,
+            1_356_998_400_000,
+            1_609_459_200_000,
+            0.0,
+            0.0
+        )
+        .is_empty());
+        assert!(transits(Planet::Mars, 1_325_376_000_000, 1_356_998_400_000, 0.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_mercury() {
+        // 2016-01-01 to 2020-01-01 UTC
+        let found = transits(
+            Planet::Mercury,
+            1_451_606_400_000,
+            1_577_836_800_000,
+            52.5,
+            13.4,
+        );
+        assert_eq!(found.len(), 2, "{found:?}");
+        // greatest transits 2016-05-09 14:57 and 2019-11-11 15:20 UTC
+        assert!(
+            (found[0].greatest - 1_462_805_820_000).abs() < 15 * MINUTE,
+            "{found:?}"
+        );
+        assert!(
+            (found[1].greatest - 1_573_485_600_000).abs() < 15 * MINUTE,
+            "{found:?}"
+        );
+        assert!(found.iter().all(|transit| transit.visible));
+    }
+}
@@ -0,0 +1,280 @@
+//! A [ratatui](https://ratatui.rs) widget showing the path of the sun over the day, its
+//! current position and the upcoming sun events, e.g. for terminal dashboards.
+//!
+//! # Example
+//!
+//! ```rust
+//! use ratatui::buffer::Buffer;
+//! use ratatui::layout::Rect;
+//! use ratatui::widgets::{Block, Widget};
+//! use sun::tui::SunWidget;
+//!
+//! let area = Rect::new(0, 0, 60, 14);
+//! let mut buffer = Buffer::empty(area);
+//! SunWidget::new(1_362_484_800_000, 48.8, 9.2)
+//!     .utc_offset(60)
+//!     .block(Block::bordered().title("Stuttgart"))
+//!     .render(area, &mut buffer);
+//! ```
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Widget};
+
+use crate::cursor::EventCursor;
+use crate::{pos, SunPhase, MILLISECONDS_PER_DAY_INT};
+
+const MILLISECONDS_PER_MINUTE: i64 = 60 * 1_000;
+
+const HORIZON: &str = "─";
+const ARC_ABOVE: &str = "•";
+const ARC_BELOW: &str = "·";
+const MARKER: &str = "☀";
+
+/// Name of a phase in the list of events.
+const fn label(phase: SunPhase) -> &'static str {
+    match phase {
+        SunPhase::Sunrise => "sunrise",
+        SunPhase::Sunset => "sunset",
+        SunPhase::SunriseEnd => "sunrise end",
+        SunPhase::SunsetStart => "sunset start",
+        SunPhase::Dawn => "dawn",
+        SunPhase::Dusk => "dusk",
+        SunPhase::NauticalDawn => "nautical dawn",
+        SunPhase::NauticalDusk => "nautical dusk",
+        SunPhase::NightEnd => "night end",
+        SunPhase::Night => "night",
+        SunPhase::GoldenHourEnd => "golden hour end",
+        SunPhase::GoldenHour => "golden hour",
+        SunPhase::BlueHourEnd(_) => "blue hour end",
+        SunPhase::BlueHourStart(_) => "blue hour",
+        SunPhase::Custom(..) => "custom",
+    }
+}
+
+/// Widget drawing the altitude of the sun over the local day as an arc above and below
+/// the horizon, a marker at the current position and the next events below the arc.
+///
+/// The columns span the local day from midnight to midnight, the rows the range of
+/// altitudes the sun reaches on that day.
+#[derive(Debug, Clone)]
+pub struct SunWidget<'a> {
+    unixtime_in_ms: i64,
+    lat: f64,
+    lon: f64,
+    utc_offset_minutes: i32,
+    events: usize,
+    block: Option<Block<'a>>,
+    arc_style: Style,
+    marker_style: Style,
+}
+
+impl<'a> SunWidget<'a> {
+    /// Creates a widget showing the sun at the given time and location in UTC with the
+    /// next three events.
+    ///
+    /// * `unixtime`  - [unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds.
+    /// * `lat`       - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+    /// * `lon`       - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+    #[must_use]
+    pub const fn new(unixtime_in_ms: i64, lat: f64, lon: f64) -> Self {
+        Self {
+            unixtime_in_ms,
+            lat,
+            lon,
+            utc_offset_minutes: 0,
+            events: 3,
+            block: None,
+            arc_style: Style::new().fg(Color::Yellow),
+            marker_style: Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Sets the offset of the local time from UTC, which determines the local day and
+    /// the times of the events.
+    #[must_use]
+    pub const fn utc_offset(mut self, minutes: i32) -> Self {
+        self.utc_offset_minutes = minutes;
+        self
+    }
+
+    /// Sets the number of upcoming events listed below the arc.
+    #[must_use]
+    pub const fn events(mut self, events: usize) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Wraps the widget in a block.
+    #[must_use]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Sets the style of the arc.
+    #[must_use]
+    pub const fn arc_style(mut self, style: Style) -> Self {
+        self.arc_style = style;
+        self
+    }
+
+    /// Sets the style of the marker at the current position.
+    #[must_use]
+    pub const fn marker_style(mut self, style: Style) -> Self {
+        self.marker_style = style;
+        self
+    }
+
+    fn offset_in_ms(&self) -> i64 {
+        i64::from(self.utc_offset_minutes) * MILLISECONDS_PER_MINUTE
+    }
+
+    /// Draws the arc, the horizon and the marker.
+    fn render_arc(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let day_start = self.unixtime_in_ms
+            - (self.unixtime_in_ms + self.offset_in_ms()).rem_euclid(MILLISECONDS_PER_DAY_INT);
+        let time_at = |column: f64| {
+            #[allow(clippy::cast_possible_truncation)]
+            let offset = (column / f64::from(area.width) * 86_400_000.0) as i64;
+            day_start + offset
+        };
+        let altitudes: Vec<f64> = (0..area.width)
+            .map(|x| {
+                let time = time_at(f64::from(x) + 0.5);
+                pos(time, self.lat, self.lon).altitude.to_degrees()
+            })
+            .collect();
+        let top = altitudes.iter().copied().fold(0.0, f64::max);
+        let bottom = altitudes.iter().copied().fold(0.0, f64::min);
+        let row_of = |altitude: f64| {
+            let fraction = if top > bottom {
+                (top - altitude) / (top - bottom)
+            } else {
+                0.0
+            };
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let row = (fraction * f64::from(area.height - 1)).round() as u16;
+            area.y + row.min(area.height - 1)
+        };
+        let horizon = row_of(0.0);
+        for x in area.left()..area.right() {
+            buf[(x, horizon)]
+                .set_symbol(HORIZON)
+                .set_style(Style::new().add_modifier(Modifier::DIM));
+        }
+        for (x, &altitude) in (area.left()..).zip(&altitudes) {
+            let symbol = if altitude >= 0.0 {
+                ARC_ABOVE
+            } else {
+                ARC_BELOW
+            };
+            buf[(x, row_of(altitude))]
+                .set_symbol(symbol)
+                .set_style(self.arc_style);
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let elapsed = (self.unixtime_in_ms - day_start) as f64 / 86_400_000.0;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let column = (elapsed * f64::from(area.width)) as u16;
+        let altitude = pos(self.unixtime_in_ms, self.lat, self.lon)
+            .altitude
+            .to_degrees();
+        buf[(area.x + column.min(area.width - 1), row_of(altitude))]
+            .set_symbol(MARKER)
+            .set_style(self.marker_style);
+    }
+
+    /// Lists the next events with their local time.
+    fn render_events(&self, area: Rect, buf: &mut Buffer) {
+        let events = EventCursor::new(self.unixtime_in_ms, self.lat, self.lon);
+        for (y, event) in (area.top()..area.bottom()).zip(events) {
+            let local =
+                (event.unixtime_in_ms + self.offset_in_ms()).rem_euclid(MILLISECONDS_PER_DAY_INT);
+            let text = format!(
+                "{:02}:{:02} {}",
+                local / 3_600_000,
+                local / 60_000 % 60,
+                label(event.phase)
+            );
+            buf.set_stringn(area.x, y, text, usize::from(area.width), Style::new());
+        }
+    }
+}
+
+impl Widget for SunWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let inner = match &self.block {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.clone().render(area, buf);
+                inner
+            }
+            None => area,
+        };
+        // keep at least three rows for the arc
+        let events = u16::try_from(self.events)
+            .unwrap_or(u16::MAX)
+            .min(inner.height.saturating_sub(3));
+        let arc = Rect {
+            height: inner.height - events,
+            ..inner
+        };
+        let list = Rect {
+            y: arc.bottom(),
+            height: events,
+            ..inner
+        };
+        self.render_arc(arc, buf);
+        self.render_events(list, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width)
+            .map(|x| buf[(x, y)].symbol().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let area = Rect::new(0, 0, 48, 12);
+        let mut buf = Buffer::empty(area);
+        // 2013-03-05 12:00 UTC in Stuttgart
+        SunWidget::new(1_362_484_800_000, 48.8, 9.2)
+            .utc_offset(60)
+            .render(area, &mut buf);
+        let rows: Vec<String> = (0..12).map(|y| row(&buf, y)).collect();
+        // the marker stands at 13:00 local time, right of the middle
+        let marker = rows.iter().position(|row| row.contains(MARKER)).unwrap();
+        assert_eq!(marker, 0);
+        assert_eq!(rows[0].chars().position(|c| c == '☀'), Some(26));
+        assert!(rows.iter().any(|row| row.contains(HORIZON)));
+        assert!(rows[9].starts_with("17:31 golden hour"));
+        assert!(rows[10].starts_with("18:11 sunset start"));
+        assert!(rows[11].starts_with("18:13 sunset "));
+    }
+
+    #[test]
+    fn test_small_area() {
+        let area = Rect::new(0, 0, 10, 5);
+        let mut buf = Buffer::empty(area);
+        SunWidget::new(1_362_441_600_000, 48.8, 9.2)
+            .events(10)
+            .block(Block::bordered())
+            .render(area, &mut buf);
+        // the border leaves three rows for the arc and none for the events
+        assert_eq!(row(&buf, 0), "┌────────┐");
+        assert!((1..4).any(|y| row(&buf, y).contains(MARKER)));
+        assert_eq!(label(SunPhase::NauticalDusk), "nautical dusk");
+    }
+}
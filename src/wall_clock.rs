@@ -0,0 +1,130 @@
+//! The position of the sun at the same local clock time on every day of a year, e.g. to
+//! see whether the sun reaches a balcony at 17:00 in summer as well as in winter.
+//!
+//! The clock time is converted with the time zone of the location, so the series follows
+//! the jumps of daylight saving time like a wall clock does.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chrono::NaiveTime;
+//! use sun::wall_clock::wall_clock_series;
+//!
+//! let five_pm = NaiveTime::from_hms_opt(17, 0, 0).unwrap();
+//! let series = wall_clock_series(2024, five_pm, 52.5, 13.4, chrono_tz::Europe::Berlin);
+//! let sunny_days = series
+//!     .iter()
+//!     .filter(|entry| entry.position.altitude > 0.0)
+//!     .count();
+//! println!("the sun is up at 17:00 on {sunny_days} days");
+//! ```
+
+use chrono::{NaiveDate, NaiveTime, Offset, TimeDelta, TimeZone};
+use chrono_tz::Tz;
+
+use crate::{pos, Position};
+
+/// Position of the sun at a local clock time, see [`wall_clock_series`].
+#[derive(Debug, Clone, Copy)]
+pub struct WallClockPosition {
+    /// Local date.
+    pub date: NaiveDate,
+    /// [Unix time](https://en.wikipedia.org/wiki/Unix_time) in milliseconds of the clock
+    /// time on that date.
+    pub unixtime_in_ms: i64,
+    /// UTC offset in seconds used for the conversion.
+    pub utc_offset_s: i32,
+    /// Position of the sun.
+    pub position: Position,
+}
+
+/// Converts a local date and time into a unix time and the UTC offset.
+///
+/// A time that is skipped when the clocks are set forward is read with the offset before
+/// the jump, e.g. 02:30 becomes 03:30. A time that occurs twice when the clocks are set
+/// back is taken at its first occurrence.
+fn to_unixtime(tz: Tz, date: NaiveDate, time: NaiveTime) -> (i64, i32) {
+    let local = date.and_time(time);
+    let offset = tz
+        .from_local_datetime(&local)
+        .earliest()
+        .map_or_else(
+            || tz.offset_from_utc_datetime(&(local - TimeDelta::days(1))),
+            |time| *time.offset(),
+        )
+        .fix()
+        .local_minus_utc();
+    let unixtime_in_ms = local.and_utc().timestamp_millis() - i64::from(offset) * 1_000;
+    (unixtime_in_ms, offset)
+}
+
+/// Calculates the sun position at the local clock `time` on every day of `year`.
+///
+/// * `year`  - year of the series.
+/// * `time`  - local clock time.
+/// * `lat`   - [latitude](https://en.wikipedia.org/wiki/Latitude) in degrees.
+/// * `lon`   - [longitude](https://en.wikipedia.org/wiki/Longitude) in degrees.
+/// * `tz`    - [IANA time zone](https://www.iana.org/time-zones) of the location.
+#[must_use]
+pub fn wall_clock_series(
+    year: i32,
+    time: NaiveTime,
+    lat: f64,
+    lon: f64,
+    tz: Tz,
+) -> Vec<WallClockPosition> {
+    let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+        return Vec::new();
+    };
+    first
+        .iter_days()
+        .take_while(|date| date.years_since(first) == Some(0))
+        .map(|date| {
+            let (unixtime_in_ms, utc_offset_s) = to_unixtime(tz, date, time);
+            WallClockPosition {
+                date,
+                unixtime_in_ms,
+                utc_offset_s,
+                position: pos(unixtime_in_ms, lat, lon),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_series() {
+        let time = NaiveTime::from_hms_opt(17, 0, 0).unwrap();
+        let series = wall_clock_series(2024, time, 52.5, 13.4, chrono_tz::Europe::Berlin);
+        assert_eq!(series.len(), 366);
+        // 2024-01-01 16:00 UTC and 2024-07-01 15:00 UTC
+        assert_eq!(series[0].unixtime_in_ms, 1_704_124_800_000);
+        assert_eq!(
+            series[182].date,
+            NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()
+        );
+        assert_eq!(series[182].unixtime_in_ms, 1_719_846_000_000);
+        assert_eq!(series[182].utc_offset_s, 7_200);
+        let expected = pos(1_719_846_000_000, 52.5, 13.4);
+        assert!((series[182].position.altitude - expected.altitude).abs() < 1e-12);
+        // the sun has set at 17:00 in midwinter, but not in midsummer
+        assert!(series[0].position.altitude < 0.0);
+        assert!(series[182].position.altitude > 0.0);
+    }
+
+    #[test]
+    fn test_gap_and_overlap() {
+        let tz = chrono_tz::Europe::Berlin;
+        let time = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        // skipped on 2024-03-31, read as 03:30 CEST = 01:30 UTC
+        let spring = to_unixtime(tz, NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(), time);
+        assert_eq!(spring, (1_711_848_600_000, 3_600));
+        // twice on 2024-10-27, first at 00:30 UTC
+        let autumn = to_unixtime(tz, NaiveDate::from_ymd_opt(2024, 10, 27).unwrap(), time);
+        assert_eq!(autumn, (1_729_989_000_000, 7_200));
+    }
+}
@@ -0,0 +1,250 @@
+//! WebAssembly bindings with the API of [suncalc](https://github.com/mourner/suncalc),
+//! so a web bundle can replace the JavaScript library without changing its call sites.
+//!
+//! The functions have suncalc's names, argument order and return shapes, e.g.
+//! `getPosition(date, lat, lng)` returns `{azimuth, altitude}` with the azimuth measured
+//! from south to west like in suncalc, not from north like in the rest of this crate.
+//! Times are returned as `Date` objects, which are invalid if an event doesn't occur.
+//!
+//! The package is built with `wasm-pack build` in this directory.
+//!
+//! ```js
+//! import * as SunCalc from "sun";
+//!
+//! const times = SunCalc.getTimes(new Date(), 51.5, -0.1);
+//! SunCalc.addTime(-4, "blueHourEnd", "blueHour");
+//! const { azimuth, altitude } = SunCalc.getPosition(times.sunrise, 51.5, -0.1);
+//! ```
+
+use std::cell::RefCell;
+use std::f64::consts::PI;
+
+use js_sys::{Date, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use sun::moon::{moon_illumination, moon_pos, moon_times_after};
+use sun::{pos, solar_noon, try_time_at_phase, SunPhase};
+
+/// The sun times of suncalc as `(angle, rise name, set name)`.
+const DEFAULT_TIMES: [(f64, &str, &str); 6] = [
+    (-0.833, "sunrise", "sunset"),
+    (-0.3, "sunriseEnd", "sunsetStart"),
+    (-6.0, "dawn", "dusk"),
+    (-12.0, "nauticalDawn", "nauticalDusk"),
+    (-18.0, "nightEnd", "night"),
+    (6.0, "goldenHourEnd", "goldenHour"),
+];
+
+thread_local! {
+    /// The times returned by `getTimes`, extended by `addTime`.
+    static TIMES: RefCell<Vec<(f64, String, String)>> = RefCell::new(
+        DEFAULT_TIMES
+            .iter()
+            .map(|&(angle, rise, set)| (angle, rise.to_string(), set.to_string()))
+            .collect(),
+    );
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn unixtime(date: &Date) -> i64 {
+    date.get_time() as i64
+}
+
+fn object(fields: &[(&str, JsValue)]) -> Object {
+    let object = Object::new();
+    for (key, value) in fields {
+        // setting a property of a plain object can't fail
+        let _ = Reflect::set(&object, &JsValue::from_str(key), value);
+    }
+    object
+}
+
+fn date(unixtime_in_ms: f64) -> JsValue {
+    Date::new(&JsValue::from_f64(unixtime_in_ms)).into()
+}
+
+/// Azimuth from south to west and altitude of the sun in radians.
+fn position(unixtime_in_ms: i64, lat: f64, lng: f64) -> (f64, f64) {
+    let position = pos(unixtime_in_ms, lat, lng);
+    (position.azimuth - PI, position.altitude)
+}
+
+/// The times of `getTimes` as unix times in milliseconds, `NaN` if an event doesn't occur.
+#[allow(clippy::cast_precision_loss)]
+fn times(unixtime_in_ms: i64, lat: f64, lng: f64, height: f64) -> Vec<(String, f64)> {
+    let noon = solar_noon(unixtime_in_ms, lng);
+    let mut times = vec![
+        ("solarNoon".to_string(), noon as f64),
+        ("nadir".to_string(), (noon - 12 * 60 * 60 * 1_000) as f64),
+    ];
+    TIMES.with(|custom| {
+        for (angle, rise, set) in custom.borrow().iter() {
+            let time = |is_rise| {
+                let phase = SunPhase::Custom(*angle, is_rise);
+                try_time_at_phase(unixtime_in_ms, phase, lat, lng, height)
+                    .time()
+                    .map_or(f64::NAN, |time| time as f64)
+            };
+            times.push((rise.clone(), time(true)));
+            times.push((set.clone(), time(false)));
+        }
+    });
+    times
+}
+
+/// Calculates the position of the sun like `SunCalc.getPosition`.
+#[wasm_bindgen(js_name = getPosition)]
+#[must_use]
+pub fn get_position(date: &Date, lat: f64, lng: f64) -> Object {
+    let (azimuth, altitude) = position(unixtime(date), lat, lng);
+    object(&[("azimuth", azimuth.into()), ("altitude", altitude.into())])
+}
+
+/// Calculates the sun times like `SunCalc.getTimes`, including the times added with
+/// [`add_time`].
+#[wasm_bindgen(js_name = getTimes)]
+#[must_use]
+pub fn get_times(date: &Date, lat: f64, lng: f64, height: Option<f64>) -> Object {
+    let times = times(unixtime(date), lat, lng, height.unwrap_or(0.0));
+    let fields: Vec<(&str, JsValue)> = times
+        .iter()
+        .map(|(name, time)| (name.as_str(), self::date(*time)))
+        .collect();
+    object(&fields)
+}
+
+/// Adds a pair of times at the altitude `angle` in degrees to the results of
+/// [`get_times`], like `SunCalc.addTime`.
+#[wasm_bindgen(js_name = addTime)]
+pub fn add_time(angle: f64, rise_name: String, set_name: String) {
+    TIMES.with(|times| times.borrow_mut().push((angle, rise_name, set_name)));
+}
+
+/// Calculates the position of the moon like `SunCalc.getMoonPosition`, with the
+/// distance in kilometers.
+#[wasm_bindgen(js_name = getMoonPosition)]
+#[must_use]
+pub fn get_moon_position(date: &Date, lat: f64, lng: f64) -> Object {
+    let moon = moon_pos(unixtime(date), lat, lng);
+    object(&[
+        ("azimuth", (moon.azimuth - PI).into()),
+        ("altitude", moon.altitude.into()),
+        ("distance", moon.distance.into()),
+        ("parallacticAngle", moon.parallactic_angle.into()),
+    ])
+}
+
+/// Calculates the illumination of the moon like `SunCalc.getMoonIllumination`.
+#[wasm_bindgen(js_name = getMoonIllumination)]
+#[must_use]
+pub fn get_moon_illumination(date: &Date) -> Object {
+    let illumination = moon_illumination(unixtime(date));
+    object(&[
+        ("fraction", illumination.fraction.into()),
+        ("phase", illumination.phase.into()),
+        ("angle", illumination.angle.into()),
+    ])
+}
+
+/// Calculates the rise and set of the moon within the local day of `date`, or the UTC
+/// day if `in_utc` is `true`, like `SunCalc.getMoonTimes`.
+///
+/// Like in suncalc, `rise` and `set` are missing if the moon doesn't rise or set, and
+/// `alwaysUp` or `alwaysDown` is `true` if it does neither.
+#[wasm_bindgen(js_name = getMoonTimes)]
+#[must_use]
+pub fn get_moon_times(date: &Date, lat: f64, lng: f64, in_utc: Option<bool>) -> Object {
+    let start = Date::new(date);
+    if in_utc.unwrap_or(false) {
+        start.set_utc_hours(0);
+        start.set_utc_minutes(0);
+        start.set_utc_seconds(0);
+        start.set_utc_milliseconds(0);
+    } else {
+        start.set_hours(0);
+        start.set_minutes(0);
+        start.set_seconds(0);
+        start.set_milliseconds(0);
+    }
+    let times = moon_times_after(unixtime(&start), lat, lng);
+    let mut fields = Vec::new();
+    #[allow(clippy::cast_precision_loss)]
+    let to_date = |time: i64| self::date(time as f64);
+    if let Some(rise) = times.rise {
+        fields.push(("rise", to_date(rise)));
+    }
+    if let Some(set) = times.set {
+        fields.push(("set", to_date(set)));
+    }
+    if times.always_up {
+        fields.push(("alwaysUp", true.into()));
+    }
+    if times.always_down {
+        fields.push(("alwaysDown", true.into()));
+    }
+    object(&fields)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        // suncalc's test: azimuth -2.5003175907168385, altitude -0.7000406838781611
+        let (azimuth, altitude) = position(1_362_441_600_000, 50.5, 30.5);
+        let tolerance = if cfg!(feature = "fast-math") {
+            sun::fast_math::ASIN_MAX_ERROR
+        } else {
+            1e-10
+        };
+        assert!((azimuth - -2.500_317_590_716_838_5).abs() < tolerance);
+        assert!((altitude - -0.700_040_683_878_161_1).abs() < tolerance);
+    }
+
+    #[test]
+    fn test_times() {
+        // suncalc's test for 2013-03-05 at 50.5, 30.5
+        let times = times(1_362_441_600_000, 50.5, 30.5, 0.0);
+        let expected = [
+            ("solarNoon", "10:10:57"),
+            ("nadir", "-01:49:03"),
+            ("sunrise", "04:34:56"),
+            ("sunset", "15:46:57"),
+            ("sunriseEnd", "04:38:19"),
+            ("sunsetStart", "15:43:34"),
+            ("dawn", "04:02:17"),
+            ("dusk", "16:19:36"),
+            ("nauticalDawn", "03:24:31"),
+            ("nauticalDusk", "16:57:22"),
+            ("nightEnd", "02:46:17"),
+            ("night", "17:35:36"),
+            ("goldenHourEnd", "05:19:01"),
+            ("goldenHour", "15:02:52"),
+        ];
+        assert_eq!(times.len(), expected.len());
+        for ((name, time), (expected_name, expected_time)) in times.iter().zip(expected) {
+            assert_eq!(name, expected_name);
+            let sign = if expected_time.starts_with('-') {
+                -1.0
+            } else {
+                1.0
+            };
+            let seconds: f64 = expected_time
+                .trim_start_matches('-')
+                .split(':')
+                .map(|part| part.parse::<f64>().unwrap())
+                .fold(0.0, |total, part| total * 60.0 + part);
+            let expected = 1_362_441_600.0 + sign * seconds;
+            assert!((time / 1_000.0 - expected).abs() < 1.0, "{name}");
+        }
+        add_time(-4.0, "blueHourEnd".to_string(), "blueHour".to_string());
+        let extended = super::times(1_362_441_600_000, 50.5, 30.5, 0.0);
+        assert_eq!(extended.len(), 16);
+        assert_eq!(extended[15].0, "blueHour");
+        // polar night in Longyearbyen
+        let polar = super::times(1_387_584_000_000, 78.2, 15.6, 0.0);
+        assert!(polar[2].1.is_nan());
+    }
+}